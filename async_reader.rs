@@ -0,0 +1,122 @@
+use std::io::ErrorKind;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    feed::{FeedParser, Status},
+    parser::{ParserOptions, ParseError, ObjectValue}
+};
+
+
+const READ_SIZE: usize = 8 * 1024;
+
+// reads chunks into a feed parser until a whole document is there
+pub struct AsyncParser<R>
+{
+    reader: R,
+    feeder: FeedParser
+}
+
+impl<R: AsyncRead + Unpin> AsyncParser<R>
+{
+    pub fn new(reader: R) -> Self
+    {
+        Self{reader, feeder: FeedParser::new()}
+    }
+
+    pub fn options(mut self, options: ParserOptions) -> Self
+    {
+        self.feeder = self.feeder.options(options);
+
+        self
+    }
+
+    // stops reading as soon as the first document is complete
+    pub async fn parse(mut self) -> Result<ObjectValue, ParseError>
+    {
+        let mut buffer = vec![0; READ_SIZE];
+
+        loop
+        {
+            let amount = match self.reader.read(&mut buffer).await
+            {
+                Ok(x) => x,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(self.feeder.read_error(&err))
+            };
+
+            if amount == 0
+            {
+                return self.feeder.finish();
+            }
+
+            if let Status::Done(value) = self.feeder.feed(&buffer[..amount])?
+            {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll}
+    };
+
+    use tokio::io::ReadBuf;
+
+    use crate::parser::ParseErrorKind;
+
+    use super::*;
+
+
+    // gives out its bytes one at a time and then fails
+    struct Failing(&'static [u8]);
+
+    impl AsyncRead for Failing
+    {
+        fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context, buffer: &mut ReadBuf) -> Poll<io::Result<()>>
+        {
+            match self.0.split_first()
+            {
+                Some((&c, rest)) =>
+                {
+                    buffer.put_slice(&[c]);
+                    self.0 = rest;
+
+                    Poll::Ready(Ok(()))
+                },
+                None => Poll::Ready(Err(io::Error::other("disconnected")))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_after_the_first_document()
+    {
+        let value = AsyncParser::new(&b"{\"a\": [1, 2]} [3]"[..]).parse().await.unwrap();
+
+        assert_eq!(value.pointer("/a/1"), Some(&ObjectValue::from(2)));
+    }
+
+    #[tokio::test]
+    async fn truncated()
+    {
+        let err = AsyncParser::new(&b"[1, 2"[..]).parse().await.unwrap_err();
+
+        assert_eq!(err.kind, ParseErrorKind::Syntax);
+    }
+
+    #[tokio::test]
+    async fn reader_error()
+    {
+        let err = AsyncParser::new(Failing(b"[1, ")).parse().await.unwrap_err();
+
+        assert_eq!(err.kind, ParseErrorKind::Io);
+        assert_eq!(err.offset, 4);
+    }
+}
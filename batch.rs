@@ -0,0 +1,371 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write, BufRead, BufReader, BufWriter}
+};
+
+use cringejsonparser::{
+    validate,
+    parser::ParserOptions,
+    stream::{self, ArrayStream, LineStream, Checkpoint}
+};
+
+use crate::{
+    complain,
+    random::Rng
+};
+
+
+fn open_array(filepath: &str) -> ArrayStream<BufReader<File>>
+{
+    let file = File::open(filepath)
+        .unwrap_or_else(|err| complain(&format!("error reading file: {err:?}")));
+
+    ArrayStream::new(BufReader::new(file))
+}
+
+// the stream only splits at the right commas, so an element has to be checked before
+// anything gets written from it
+fn check_element<R: BufRead>(elements: &ArrayStream<R>, element: &str) -> io::Result<()>
+{
+    // a single element cant have anything after its value
+    let options = ParserOptions::default().trailing_content(false);
+
+    validate::validate(element, &options).map_err(|diagnostic|
+    {
+        let offset = elements.element_offset() + diagnostic.span.start as u64;
+
+        io::Error::new(io::ErrorKind::InvalidData, format!("invalid element at byte {offset}: {}", diagnostic.message))
+    })
+}
+
+fn create_file(filepath: &str) -> BufWriter<File>
+{
+    let file = File::create(filepath)
+        .unwrap_or_else(|err| complain(&format!("error creating {filepath}: {err:?}")));
+
+    BufWriter::new(file)
+}
+
+fn finish_chunk(mut writer: BufWriter<File>) -> io::Result<()>
+{
+    writer.write_all(b"\n]\n")?;
+
+    writer.flush()
+}
+
+// written next to the real file and renamed over it so a crash never leaves half a checkpoint
+fn save_checkpoint(filepath: &str, checkpoint: &Checkpoint) -> io::Result<()>
+{
+    let temporary = format!("{filepath}.tmp");
+
+    fs::write(&temporary, format!("{checkpoint}\n"))?;
+
+    fs::rename(temporary, filepath)
+}
+
+fn load_checkpoint(filepath: &str) -> Option<Checkpoint>
+{
+    let text = match fs::read_to_string(filepath)
+    {
+        Ok(x) => x,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+        Err(err) => complain(&format!("error reading {filepath}: {err:?}"))
+    };
+
+    let checkpoint = Checkpoint::parse(&text).unwrap_or_else(|| complain(&format!("{filepath} isnt a checkpoint")));
+
+    Some(checkpoint)
+}
+
+pub fn chunk(mut args: impl Iterator<Item=String>)
+{
+    let mut filepath = None;
+    let mut size: usize = 1000;
+    let mut output = "part-%d.json".to_owned();
+    let mut checkpoint_path = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut value = |name: &str|
+        {
+            args.next().unwrap_or_else(|| complain(&format!("{name} needs a value")))
+        };
+
+        match arg.as_str()
+        {
+            "--size" =>
+            {
+                size = value("--size").parse().ok().filter(|x| *x > 0)
+                    .unwrap_or_else(|| complain("size must be a positive number"));
+            },
+            "--out" => output = value("--out"),
+            "--checkpoint" => checkpoint_path = Some(value("--checkpoint")),
+            _ => filepath = Some(arg)
+        }
+    }
+
+    if !output.contains("%d")
+    {
+        complain("--out must contain %d for the chunk number");
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to chunk"));
+
+    let checkpoint = checkpoint_path.as_deref().and_then(load_checkpoint);
+
+    if checkpoint.map(|checkpoint| checkpoint.is_finished()).unwrap_or(false)
+    {
+        eprintln!("already finished, nothing left to chunk");
+
+        return;
+    }
+
+    // checkpoints only get saved after a full chunk so the chunk numbering carries on from there
+    let first_chunk = checkpoint.map(|checkpoint|
+    {
+        if checkpoint.elements() % size as u64 != 0
+        {
+            complain("the checkpoint was made with a different --size");
+        }
+
+        (checkpoint.elements() / size as u64) as usize
+    }).unwrap_or(0);
+
+    let mut elements = match checkpoint
+    {
+        Some(checkpoint) =>
+        {
+            let file = File::open(&filepath)
+                .unwrap_or_else(|err| complain(&format!("error reading file: {err:?}")));
+
+            ArrayStream::resume(BufReader::new(file), &checkpoint)
+                .unwrap_or_else(|err| complain(&format!("error resuming: {err:?}")))
+        },
+        None => open_array(&filepath)
+    };
+
+    let result = (|| -> io::Result<usize>
+    {
+        let mut chunks = first_chunk;
+        let mut current: Option<(BufWriter<File>, usize)> = None;
+
+        while let Some(element) = elements.next()
+        {
+            let element = element?;
+            check_element(&elements, &element)?;
+
+            let (writer, count) = current.get_or_insert_with(||
+            {
+                let writer = create_file(&output.replace("%d", &chunks.to_string()));
+                chunks += 1;
+
+                (writer, 0)
+            });
+
+            writer.write_all(if *count == 0 { b"[\n" } else { b",\n" })?;
+            writer.write_all(element.as_bytes())?;
+
+            *count += 1;
+
+            if *count == size
+            {
+                finish_chunk(current.take().expect("just inserted").0)?;
+
+                if let Some(checkpoint_path) = &checkpoint_path
+                {
+                    save_checkpoint(checkpoint_path, &elements.checkpoint())?;
+                }
+            }
+        }
+
+        if let Some((writer, _)) = current
+        {
+            finish_chunk(writer)?;
+        }
+
+        if let Some(checkpoint_path) = &checkpoint_path
+        {
+            save_checkpoint(checkpoint_path, &elements.checkpoint())?;
+        }
+
+        Ok(chunks - first_chunk)
+    })();
+
+    match result
+    {
+        Ok(chunks) => eprintln!("wrote {chunks} chunks"),
+        Err(err) => complain(&format!("error chunking: {err}"))
+    }
+}
+
+pub fn concat(args: impl Iterator<Item=String>)
+{
+    let mut lines = false;
+    let mut filepaths = Vec::new();
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--lines" => lines = true,
+            _ => filepaths.push(arg)
+        }
+    }
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide paths to concatenate");
+    }
+
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+
+    let result = (|| -> io::Result<()>
+    {
+        let mut count = 0;
+
+        for filepath in &filepaths
+        {
+            let mut elements = open_array(filepath);
+
+            while let Some(element) = elements.next()
+            {
+                let element = element.and_then(|element| check_element(&elements, &element).map(|_| element))
+                    .map_err(|err| io::Error::new(err.kind(), format!("{filepath}: {err}")))?;
+
+                if lines
+                {
+                    writer.write_all(stream::minify(&element).as_bytes())?;
+                    writer.write_all(b"\n")?;
+                } else
+                {
+                    writer.write_all(if count == 0 { b"[\n" } else { b",\n" })?;
+                    writer.write_all(element.as_bytes())?;
+                }
+
+                count += 1;
+            }
+        }
+
+        if !lines
+        {
+            writer.write_all(if count == 0 { b"[]\n" } else { b"\n]\n" })?;
+        }
+
+        writer.flush()
+    })();
+
+    if let Err(err) = result
+    {
+        // keep whatever was already written, exiting skips the destructors
+        let _ = writer.flush();
+
+        complain(&format!("error concatenating: {err}"))
+    }
+}
+
+pub fn sample(mut args: impl Iterator<Item=String>)
+{
+    let mut filepath = None;
+    let mut amount: usize = 10;
+    let mut seed = None;
+    let mut lines = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut value = |name: &str|
+        {
+            args.next().unwrap_or_else(|| complain(&format!("{name} needs a value")))
+        };
+
+        match arg.as_str()
+        {
+            "-n" =>
+            {
+                amount = value("-n").parse()
+                    .unwrap_or_else(|_| complain("-n must be a number"));
+            },
+            "--seed" =>
+            {
+                seed = Some(value("--seed").parse::<u64>()
+                    .unwrap_or_else(|_| complain("seed must be a number")));
+            },
+            "--lines" => lines = Some(true),
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to sample"));
+
+    let mut rng = seed.map(Rng::new).unwrap_or_else(Rng::from_time);
+
+    let result = (|| -> io::Result<()>
+    {
+        let mut reader = BufReader::new(File::open(&filepath)?);
+
+        // anything that doesnt start with an array is treated as one document per line
+        let lines = match lines
+        {
+            Some(x) => x,
+            None =>
+            {
+                let first = reader.fill_buf()?.iter().find(|c| !c.is_ascii_whitespace()).copied();
+
+                first != Some(b'[')
+            }
+        };
+
+        let elements: Box<dyn Iterator<Item=io::Result<String>>> = if lines
+        {
+            Box::new(LineStream::new(reader))
+        } else
+        {
+            Box::new(ArrayStream::new(reader))
+        };
+
+        let mut reservoir: Vec<(usize, String)> = Vec::with_capacity(amount);
+
+        for (id, element) in elements.enumerate()
+        {
+            let element = element?;
+
+            if reservoir.len() < amount
+            {
+                reservoir.push((id, element));
+            } else
+            {
+                let replaced = rng.below(id as u64 + 1) as usize;
+
+                if replaced < amount
+                {
+                    reservoir[replaced] = (id, element);
+                }
+            }
+        }
+
+        reservoir.sort_unstable_by_key(|(id, _)| *id);
+
+        let stdout = io::stdout();
+        let mut writer = BufWriter::new(stdout.lock());
+
+        if lines
+        {
+            reservoir.iter().try_for_each(|(_, element)| writeln!(writer, "{element}"))?;
+        } else if reservoir.is_empty()
+        {
+            writer.write_all(b"[]\n")?;
+        } else
+        {
+            let elements: Vec<&str> = reservoir.iter().map(|(_, element)| element.as_str()).collect();
+
+            writeln!(writer, "[\n{}\n]", elements.join(",\n"))?;
+        }
+
+        writer.flush()
+    })();
+
+    if let Err(err) = result
+    {
+        complain(&format!("error sampling: {err}"))
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    hint,
+    time::{Duration, Instant},
+    alloc::{GlobalAlloc, System, Layout},
+    sync::atomic::{AtomicUsize, Ordering}
+};
+
+use cringejsonparser::{
+    lexer::{Lexer, TokenKind, Position},
+    validate,
+    events::Handler,
+    parser::{Parser, ParserOptions}
+};
+
+use crate::{
+    complain,
+    read_file
+};
+
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8
+    {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+
+        unsafe{ System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        unsafe{ System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8
+    {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+
+        unsafe{ System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// the cost of parsing with nothing done with the values
+struct Discard;
+
+impl Handler for Discard {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode
+{
+    Dom,
+    Borrowed,
+    Events,
+    Tape,
+    #[cfg(feature = "parallel")]
+    Parallel,
+    Stream,
+    Validate
+}
+
+impl Mode
+{
+    fn name(self) -> &'static str
+    {
+        match self
+        {
+            Self::Dom => "dom",
+            Self::Borrowed => "borrowed",
+            Self::Events => "events",
+            Self::Tape => "tape",
+            #[cfg(feature = "parallel")]
+            Self::Parallel => "parallel",
+            Self::Stream => "stream",
+            Self::Validate => "validate"
+        }
+    }
+
+    // the error is only looked at during the warm up, timed runs throw it away
+    fn run(self, text: &str) -> Result<(), String>
+    {
+        match self
+        {
+            Self::Dom => hint::black_box(Parser::new(text.chars()).parse()).map(|_| ()).map_err(|err| err.to_string()),
+            Self::Borrowed =>
+            {
+                hint::black_box(Parser::new(text.chars()).parse_borrowed()).map(|_| ()).map_err(|err| err.to_string())
+            },
+            Self::Events =>
+            {
+                hint::black_box(Parser::new(text.chars()).parse_events(&mut Discard)).map_err(|err| err.to_string())
+            },
+            Self::Tape => hint::black_box(Parser::new(text.chars()).parse_tape()).map(|_| ()).map_err(|err| err.to_string()),
+            #[cfg(feature = "parallel")]
+            Self::Parallel =>
+            {
+                hint::black_box(Parser::new(text.chars()).parse_parallel()).map(|_| ()).map_err(|err| err.to_string())
+            },
+            // the lexer takes anything, the closest it gets to an error is a character it doesnt know
+            Self::Stream => match hint::black_box(Lexer::new(text).find(|token| token.kind == TokenKind::Unknown))
+            {
+                Some(token) =>
+                {
+                    let position = Position::from_offset(text, token.span.start);
+
+                    Err(format!("unexpected character: {} at {position}", token.text))
+                },
+                None => Ok(())
+            },
+            Self::Validate =>
+            {
+                hint::black_box(validate::validate(text, &ParserOptions::default())).map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+pub fn bench(mut args: impl Iterator<Item=String>)
+{
+    let mut filepath = None;
+    let mut iterations: usize = 10;
+    let mut mode = Mode::Dom;
+
+    while let Some(arg) = args.next()
+    {
+        let mut value = |name: &str|
+        {
+            args.next().unwrap_or_else(|| complain(&format!("{name} needs a value")))
+        };
+
+        match arg.as_str()
+        {
+            "--iterations" =>
+            {
+                iterations = value("--iterations").parse().ok().filter(|x| *x > 0)
+                    .unwrap_or_else(|| complain("iterations must be a positive number"));
+            },
+            "--mode" =>
+            {
+                mode = match value("--mode").as_str()
+                {
+                    "dom" => Mode::Dom,
+                    "borrowed" => Mode::Borrowed,
+                    "events" => Mode::Events,
+                    "tape" => Mode::Tape,
+                    #[cfg(feature = "parallel")]
+                    "parallel" => Mode::Parallel,
+                    "stream" => Mode::Stream,
+                    "validate" => Mode::Validate,
+                    x => complain(&format!("unknown mode: {x} (expected dom, borrowed, events, tape, stream or validate)"))
+                };
+            },
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let text = read_file(&filepath.unwrap_or_else(|| complain("pls provide a path to benchmark")));
+
+    // warm up caches and catch invalid input before timing anything
+    if let Err(err) = mode.run(&text)
+    {
+        complain(&format!("invalid json: {err}"))
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+
+    let mut total = Duration::ZERO;
+    for _ in 0..iterations
+    {
+        let start = Instant::now();
+
+        let _ = mode.run(&text);
+
+        total += start.elapsed();
+    }
+
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / iterations;
+    let allocated_bytes = (ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes) / iterations;
+
+    let megabytes = text.len() as f64 / 1_000_000.0;
+    let per_iteration = total / iterations as u32;
+
+    println!("mode: {}, iterations: {iterations}, size: {megabytes:.2} MB", mode.name());
+    println!(
+        "time: {per_iteration:?} per iteration, {:.2} MB/s",
+        megabytes * iterations as f64 / total.as_secs_f64()
+    );
+    println!("allocations: {allocations} per iteration ({allocated_bytes} bytes)");
+}
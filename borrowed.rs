@@ -0,0 +1,257 @@
+use std::{
+    borrow::Cow,
+    sync::Arc
+};
+
+use crate::parser::{Parser, ParserOptions, ObjectValue, Object, ObjectField};
+
+
+// same shape as ObjectValue except strings and keys without escapes point straight into
+// the parsed text, lists and objects are plain vecs since nothing gets shared
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedValue<'a>
+{
+    Null,
+    Text(Cow<'a, str>),
+    Number(i64),
+    Float(f64),
+    Bool(bool),
+    List(Vec<BorrowedValue<'a>>),
+    Object(Vec<(Cow<'a, str>, BorrowedValue<'a>)>),
+    // only with Parser::raw_below, lists and objects that deep are left as text
+    Raw(RawValue<'a>)
+}
+
+impl<'a> BorrowedValue<'a>
+{
+    pub fn get_text(&self) -> Option<&str>
+    {
+        match self
+        {
+            Self::Text(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_number(&self) -> Option<i64>
+    {
+        match self
+        {
+            Self::Number(x) => Some(*x),
+            _ => None
+        }
+    }
+
+    // integers convert too, big ones lose precision
+    pub fn get_float(&self) -> Option<f64>
+    {
+        match self
+        {
+            Self::Float(x) => Some(*x),
+            Self::Number(x) => Some(*x as f64),
+            _ => None
+        }
+    }
+
+    pub fn is_null(&self) -> bool
+    {
+        matches!(self, Self::Null)
+    }
+
+    pub fn get_bool(&self) -> Option<bool>
+    {
+        match self
+        {
+            Self::Bool(x) => Some(*x),
+            _ => None
+        }
+    }
+
+    pub fn get_list(&self) -> Option<&[BorrowedValue<'a>]>
+    {
+        match self
+        {
+            Self::List(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_object(&self) -> Option<&[(Cow<'a, str>, BorrowedValue<'a>)]>
+    {
+        match self
+        {
+            Self::Object(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_raw(&self) -> Option<&RawValue<'a>>
+    {
+        match self
+        {
+            Self::Raw(x) => Some(x),
+            _ => None
+        }
+    }
+
+    // first field with the key, same as ObjectValue
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'a>>
+    {
+        self.get_object()?.iter().find(|(field, _)| field == key).map(|(_, value)| value)
+    }
+
+    pub fn type_name(&self) -> &'static str
+    {
+        match self
+        {
+            Self::Null => "null",
+            Self::Text(_) => "string",
+            Self::Number(_) | Self::Float(_) => "number",
+            Self::Bool(_) => "bool",
+            Self::List(_) => "array",
+            Self::Object(_) => "object",
+            Self::Raw(_) => "raw"
+        }
+    }
+
+    // stable so duplicate keys keep their order, raw values stay exactly like they were written
+    pub fn sort_keys(&mut self)
+    {
+        match self
+        {
+            Self::List(list) => list.iter_mut().for_each(Self::sort_keys),
+            Self::Object(fields) =>
+            {
+                fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+                fields.iter_mut().for_each(|(_, value)| value.sort_keys());
+            },
+            _ => ()
+        }
+    }
+
+    // copies every borrowed string, for when the value has to outlive the text
+    pub fn into_owned(self) -> ObjectValue
+    {
+        match self
+        {
+            Self::Null => ObjectValue::Null,
+            Self::Text(x) => ObjectValue::Text(x.into()),
+            Self::Number(x) => ObjectValue::Number(x),
+            Self::Float(x) => ObjectValue::Float(x),
+            Self::Bool(x) => ObjectValue::Bool(x),
+            Self::List(list) =>
+            {
+                ObjectValue::List(list.into_iter().map(Self::into_owned).collect::<Vec<_>>().into())
+            },
+            Self::Object(fields) =>
+            {
+                let fields = fields.into_iter().map(|(key, value)| ObjectField::new(key, value.into_owned())).collect();
+
+                ObjectValue::Object(Arc::new(Object::from_fields(fields)))
+            },
+            Self::Raw(raw) => raw.parse().into_owned()
+        }
+    }
+}
+
+// the text of a value thats been checked but not built, it gets parsed again with the
+// same options it was checked with so that cant fail
+#[derive(Debug, Clone)]
+pub struct RawValue<'a>
+{
+    text: &'a str,
+    options: Arc<ParserOptions>
+}
+
+impl<'a> RawValue<'a>
+{
+    pub(crate) fn new(text: &'a str, options: Arc<ParserOptions>) -> Self
+    {
+        Self{text, options}
+    }
+
+    // exactly like it was in the source, whitespace and comments inside it included
+    pub fn get(&self) -> &'a str
+    {
+        self.text
+    }
+
+    pub fn parse(&self) -> BorrowedValue<'a>
+    {
+        Parser::new(self.text.chars())
+            .options((*self.options).clone())
+            .parse_borrowed()
+            .expect("raw values are checked when theyre captured")
+    }
+}
+
+// the same text is the same value whatever it was checked with
+impl PartialEq for RawValue<'_>
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.text == other.text
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::DuplicateKeys;
+
+    use super::*;
+
+
+    fn parse(text: &str, duplicate_keys: DuplicateKeys) -> Result<BorrowedValue<'_>, usize>
+    {
+        Parser::new(text.chars())
+            .options(ParserOptions::default().duplicate_keys(duplicate_keys))
+            .raw_below(1)
+            .parse_borrowed()
+            .map_err(|err| err.offset)
+    }
+
+    fn raw<'a>(value: &BorrowedValue<'a>) -> RawValue<'a>
+    {
+        match value
+        {
+            BorrowedValue::List(values) => match &values[0]
+            {
+                BorrowedValue::Raw(raw) => raw.clone(),
+                value => panic!("{value:?} isnt raw")
+            },
+            value => panic!("{value:?} isnt a list")
+        }
+    }
+
+    #[test]
+    fn raw_text_is_kept_as_it_was()
+    {
+        let value = parse("[ {\"a\" : [1, 2] } , 3]", DuplicateKeys::Keep).unwrap();
+
+        assert_eq!(raw(&value).get(), "{\"a\" : [1, 2] }");
+        assert_eq!(value.into_owned(), Parser::new("[{\"a\": [1, 2]}, 3]".chars()).parse().unwrap());
+    }
+
+    #[test]
+    fn raw_values_keep_the_duplicate_key_policy()
+    {
+        let text = r#"[{"a": 1, "a": 2}]"#;
+
+        let fields = |duplicate_keys|
+        {
+            match raw(&parse(text, duplicate_keys).unwrap()).parse()
+            {
+                BorrowedValue::Object(fields) => fields,
+                value => panic!("{value:?} isnt an object")
+            }
+        };
+
+        assert_eq!(fields(DuplicateKeys::Keep).len(), 2);
+        assert_eq!(fields(DuplicateKeys::FirstWins), [("a".into(), BorrowedValue::Number(1))]);
+        assert_eq!(fields(DuplicateKeys::LastWins), [("a".into(), BorrowedValue::Number(2))]);
+
+        // checked right when its captured so parse never has to fail
+        assert_eq!(parse(text, DuplicateKeys::Error), Err(10));
+    }
+}
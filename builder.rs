@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use crate::parser::{ObjectValue, Object};
+
+
+// for when the shape of the value is only known at runtime and json! doesnt fit,
+// every value is anything that turns into an ObjectValue
+//
+//     Object::builder().field("name", "main").field("vaddr", 0x401000).build()
+#[derive(Debug, Clone, Default)]
+pub struct ObjectBuilder
+{
+    object: Object
+}
+
+impl ObjectBuilder
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    // a key thats already there gets its value replaced but stays where it was
+    pub fn field(mut self, key: impl Into<Arc<str>>, value: impl Into<ObjectValue>) -> Self
+    {
+        self.object.insert(key, value.into());
+
+        self
+    }
+
+    // only adds the field if theres a value, for optional things that shouldnt show up as null
+    pub fn field_opt(self, key: impl Into<Arc<str>>, value: Option<impl Into<ObjectValue>>) -> Self
+    {
+        match value
+        {
+            Some(value) => self.field(key, value),
+            None => self
+        }
+    }
+
+    pub fn build(self) -> Object
+    {
+        self.object
+    }
+}
+
+//     ListBuilder::new().push(".text").push(".data").build()
+#[derive(Debug, Clone, Default)]
+pub struct ListBuilder
+{
+    values: Vec<ObjectValue>
+}
+
+impl ListBuilder
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    pub fn push(mut self, value: impl Into<ObjectValue>) -> Self
+    {
+        self.values.push(value.into());
+
+        self
+    }
+
+    pub fn extend<T: Into<ObjectValue>>(mut self, values: impl IntoIterator<Item=T>) -> Self
+    {
+        self.values.extend(values.into_iter().map(Into::into));
+
+        self
+    }
+
+    pub fn build(self) -> ObjectValue
+    {
+        ObjectValue::List(Arc::new(self.values))
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs,
+    process
+};
+
+use cringejsonparser::{Parser, ParserOptions};
+
+use crate::complain;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected
+{
+    Accept,
+    Reject,
+    // the rfc leaves it up to the parser
+    Either
+}
+
+impl Expected
+{
+    // jsontestsuite names every file after what a parser has to do with it
+    fn from_name(name: &str) -> Option<Self>
+    {
+        match name.split('_').next()?
+        {
+            "y" => Some(Self::Accept),
+            "n" => Some(Self::Reject),
+            "i" => Some(Self::Either),
+            _ => None
+        }
+    }
+}
+
+// runs every file in the test_parsing directory of jsontestsuite, strict unless told otherwise
+pub fn conformance(args: impl Iterator<Item=String>)
+{
+    let mut options = ParserOptions::strict();
+    let mut verbose = false;
+    let mut directory = None;
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--lenient" => options = ParserOptions::default(),
+            "--verbose" => verbose = true,
+            _ => directory = Some(arg)
+        }
+    }
+
+    let directory = directory.unwrap_or_else(|| complain("pls provide the test_parsing directory of jsontestsuite"));
+
+    let mut names: Vec<String> = fs::read_dir(&directory)
+        .unwrap_or_else(|err| complain(&format!("error reading {directory}: {err:?}")))
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+
+    names.sort();
+
+    let mut failed = 0;
+    let mut passed = 0;
+    let mut accepted = 0;
+    let mut either = 0;
+
+    for name in names
+    {
+        let expected = match Expected::from_name(&name)
+        {
+            Some(x) => x,
+            None => continue
+        };
+
+        let bytes = fs::read(format!("{directory}/{name}"))
+            .unwrap_or_else(|err| complain(&format!("error reading {name}: {err:?}")));
+
+        let result = Parser::from_bytes(&bytes).options(options.clone()).parse();
+
+        match (expected, result)
+        {
+            (Expected::Either, result) =>
+            {
+                either += 1;
+
+                if result.is_ok()
+                {
+                    accepted += 1;
+                }
+
+                if verbose
+                {
+                    let outcome = if result.is_ok() { "accepted" } else { "rejected" };
+
+                    println!("{name}: {outcome}");
+                }
+            },
+            (Expected::Accept, Ok(_)) | (Expected::Reject, Err(_)) => passed += 1,
+            (Expected::Accept, Err(err)) =>
+            {
+                failed += 1;
+
+                println!("{name}: should parse but didnt: {err}");
+            },
+            (Expected::Reject, Ok(value)) =>
+            {
+                failed += 1;
+
+                println!("{name}: should be rejected but parsed as {value}");
+            }
+        }
+    }
+
+    println!("{passed}/{} passed, {accepted}/{either} implementation defined ones accepted", passed + failed);
+
+    if failed > 0
+    {
+        process::exit(1)
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    fmt,
+    sync::Arc,
+    hash::BuildHasher,
+    collections::{HashMap, BTreeMap}
+};
+
+use crate::parser::{self, ObjectValue, Object, TypeError};
+
+#[cfg(feature = "derive")]
+pub use cringejsonparser_derive::{FromJson, ToJson};
+
+
+// typed values that can be turned into a tree, #[derive(ToJson)] writes this for structs
+// with every field as a key, renamed with #[json(rename = "key")] or left out with #[json(skip)]
+pub trait ToJson
+{
+    fn to_json(&self) -> ObjectValue;
+}
+
+// the other way, #[derive(FromJson)] reads every field from the key with its name, a missing key
+// is an error unless the field is an Option or marked with #[json(default)]
+pub trait FromJson: Sized
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>;
+
+    // what a field of this type is when its key isnt in the object, nothing means its required
+    fn from_missing() -> Option<Self>
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromJsonError
+{
+    // the json pointer to the value thats the wrong type
+    Type(String, TypeError),
+    // the key at the pointer isnt there and the field needs it
+    Missing(String),
+    // a number that doesnt fit in the integer its going into
+    OutOfRange(String)
+}
+
+impl FromJsonError
+{
+    // what the pointer is relative to is one level further up, token is the key or index
+    // of the value the error happened in
+    pub fn within(self, token: &str) -> Self
+    {
+        let prefix = |pointer: String| format!("/{}{pointer}", parser::escape_pointer_token(token));
+
+        match self
+        {
+            Self::Type(pointer, err) => Self::Type(prefix(pointer), err),
+            Self::Missing(pointer) => Self::Missing(prefix(pointer)),
+            Self::OutOfRange(pointer) => Self::OutOfRange(prefix(pointer))
+        }
+    }
+
+    pub fn pointer(&self) -> &str
+    {
+        match self
+        {
+            Self::Type(pointer, _) | Self::Missing(pointer) | Self::OutOfRange(pointer) => pointer
+        }
+    }
+}
+
+impl From<TypeError> for FromJsonError
+{
+    fn from(err: TypeError) -> Self
+    {
+        Self::Type(String::new(), err)
+    }
+}
+
+impl fmt::Display for FromJsonError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::Type(pointer, err) => write!(f, "{err} at {pointer:?}"),
+            Self::Missing(pointer) => write!(f, "missing value at {pointer:?}"),
+            Self::OutOfRange(pointer) => write!(f, "number at {pointer:?} is out of range")
+        }
+    }
+}
+
+impl std::error::Error for FromJsonError {}
+
+// the value at key converted to T, used by #[derive(FromJson)] for every field
+pub fn field<T: FromJson>(object: &Object, key: &str) -> Result<T, FromJsonError>
+{
+    match object.get(key)
+    {
+        Some(value) => T::from_json(value).map_err(|err| err.within(key)),
+        None => T::from_missing().ok_or_else(|| FromJsonError::Missing(String::new()).within(key))
+    }
+}
+
+// same as field but a missing key gives whatever default gives instead
+pub fn field_or_else<T: FromJson>(object: &Object, key: &str, default: impl FnOnce() -> T) -> Result<T, FromJsonError>
+{
+    match object.get(key)
+    {
+        Some(value) => T::from_json(value).map_err(|err| err.within(key)),
+        None => Ok(default())
+    }
+}
+
+// the element at id of a list, for tuple structs
+pub fn element<T: FromJson>(list: &[ObjectValue], id: usize) -> Result<T, FromJsonError>
+{
+    let token = id.to_string();
+
+    match list.get(id)
+    {
+        Some(value) => T::from_json(value).map_err(|err| err.within(&token)),
+        None => T::from_missing().ok_or_else(|| FromJsonError::Missing(String::new()).within(&token))
+    }
+}
+
+impl ToJson for ObjectValue
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.clone()
+    }
+}
+
+impl FromJson for ObjectValue
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        Ok(value.clone())
+    }
+}
+
+impl ToJson for bool
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        (*self).into()
+    }
+}
+
+impl FromJson for bool
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        Ok(value.try_get_bool()?)
+    }
+}
+
+macro_rules! integers
+{
+    ($($integer:ty),*) =>
+    {
+        $(
+            impl ToJson for $integer
+            {
+                fn to_json(&self) -> ObjectValue
+                {
+                    (*self).into()
+                }
+            }
+
+            impl FromJson for $integer
+            {
+                fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+                {
+                    <$integer>::try_from(value.try_get_number()?).map_err(|_| FromJsonError::OutOfRange(String::new()))
+                }
+            }
+        )*
+    };
+}
+
+integers!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl ToJson for f32
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        (*self).into()
+    }
+}
+
+// integers are fine where a float is wanted, same as get_float
+impl FromJson for f32
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        Ok(value.try_get_float()? as f32)
+    }
+}
+
+impl ToJson for f64
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        (*self).into()
+    }
+}
+
+impl FromJson for f64
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        Ok(value.try_get_float()?)
+    }
+}
+
+impl ToJson for str
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.into()
+    }
+}
+
+impl ToJson for String
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.as_str().into()
+    }
+}
+
+impl FromJson for String
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        Ok(value.try_get_text()?.to_owned())
+    }
+}
+
+impl ToJson for Arc<str>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.clone().into()
+    }
+}
+
+// shares the text with the tree instead of copying it
+impl FromJson for Arc<str>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        match value
+        {
+            ObjectValue::Text(text) => Ok(text.clone()),
+            value => Err(value.type_error("string").into())
+        }
+    }
+}
+
+// none is null both ways, and a missing key is none too
+impl<T: ToJson> ToJson for Option<T>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.as_ref().map(T::to_json).unwrap_or(ObjectValue::Null)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        match value
+        {
+            ObjectValue::Null => Ok(None),
+            value => T::from_json(value).map(Some)
+        }
+    }
+
+    fn from_missing() -> Option<Self>
+    {
+        Some(None)
+    }
+}
+
+impl<T: ToJson> ToJson for Box<T>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        (**self).to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl<T: ToJson> ToJson for [T]
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.iter().map(T::to_json).collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        value.try_get_list()?.iter().enumerate().map(|(id, value)|
+        {
+            T::from_json(value).map_err(|err| err.within(&id.to_string()))
+        }).collect()
+    }
+}
+
+// maps are objects with their keys in whatever order iterating them gives
+impl<T: ToJson, S> ToJson for HashMap<String, T, S>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.iter().map(|(key, value)| (key.as_str(), value.to_json())).collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, T, S>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        map_from_json(value)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T>
+{
+    fn to_json(&self) -> ObjectValue
+    {
+        self.iter().map(|(key, value)| (key.as_str(), value.to_json())).collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T>
+{
+    fn from_json(value: &ObjectValue) -> Result<Self, FromJsonError>
+    {
+        map_from_json(value)
+    }
+}
+
+// with repeated keys the last one wins
+fn map_from_json<T: FromJson, M: FromIterator<(String, T)>>(value: &ObjectValue) -> Result<M, FromJsonError>
+{
+    value.try_get_object()?.fields().iter().map(|field|
+    {
+        T::from_json(field.value()).map(|value| (field.key().to_owned(), value)).map_err(|err| err.within(field.key()))
+    }).collect()
+}
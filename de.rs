@@ -0,0 +1,561 @@
+use std::{
+    fmt,
+    slice
+};
+
+use serde::de::{
+    self,
+    Deserialize,
+    DeserializeOwned,
+    DeserializeSeed,
+    Visitor,
+    SeqAccess,
+    MapAccess,
+    EnumAccess,
+    VariantAccess,
+    Unexpected
+};
+
+use crate::parser::{Parser, ParseError, ObjectValue, Object, ObjectField};
+
+
+// lets anything with #[derive(Deserialize)] be read by this parser instead of serde_json, the
+// text gets parsed into a tree first and the types borrow their strings out of it if they want
+//
+//     let symbol: Symbol = cringejsonparser::de::from_str(r#"{"name": "main", "vaddr": 4198400}"#)?;
+//
+// enums are the same shape serde_json uses, a unit variant is a string with its name and
+// anything else is an object with just the name as the key, numbers too big for an i64 are
+// floats in the tree so they dont fit into a u64 field
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, Error>
+{
+    from_parser(Parser::new(text.chars()))
+}
+
+// for when the parser needs options like comments or json5
+pub fn from_parser<T: DeserializeOwned>(parser: Parser) -> Result<T, Error>
+{
+    let value = parser.parse()?;
+
+    from_value(&value)
+}
+
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de ObjectValue) -> Result<T, Error>
+{
+    T::deserialize(value)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error
+{
+    Parse(ParseError),
+    // whatever the type being serialized or deserialized complained about
+    Message(String)
+}
+
+impl From<ParseError> for Error
+{
+    fn from(err: ParseError) -> Self
+    {
+        Self::Parse(err)
+    }
+}
+
+impl fmt::Display for Error
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::Message(message) => write!(f, "{message}")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error
+{
+    fn custom<T: fmt::Display>(message: T) -> Self
+    {
+        Self::Message(message.to_string())
+    }
+}
+
+// what serde calls the value in its messages about getting the wrong type
+fn unexpected(value: &ObjectValue) -> Unexpected<'_>
+{
+    match value
+    {
+        ObjectValue::Null => Unexpected::Unit,
+        ObjectValue::Text(text) => Unexpected::Str(text),
+        ObjectValue::Number(x) => Unexpected::Signed(*x),
+        ObjectValue::Float(x) => Unexpected::Float(*x),
+        ObjectValue::Bool(x) => Unexpected::Bool(*x),
+        ObjectValue::List(_) => Unexpected::Seq,
+        ObjectValue::Object(_) => Unexpected::Map
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &'de ObjectValue
+{
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+    {
+        match self
+        {
+            ObjectValue::Null => visitor.visit_unit(),
+            ObjectValue::Text(text) => visitor.visit_borrowed_str(text),
+            ObjectValue::Number(x) => visitor.visit_i64(*x),
+            ObjectValue::Float(x) => visitor.visit_f64(*x),
+            ObjectValue::Bool(x) => visitor.visit_bool(*x),
+            ObjectValue::List(values) =>
+            {
+                let mut seq = SeqDeserializer{values: values.iter()};
+                let value = visitor.visit_seq(&mut seq)?;
+
+                seq.finish(values.len())?;
+
+                Ok(value)
+            },
+            ObjectValue::Object(object) =>
+            {
+                let mut map = MapDeserializer{fields: object.fields().iter(), value: None};
+                let value = visitor.visit_map(&mut map)?;
+
+                map.finish(object)?;
+
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+    {
+        match self
+        {
+            ObjectValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value)
+        }
+    }
+
+    // a newtype is the same json as whats inside of it
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Error>
+    {
+        match self
+        {
+            ObjectValue::Text(variant) => visitor.visit_enum(EnumDeserializer{variant, value: None}),
+            ObjectValue::Object(object) if object.fields().len() == 1 =>
+            {
+                let field = &object.fields()[0];
+
+                visitor.visit_enum(EnumDeserializer{variant: field.key(), value: Some(field.value())})
+            },
+            value => Err(de::Error::invalid_type(unexpected(value), &"a string or an object with one key"))
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+    {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any!{
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqDeserializer<'de>
+{
+    values: slice::Iter<'de, ObjectValue>
+}
+
+impl SeqDeserializer<'_>
+{
+    // a tuple or an array that stopped reading early means the list was too long for it
+    fn finish(&self, len: usize) -> Result<(), Error>
+    {
+        if self.values.len() == 0
+        {
+            Ok(())
+        } else
+        {
+            Err(de::Error::invalid_length(len, &"fewer elements in the list"))
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de>
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    {
+        self.values.next().map(|value| seed.deserialize(value)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize>
+    {
+        Some(self.values.len())
+    }
+}
+
+struct MapDeserializer<'de>
+{
+    fields: slice::Iter<'de, ObjectField>,
+    // the value of the key that was just given out
+    value: Option<&'de ObjectValue>
+}
+
+impl MapDeserializer<'_>
+{
+    fn finish(&self, object: &Object) -> Result<(), Error>
+    {
+        if self.fields.len() == 0
+        {
+            Ok(())
+        } else
+        {
+            Err(de::Error::invalid_length(object.fields().len(), &"fewer fields in the object"))
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer<'de>
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    {
+        match self.fields.next()
+        {
+            Some(field) =>
+            {
+                self.value = Some(field.value());
+
+                seed.deserialize(KeyDeserializer(field.key())).map(Some)
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error>
+    {
+        match self.value.take()
+        {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("value asked for before its key"))
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize>
+    {
+        Some(self.fields.len())
+    }
+}
+
+// keys are always strings in the text but maps with integer or unit enum keys
+// are common enough that the string gets parsed into whatever was asked for
+struct KeyDeserializer<'de>(&'de str);
+
+impl KeyDeserializer<'_>
+{
+    fn parse<T: std::str::FromStr>(&self, expected: &str) -> Result<T, Error>
+    {
+        self.0.parse().map_err(|_| de::Error::invalid_value(Unexpected::Str(self.0), &expected))
+    }
+}
+
+macro_rules! key_integers
+{
+    ($($method:ident => $integer:ty, $visit:ident);*) =>
+    {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+            {
+                visitor.$visit(self.parse::<$integer>("an integer key")?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de>
+{
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+    {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    key_integers!{
+        deserialize_i8 => i64, visit_i64;
+        deserialize_i16 => i64, visit_i64;
+        deserialize_i32 => i64, visit_i64;
+        deserialize_i64 => i64, visit_i64;
+        deserialize_u8 => u64, visit_u64;
+        deserialize_u16 => u64, visit_u64;
+        deserialize_u32 => u64, visit_u64;
+        deserialize_u64 => u64, visit_u64
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error>
+    {
+        visitor.visit_bool(self.parse("a true or false key")?)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error>
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Error>
+    {
+        visitor.visit_enum(EnumDeserializer{variant: self.0, value: None})
+    }
+
+    serde::forward_to_deserialize_any!{
+        i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer<'de>
+{
+    variant: &'de str,
+    // nothing for a unit variant written as just its name
+    value: Option<&'de ObjectValue>
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de>
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error>
+    {
+        let variant = seed.deserialize(KeyDeserializer(self.variant))?;
+
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumDeserializer<'de>
+{
+    type Error = Error;
+
+    // {"Variant": null} is fine for a unit variant too
+    fn unit_variant(self) -> Result<(), Error>
+    {
+        match self.value
+        {
+            None | Some(ObjectValue::Null) => Ok(()),
+            Some(value) => Err(de::Error::invalid_type(unexpected(value), &"a unit variant"))
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error>
+    {
+        match self.value
+        {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"a newtype variant"))
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    {
+        match self.value
+        {
+            Some(value @ ObjectValue::List(_)) => de::Deserializer::deserialize_any(value, visitor),
+            Some(value) => Err(de::Error::invalid_type(unexpected(value), &"a tuple variant")),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"a tuple variant"))
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error>
+    {
+        match self.value
+        {
+            Some(value @ ObjectValue::Object(_)) => de::Deserializer::deserialize_any(value, visitor),
+            Some(value) => Err(de::Error::invalid_type(unexpected(value), &"a struct variant")),
+            None => Err(de::Error::invalid_type(Unexpected::UnitVariant, &"a struct variant"))
+        }
+    }
+}
+
+// so a tree can sit inside of a derived type for the parts that dont have a fixed shape
+impl<'de> Deserialize<'de> for ObjectValue
+{
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor
+{
+    type Value = ObjectValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.write_str("any json value")
+    }
+
+    fn visit_bool<E: de::Error>(self, x: bool) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, x: i64) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, x: u64) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_f64<E: de::Error>(self, x: f64) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_str<E: de::Error>(self, x: &str) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_string<E: de::Error>(self, x: String) -> Result<ObjectValue, E>
+    {
+        Ok(x.into())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<ObjectValue, E>
+    {
+        Ok(ObjectValue::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<ObjectValue, D::Error>
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<ObjectValue, E>
+    {
+        Ok(ObjectValue::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ObjectValue, A::Error>
+    {
+        let mut values: Vec<ObjectValue> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(value) = seq.next_element()?
+        {
+            values.push(value);
+        }
+
+        Ok(values.into())
+    }
+
+    // repeated keys are kept like DuplicateKeys::Keep would
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ObjectValue, A::Error>
+    {
+        let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+
+        while let Some(key) = map.next_key::<String>()?
+        {
+            fields.push(ObjectField::new(key, map.next_value()?));
+        }
+
+        Ok(Object::from_fields(fields).into())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Kind
+    {
+        Function,
+        Data(u32),
+        Range{start: u64, end: u64}
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Symbol
+    {
+        name: String,
+        vaddr: u64,
+        size: Option<u32>,
+        kinds: Vec<Kind>,
+        extra: HashMap<String, ObjectValue>
+    }
+
+    #[test]
+    fn derived_struct()
+    {
+        let text = r#"{
+            "name": "main",
+            "vaddr": 4198400,
+            "size": null,
+            "kinds": ["Function", {"Data": 4}, {"Range": {"start": 1, "end": 2}}],
+            "extra": {"a": [1, "x"]}
+        }"#;
+
+        let symbol: Symbol = from_str(text).unwrap();
+
+        assert_eq!(symbol.name, "main");
+        assert_eq!(symbol.vaddr, 4198400);
+        assert_eq!(symbol.size, None);
+        assert_eq!(symbol.kinds, vec![Kind::Function, Kind::Data(4), Kind::Range{start: 1, end: 2}]);
+        assert_eq!(symbol.extra["a"], ObjectValue::from(vec![ObjectValue::from(1), ObjectValue::from("x")]));
+    }
+
+    #[test]
+    fn borrows_strings_from_the_tree()
+    {
+        let value = Parser::new(r#"{"a": "text"}"#.chars()).parse().unwrap();
+
+        let borrowed: HashMap<&str, &str> = from_value(&value).unwrap();
+
+        assert_eq!(borrowed["a"], "text");
+    }
+
+    #[test]
+    fn errors()
+    {
+        assert!(matches!(from_str::<u32>("[1"), Err(Error::Parse(_))));
+        assert!(matches!(from_str::<u32>("-1"), Err(Error::Message(_))));
+        assert!(matches!(from_str::<Symbol>(r#"{"name": "main"}"#), Err(Error::Message(_))));
+    }
+}
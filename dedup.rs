@@ -0,0 +1,207 @@
+use std::{
+    mem,
+    sync::Arc,
+    collections::HashMap,
+    hash::{Hash, Hasher, DefaultHasher}
+};
+
+use crate::parser::{ObjectValue, Object};
+
+
+const ARC_HEADER: usize = mem::size_of::<usize>() * 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DedupReport
+{
+    pub subtrees: usize,
+    pub shared: usize,
+    pub bytes_saved: usize
+}
+
+// interns identical lists and objects so they point at one allocation, children get
+// interned before their parents so parents only need a shallow comparison
+#[derive(Debug, Default)]
+pub struct Deduplicator
+{
+    interned: HashMap<u64, Vec<ObjectValue>>,
+    report: DedupReport
+}
+
+impl Deduplicator
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    pub fn report(&self) -> DedupReport
+    {
+        self.report
+    }
+
+    pub fn deduplicate(&mut self, value: &mut ObjectValue)
+    {
+        match value
+        {
+            ObjectValue::List(list) =>
+            {
+                // already shared subtrees are left as they are instead of being copied
+                if let Some(list) = Arc::get_mut(list)
+                {
+                    list.iter_mut().for_each(|value| self.deduplicate(value));
+                }
+            },
+            ObjectValue::Object(object) =>
+            {
+                if let Some(object) = Arc::get_mut(object)
+                {
+                    object.fields_mut().iter_mut().for_each(|field| self.deduplicate(field.value_mut()));
+                }
+            },
+            _ => return
+        }
+
+        self.intern(value);
+    }
+
+    fn intern(&mut self, value: &mut ObjectValue)
+    {
+        self.report.subtrees += 1;
+
+        let bucket = self.interned.entry(shallow_hash(value)).or_default();
+
+        match bucket.iter().find(|existing| shallow_eq(existing, value))
+        {
+            Some(existing) =>
+            {
+                if !same_allocation(existing, value)
+                {
+                    self.report.shared += 1;
+                    self.report.bytes_saved += own_size(value);
+
+                    *value = existing.clone();
+                }
+            },
+            None => bucket.push(value.clone())
+        }
+    }
+}
+
+fn same_allocation(a: &ObjectValue, b: &ObjectValue) -> bool
+{
+    match (a, b)
+    {
+        (ObjectValue::List(a), ObjectValue::List(b)) => Arc::ptr_eq(a, b),
+        (ObjectValue::Object(a), ObjectValue::Object(b)) => Arc::ptr_eq(a, b),
+        _ => false
+    }
+}
+
+fn hash_child(value: &ObjectValue, state: &mut impl Hasher)
+{
+    mem::discriminant(value).hash(state);
+
+    match value
+    {
+        ObjectValue::Null => (),
+        ObjectValue::Text(x) => x.hash(state),
+        ObjectValue::Number(x) => x.hash(state),
+        ObjectValue::Float(x) => x.to_bits().hash(state),
+        ObjectValue::Bool(x) => x.hash(state),
+        ObjectValue::List(x) => (Arc::as_ptr(x) as *const ()).hash(state),
+        ObjectValue::Object(x) => Arc::as_ptr(x).hash(state)
+    }
+}
+
+fn shallow_hash(value: &ObjectValue) -> u64
+{
+    let mut state = DefaultHasher::new();
+
+    match value
+    {
+        ObjectValue::List(list) =>
+        {
+            list.len().hash(&mut state);
+            list.iter().for_each(|value| hash_child(value, &mut state));
+        },
+        ObjectValue::Object(object) =>
+        {
+            object.fields().len().hash(&mut state);
+            object.fields().iter().for_each(|field|
+            {
+                field.key().hash(&mut state);
+                hash_child(field.value(), &mut state);
+            });
+        },
+        x => hash_child(x, &mut state)
+    }
+
+    state.finish()
+}
+
+fn child_eq(a: &ObjectValue, b: &ObjectValue) -> bool
+{
+    match (a, b)
+    {
+        (ObjectValue::Null, ObjectValue::Null) => true,
+        (ObjectValue::Text(a), ObjectValue::Text(b)) => a == b,
+        (ObjectValue::Number(a), ObjectValue::Number(b)) => a == b,
+        (ObjectValue::Float(a), ObjectValue::Float(b)) => a.to_bits() == b.to_bits(),
+        (ObjectValue::Bool(a), ObjectValue::Bool(b)) => a == b,
+        (a, b) => same_allocation(a, b)
+    }
+}
+
+fn shallow_eq(a: &ObjectValue, b: &ObjectValue) -> bool
+{
+    match (a, b)
+    {
+        (ObjectValue::List(a), ObjectValue::List(b)) =>
+        {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| child_eq(a, b))
+        },
+        (ObjectValue::Object(a), ObjectValue::Object(b)) =>
+        {
+            a.fields().len() == b.fields().len()
+                && a.fields().iter().zip(b.fields().iter()).all(|(a, b)|
+                {
+                    a.key() == b.key() && child_eq(a.value(), b.value())
+                })
+        },
+        (a, b) => child_eq(a, b)
+    }
+}
+
+fn leaf_size(value: &ObjectValue) -> usize
+{
+    match value
+    {
+        ObjectValue::Text(x) => x.len(),
+        _ => 0
+    }
+}
+
+// heap memory owned directly by this node, its children are already interned at this point
+fn own_size(value: &ObjectValue) -> usize
+{
+    match value
+    {
+        ObjectValue::List(list) =>
+        {
+            ARC_HEADER
+                + mem::size_of_val::<[ObjectValue]>(list)
+                + list.iter().map(leaf_size).sum::<usize>()
+        },
+        ObjectValue::Object(object) =>
+        {
+            ARC_HEADER
+                + mem::size_of::<Object>()
+                + mem::size_of_val(object.fields())
+                + object.fields().iter().map(|field|
+                {
+                    field.key().len() + leaf_size(field.value())
+                }).sum::<usize>()
+        },
+        _ => 0
+    }
+}
@@ -0,0 +1,333 @@
+use proc_macro::{
+    TokenStream,
+    TokenTree,
+    Delimiter,
+    Spacing
+};
+
+
+// #[derive(ToJson)] and #[derive(FromJson)] for the traits in cringejsonparser::convert, this
+// only needs the names of the fields and not their types so it goes through the tokens by hand
+// instead of pulling in syn, the impls are put together as text and parsed at the end
+//
+// fields take #[json(rename = "key")], #[json(default)] for a missing key to be Default::default
+// and #[json(skip)] to never be written or read, tuple structs with one field are the same json
+// as that field and with more theyre lists, unit structs are null
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream
+{
+    expand(input, Struct::impl_to_json)
+}
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream
+{
+    expand(input, Struct::impl_from_json)
+}
+
+fn expand(input: TokenStream, generate: impl FnOnce(&Struct) -> String) -> TokenStream
+{
+    let code = match Struct::parse(input)
+    {
+        Ok(parsed) => generate(&parsed),
+        Err(message) => format!("::core::compile_error!({message:?});")
+    };
+
+    code.parse().unwrap_or_else(|err| panic!("generated code doesnt parse: {err}"))
+}
+
+#[derive(Default)]
+struct Field
+{
+    // whatever self.member is, the name or the index
+    member: String,
+    // a string literal ready to be pasted in
+    key: String,
+    default: bool,
+    skip: bool
+}
+
+enum Shape
+{
+    Named(Vec<Field>),
+    Tuple(Vec<Field>),
+    Unit
+}
+
+struct Struct
+{
+    name: String,
+    shape: Shape
+}
+
+impl Struct
+{
+    fn parse(input: TokenStream) -> Result<Self, String>
+    {
+        let mut tokens = input.into_iter();
+
+        while let Some(token) = tokens.next()
+        {
+            let keyword = match token
+            {
+                TokenTree::Ident(ident) => ident.to_string(),
+                _ => continue
+            };
+
+            match keyword.as_str()
+            {
+                "struct" => (),
+                "enum" | "union" => return Err(format!("json can only be derived for structs, not for an {keyword}")),
+                _ => continue
+            }
+
+            let name = match tokens.next()
+            {
+                Some(TokenTree::Ident(ident)) => ident.to_string(),
+                _ => return Err("expected the name of the struct".to_owned())
+            };
+
+            let shape = match tokens.next()
+            {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace =>
+                {
+                    Shape::Named(split_fields(group.stream()).into_iter().map(named_field).collect::<Result<_, _>>()?)
+                },
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    let fields = split_fields(group.stream()).into_iter().enumerate().map(|(id, tokens)|
+                    {
+                        let mut field = attributes(&tokens)?;
+                        field.member = id.to_string();
+
+                        Ok(field)
+                    }).collect::<Result<_, String>>()?;
+
+                    Shape::Tuple(fields)
+                },
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => Shape::Unit,
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '<' =>
+                {
+                    return Err("json cant be derived for structs with generics yet".to_owned());
+                },
+                _ => return Err(format!("cant make sense of the fields of {name}"))
+            };
+
+            return Ok(Self{name, shape});
+        }
+
+        Err("expected a struct".to_owned())
+    }
+
+    fn impl_to_json(&self) -> String
+    {
+        let body = match &self.shape
+        {
+            Shape::Named(fields) =>
+            {
+                let inserts: String = fields.iter().filter(|field| !field.skip).map(|field|
+                {
+                    format!("object.insert({}, ::cringejsonparser::convert::ToJson::to_json(&self.{}));", field.key, field.member)
+                }).collect();
+
+                format!("
+                    #[allow(unused_mut)]
+                    let mut object = ::cringejsonparser::Object::default();
+                    {inserts}
+                    ::cringejsonparser::ObjectValue::from(object)")
+            },
+            Shape::Tuple(fields) if fields.len() == 1 => "::cringejsonparser::convert::ToJson::to_json(&self.0)".to_owned(),
+            Shape::Tuple(fields) =>
+            {
+                let values: String = fields.iter().map(|field|
+                {
+                    format!("::cringejsonparser::convert::ToJson::to_json(&self.{}),", field.member)
+                }).collect();
+
+                format!("::cringejsonparser::ObjectValue::from(::std::vec![{values}])")
+            },
+            Shape::Unit => "::cringejsonparser::ObjectValue::Null".to_owned()
+        };
+
+        format!("
+            #[automatically_derived]
+            impl ::cringejsonparser::convert::ToJson for {}
+            {{
+                fn to_json(&self) -> ::cringejsonparser::ObjectValue
+                {{
+                    {body}
+                }}
+            }}", self.name)
+    }
+
+    fn impl_from_json(&self) -> String
+    {
+        let body = match &self.shape
+        {
+            Shape::Named(fields) =>
+            {
+                let members: String = fields.iter().map(|field|
+                {
+                    let value = if field.skip
+                    {
+                        "::std::default::Default::default()".to_owned()
+                    } else if field.default
+                    {
+                        format!("::cringejsonparser::convert::field_or_else(object, {}, ::std::default::Default::default)?", field.key)
+                    } else
+                    {
+                        format!("::cringejsonparser::convert::field(object, {})?", field.key)
+                    };
+
+                    format!("{}: {value},", field.member)
+                }).collect();
+
+                format!("
+                    let object = value.try_get_object()?;
+
+                    ::std::result::Result::Ok(Self{{{members}}})")
+            },
+            Shape::Tuple(fields) if fields.len() == 1 =>
+            {
+                "::std::result::Result::Ok(Self(::cringejsonparser::convert::FromJson::from_json(value)?))".to_owned()
+            },
+            Shape::Tuple(fields) =>
+            {
+                let elements: String = fields.iter().map(|field|
+                {
+                    format!("::cringejsonparser::convert::element(list, {})?,", field.member)
+                }).collect();
+
+                format!("
+                    let list = value.try_get_list()?;
+
+                    ::std::result::Result::Ok(Self({elements}))")
+            },
+            Shape::Unit => "value.try_get_null()?; ::std::result::Result::Ok(Self)".to_owned()
+        };
+
+        format!("
+            #[automatically_derived]
+            impl ::cringejsonparser::convert::FromJson for {}
+            {{
+                fn from_json(
+                    value: &::cringejsonparser::ObjectValue
+                ) -> ::std::result::Result<Self, ::cringejsonparser::convert::FromJsonError>
+                {{
+                    {body}
+                }}
+            }}", self.name)
+    }
+}
+
+// the commas inside of types like HashMap<String, u32> arent in a group so
+// the angle brackets have to be counted, the > of an -> doesnt count
+fn split_fields(stream: TokenStream) -> Vec<Vec<TokenTree>>
+{
+    let mut fields = vec![Vec::new()];
+    let mut depth = 0_usize;
+    let mut arrow = false;
+
+    for token in stream
+    {
+        if let TokenTree::Punct(punct) = &token
+        {
+            match punct.as_char()
+            {
+                '<' => depth += 1,
+                '>' if !arrow => depth = depth.saturating_sub(1),
+                ',' if depth == 0 =>
+                {
+                    fields.push(Vec::new());
+
+                    continue;
+                },
+                _ => ()
+            }
+
+            arrow = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else
+        {
+            arrow = false;
+        }
+
+        if let Some(field) = fields.last_mut()
+        {
+            field.push(token);
+        }
+    }
+
+    // a trailing comma leaves an empty one at the end
+    fields.retain(|field| !field.is_empty());
+
+    fields
+}
+
+fn named_field(tokens: Vec<TokenTree>) -> Result<Field, String>
+{
+    let mut field = attributes(&tokens)?;
+
+    // the name is the last identifier before the colon, anything before it is attributes and pub
+    let colon = tokens.iter().position(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'));
+
+    let member = match colon.and_then(|colon| tokens[..colon].last())
+    {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("expected a field name".to_owned())
+    };
+
+    if field.key.is_empty()
+    {
+        field.key = format!("{:?}", member.trim_start_matches("r#"));
+    }
+
+    field.member = member;
+
+    Ok(field)
+}
+
+// the #[json(..)] attributes at the start of the field
+fn attributes(tokens: &[TokenTree]) -> Result<Field, String>
+{
+    let mut field = Field::default();
+
+    for pair in tokens.windows(2)
+    {
+        let attribute = match pair
+        {
+            [TokenTree::Punct(punct), TokenTree::Group(group)] if punct.as_char() == '#' && group.delimiter() == Delimiter::Bracket =>
+            {
+                group.stream().into_iter().collect::<Vec<_>>()
+            },
+            _ => continue
+        };
+
+        let options = match attribute.as_slice()
+        {
+            [TokenTree::Ident(ident), TokenTree::Group(group)] if ident.to_string() == "json" => group.stream(),
+            _ => continue
+        };
+
+        for option in split_fields(options)
+        {
+            match option.as_slice()
+            {
+                [TokenTree::Ident(ident)] if ident.to_string() == "default" => field.default = true,
+                [TokenTree::Ident(ident)] if ident.to_string() == "skip" => field.skip = true,
+                [TokenTree::Ident(ident), TokenTree::Punct(equals), TokenTree::Literal(key)]
+                    if ident.to_string() == "rename" && equals.as_char() == '=' && key.to_string().starts_with('"') =>
+                {
+                    field.key = key.to_string();
+                },
+                _ =>
+                {
+                    let option: String = option.iter().map(|token| token.to_string()).collect();
+
+                    return Err(format!("unknown json attribute: {option}"));
+                }
+            }
+        }
+    }
+
+    Ok(field)
+}
@@ -0,0 +1,159 @@
+use std::sync::{
+    Mutex,
+    RwLock
+};
+
+use crate::parser::ObjectValue;
+
+
+// a persistent view of a parsed tree, snapshots share every subtree and
+// edits only copy the nodes on the path to the change
+#[derive(Debug, Clone)]
+pub struct Document
+{
+    root: ObjectValue
+}
+
+impl Document
+{
+    pub fn new(root: ObjectValue) -> Self
+    {
+        Self{root}
+    }
+
+    pub fn root(&self) -> &ObjectValue
+    {
+        &self.root
+    }
+
+    pub fn into_root(self) -> ObjectValue
+    {
+        self.root
+    }
+
+    pub fn snapshot(&self) -> Self
+    {
+        self.clone()
+    }
+
+    pub fn set(&mut self, pointer: &str, value: ObjectValue) -> Option<()>
+    {
+        self.root.set_pointer(pointer, value)
+    }
+
+    pub fn remove(&mut self, pointer: &str) -> Option<ObjectValue>
+    {
+        self.root.remove_pointer(pointer)
+    }
+}
+
+impl From<ObjectValue> for Document
+{
+    fn from(root: ObjectValue) -> Self
+    {
+        Self::new(root)
+    }
+}
+
+// readers grab a snapshot (the lock is only held for an Arc clone) while a writer
+// edits its own copy, the new version becomes visible to everyone at once on publish
+#[derive(Debug)]
+pub struct SharedDocument
+{
+    current: RwLock<Document>,
+    writer: Mutex<()>
+}
+
+impl SharedDocument
+{
+    pub fn new(document: Document) -> Self
+    {
+        Self{current: RwLock::new(document), writer: Mutex::new(())}
+    }
+
+    pub fn read(&self) -> Document
+    {
+        self.current.read().unwrap_or_else(|err| err.into_inner()).snapshot()
+    }
+
+    // a private copy to prepare edits on, nothing is visible until its published, only update
+    // gets to do these since a draft published without the writer lock could undo another update
+    fn draft(&self) -> Document
+    {
+        self.read()
+    }
+
+    fn publish(&self, document: Document)
+    {
+        *self.current.write().unwrap_or_else(|err| err.into_inner()) = document;
+    }
+
+    // draft, edit and publish while holding the writer lock so concurrent updates dont get lost
+    pub fn update<T>(&self, f: impl FnOnce(&mut Document) -> T) -> T
+    {
+        let _writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+
+        let mut document = self.draft();
+        let value = f(&mut document);
+
+        self.publish(document);
+
+        value
+    }
+}
+
+impl From<Document> for SharedDocument
+{
+    fn from(document: Document) -> Self
+    {
+        Self::new(document)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::thread;
+
+    use super::*;
+
+
+    #[test]
+    fn concurrent_updates_all_land()
+    {
+        let shared = SharedDocument::new(Document::new(ObjectValue::Number(0)));
+
+        thread::scope(|scope|
+        {
+            for _ in 0..8
+            {
+                scope.spawn(||
+                {
+                    for _ in 0..100
+                    {
+                        shared.update(|document|
+                        {
+                            let x = document.root().get_number().unwrap();
+
+                            document.set("", ObjectValue::Number(x + 1));
+                        });
+                    }
+                });
+            }
+        });
+
+        assert_eq!(shared.read().root(), &ObjectValue::Number(800));
+    }
+
+    #[test]
+    fn snapshots_dont_see_later_edits()
+    {
+        let shared = SharedDocument::new(Document::new(ObjectValue::Number(1)));
+
+        let before = shared.read();
+        shared.update(|document| document.set("", ObjectValue::Number(2)));
+
+        assert_eq!(before.root(), &ObjectValue::Number(1));
+        assert_eq!(shared.read().root(), &ObjectValue::Number(2));
+    }
+}
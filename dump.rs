@@ -0,0 +1,307 @@
+use std::{
+    fmt,
+    str,
+    io::{self, Write, BufWriter}
+};
+
+use crate::parser::{ObjectValue, Object, ObjectField, DEFAULT_MAX_DEPTH};
+
+
+const MAGIC: &[u8] = b"CJDP";
+const VERSION: u8 = 2;
+
+const TAG_TEXT: u8 = 0;
+const TAG_NUMBER: u8 = 1;
+const TAG_FALSE: u8 = 2;
+const TAG_TRUE: u8 = 3;
+const TAG_LIST: u8 = 4;
+const TAG_OBJECT: u8 = 5;
+const TAG_SORTED_OBJECT: u8 = 6;
+const TAG_NULL: u8 = 7;
+const TAG_FLOAT: u8 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError
+{
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnexpectedEnd,
+    InvalidTag(u8),
+    InvalidLength,
+    InvalidUtf8,
+    // lists and objects nested deeper than the limit, loading recurses so this keeps the stack bounded
+    TooDeep(usize),
+    TrailingData(usize)
+}
+
+impl fmt::Display for LoadError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::BadMagic => write!(f, "not a dump file"),
+            Self::UnsupportedVersion(x) => write!(f, "unsupported dump version: {x}"),
+            Self::UnexpectedEnd => write!(f, "dump ended unexpectedly"),
+            Self::InvalidTag(x) => write!(f, "invalid value tag: {x}"),
+            Self::InvalidLength => write!(f, "length doesnt fit in memory"),
+            Self::InvalidUtf8 => write!(f, "text isnt valid utf8"),
+            Self::TooDeep(x) => write!(f, "nesting is deeper than the limit of {x}"),
+            Self::TrailingData(x) => write!(f, "{x} bytes left after the value")
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn write_length(writer: &mut impl Write, mut length: usize) -> io::Result<()>
+{
+    loop
+    {
+        let byte = (length & 0x7f) as u8;
+        length >>= 7;
+
+        if length == 0
+        {
+            return writer.write_all(&[byte]);
+        }
+
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn write_text(writer: &mut impl Write, text: &str) -> io::Result<()>
+{
+    write_length(writer, text.len())?;
+
+    writer.write_all(text.as_bytes())
+}
+
+fn dump_value(writer: &mut impl Write, value: &ObjectValue) -> io::Result<()>
+{
+    match value
+    {
+        ObjectValue::Null => writer.write_all(&[TAG_NULL]),
+        ObjectValue::Text(x) =>
+        {
+            writer.write_all(&[TAG_TEXT])?;
+            write_text(writer, x)
+        },
+        ObjectValue::Number(x) =>
+        {
+            writer.write_all(&[TAG_NUMBER])?;
+            writer.write_all(&x.to_le_bytes())
+        },
+        ObjectValue::Float(x) =>
+        {
+            writer.write_all(&[TAG_FLOAT])?;
+            writer.write_all(&x.to_le_bytes())
+        },
+        ObjectValue::Bool(x) => writer.write_all(&[if *x { TAG_TRUE } else { TAG_FALSE }]),
+        ObjectValue::List(list) =>
+        {
+            writer.write_all(&[TAG_LIST])?;
+            write_length(writer, list.len())?;
+
+            list.iter().try_for_each(|value| dump_value(writer, value))
+        },
+        ObjectValue::Object(object) =>
+        {
+            let tag = if object.is_sorted() { TAG_SORTED_OBJECT } else { TAG_OBJECT };
+
+            writer.write_all(&[tag])?;
+            write_length(writer, object.fields().len())?;
+
+            object.fields().iter().try_for_each(|field|
+            {
+                write_text(writer, field.key())?;
+                dump_value(writer, field.value())
+            })
+        }
+    }
+}
+
+struct Reader<'a>
+{
+    bytes: &'a [u8],
+    depth: usize,
+    max_depth: usize
+}
+
+impl<'a> Reader<'a>
+{
+    fn take(&mut self, amount: usize) -> Result<&'a [u8], LoadError>
+    {
+        if self.bytes.len() < amount
+        {
+            return Err(LoadError::UnexpectedEnd);
+        }
+
+        let (taken, rest) = self.bytes.split_at(amount);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, LoadError>
+    {
+        self.take(1).map(|x| x[0])
+    }
+
+    fn length(&mut self) -> Result<usize, LoadError>
+    {
+        let mut length: usize = 0;
+        let mut shift = 0;
+
+        loop
+        {
+            let byte = self.byte()?;
+
+            let part = usize::from(byte & 0x7f).checked_shl(shift)
+                .filter(|part| (part >> shift) == usize::from(byte & 0x7f))
+                .ok_or(LoadError::InvalidLength)?;
+
+            length |= part;
+            shift += 7;
+
+            if byte & 0x80 == 0
+            {
+                break;
+            }
+        }
+
+        // every element takes at least a byte so anything longer than the rest is corrupt
+        if length > self.bytes.len()
+        {
+            return Err(LoadError::UnexpectedEnd);
+        }
+
+        Ok(length)
+    }
+
+    fn text(&mut self) -> Result<String, LoadError>
+    {
+        let length = self.length()?;
+
+        str::from_utf8(self.take(length)?)
+            .map(ToOwned::to_owned)
+            .map_err(|_| LoadError::InvalidUtf8)
+    }
+
+    fn enter(&mut self) -> Result<(), LoadError>
+    {
+        if self.depth >= self.max_depth
+        {
+            return Err(LoadError::TooDeep(self.max_depth));
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<ObjectValue, LoadError>
+    {
+        let value = match self.byte()?
+        {
+            TAG_TEXT => ObjectValue::Text(self.text()?.into()),
+            TAG_NUMBER =>
+            {
+                let bytes = self.take(8)?.try_into().expect("took exactly 8 bytes");
+
+                ObjectValue::Number(i64::from_le_bytes(bytes))
+            },
+            TAG_FLOAT =>
+            {
+                let bytes = self.take(8)?.try_into().expect("took exactly 8 bytes");
+
+                ObjectValue::Float(f64::from_le_bytes(bytes))
+            },
+            TAG_FALSE => ObjectValue::Bool(false),
+            TAG_TRUE => ObjectValue::Bool(true),
+            TAG_NULL => ObjectValue::Null,
+            TAG_LIST =>
+            {
+                let length = self.length()?;
+
+                self.enter()?;
+                let values = (0..length).map(|_| self.value()).collect::<Result<Vec<_>, _>>()?;
+                self.depth -= 1;
+
+                ObjectValue::List(values.into())
+            },
+            tag @ (TAG_OBJECT | TAG_SORTED_OBJECT) =>
+            {
+                let length = self.length()?;
+
+                self.enter()?;
+                let fields = (0..length).map(|_|
+                {
+                    let key = self.text()?;
+
+                    Ok(ObjectField::new(key, self.value()?))
+                }).collect::<Result<Vec<_>, _>>()?;
+                self.depth -= 1;
+
+                let mut object = Object::from_fields(fields);
+
+                if tag == TAG_SORTED_OBJECT
+                {
+                    object.sort_keys();
+                }
+
+                ObjectValue::Object(object.into())
+            },
+            x => return Err(LoadError::InvalidTag(x))
+        };
+
+        Ok(value)
+    }
+}
+
+impl ObjectValue
+{
+    pub fn dump(&self, writer: impl Write) -> io::Result<()>
+    {
+        let mut writer = BufWriter::new(writer);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+
+        dump_value(&mut writer, self)?;
+
+        writer.flush()
+    }
+
+    // the bytes can come straight from a memory mapped file, nesting is limited
+    // the same as ParserOptions::max_depth is by default
+    pub fn load(bytes: &[u8]) -> Result<Self, LoadError>
+    {
+        Self::load_with_max_depth(bytes, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn load_with_max_depth(bytes: &[u8], max_depth: usize) -> Result<Self, LoadError>
+    {
+        let mut reader = Reader{bytes, depth: 0, max_depth};
+
+        if reader.take(MAGIC.len()).map_err(|_| LoadError::BadMagic)? != MAGIC
+        {
+            return Err(LoadError::BadMagic);
+        }
+
+        let version = reader.byte()?;
+        if version != VERSION
+        {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+
+        let value = reader.value()?;
+
+        if !reader.bytes.is_empty()
+        {
+            return Err(LoadError::TrailingData(reader.bytes.len()));
+        }
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use crate::parser::{ObjectValue, Object, ObjectField};
+
+
+// gets told about every value as the parser reaches it so nothing has to be kept around,
+// keys always come right before the value they belong to
+#[allow(unused_variables)]
+pub trait Handler
+{
+    fn on_object_start(&mut self) {}
+    fn on_object_end(&mut self) {}
+    fn on_list_start(&mut self) {}
+    fn on_list_end(&mut self) {}
+    fn on_key(&mut self, key: &str) {}
+    fn on_string(&mut self, value: &str) {}
+    fn on_number(&mut self, value: i64) {}
+    fn on_float(&mut self, value: f64) {}
+    fn on_bool(&mut self, value: bool) {}
+    fn on_null(&mut self) {}
+}
+
+enum Frame
+{
+    List(Vec<ObjectValue>),
+    Object{fields: Vec<ObjectField>, key: Option<Arc<str>>}
+}
+
+// the dom rebuilt from events, what Parser::parse gives but without spans or reports
+#[derive(Default)]
+pub struct ValueBuilder
+{
+    stack: Vec<Frame>,
+    root: Option<ObjectValue>
+}
+
+impl ValueBuilder
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    // none until a whole value was seen
+    pub fn finish(self) -> Option<ObjectValue>
+    {
+        self.root
+    }
+
+    fn push(&mut self, value: ObjectValue)
+    {
+        match self.stack.last_mut()
+        {
+            Some(Frame::List(values)) => values.push(value),
+            // the parser always sends a key first, a value without one has nowhere to go
+            Some(Frame::Object{fields, key}) =>
+            {
+                if let Some(key) = key.take()
+                {
+                    fields.push(ObjectField::new(key, value));
+                }
+            },
+            None => self.root = Some(value)
+        }
+    }
+}
+
+impl Handler for ValueBuilder
+{
+    fn on_object_start(&mut self)
+    {
+        self.stack.push(Frame::Object{fields: Vec::new(), key: None});
+    }
+
+    fn on_object_end(&mut self)
+    {
+        if let Some(Frame::Object{fields, ..}) = self.stack.pop()
+        {
+            self.push(ObjectValue::Object(Arc::new(Object::from_fields(fields))));
+        }
+    }
+
+    fn on_list_start(&mut self)
+    {
+        self.stack.push(Frame::List(Vec::new()));
+    }
+
+    fn on_list_end(&mut self)
+    {
+        if let Some(Frame::List(values)) = self.stack.pop()
+        {
+            self.push(ObjectValue::List(values.into()));
+        }
+    }
+
+    fn on_key(&mut self, key: &str)
+    {
+        if let Some(Frame::Object{key: pending, ..}) = self.stack.last_mut()
+        {
+            *pending = Some(key.into());
+        }
+    }
+
+    fn on_string(&mut self, value: &str)
+    {
+        self.push(ObjectValue::Text(value.into()));
+    }
+
+    fn on_number(&mut self, value: i64)
+    {
+        self.push(ObjectValue::Number(value));
+    }
+
+    fn on_float(&mut self, value: f64)
+    {
+        self.push(ObjectValue::Float(value));
+    }
+
+    fn on_bool(&mut self, value: bool)
+    {
+        self.push(ObjectValue::Bool(value));
+    }
+
+    fn on_null(&mut self)
+    {
+        self.push(ObjectValue::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+
+    #[test]
+    fn value_without_a_key_is_dropped()
+    {
+        let mut builder = ValueBuilder::new();
+
+        builder.on_object_start();
+        builder.on_string("x");
+        builder.on_key("a");
+        builder.on_number(1);
+        builder.on_object_end();
+
+        let mut object = Object::default();
+        object.insert("a", ObjectValue::Number(1));
+
+        assert_eq!(builder.finish(), Some(object.into()));
+    }
+}
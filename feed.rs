@@ -0,0 +1,347 @@
+#[cfg(feature = "async")]
+use std::io;
+
+use crate::parser::{Parser, ParserOptions, ParseError, ObjectValue};
+
+#[cfg(feature = "simd")]
+use crate::simd;
+
+#[cfg(feature = "async")]
+use crate::lexer::Position;
+
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comment
+{
+    Line,
+    Block
+}
+
+#[derive(Debug, Clone)]
+pub enum Status
+{
+    NeedMoreData,
+    Done(ObjectValue)
+}
+
+// takes the input in whatever chunks it arrives in, every byte only gets scanned once to find
+// where the document ends and then the whole document is parsed in one go
+#[derive(Debug, Default)]
+pub struct FeedParser
+{
+    options: ParserOptions,
+    buffer: Vec<u8>,
+    scanned: usize,
+    depth: usize,
+    started: bool,
+    in_text: bool,
+    // whichever quote the string started with
+    quote: u8,
+    escaped: bool,
+    // only with comments turned on, a slash that might start one
+    slash: bool,
+    comment: Option<Comment>,
+    star: bool
+}
+
+impl FeedParser
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    pub fn options(mut self, options: ParserOptions) -> Self
+    {
+        self.options = options;
+
+        self
+    }
+
+    // bytes past the end of a document stay buffered and start the next one, feeding
+    // an empty chunk gets at it when it was already complete
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Status, ParseError>
+    {
+        self.buffer.extend_from_slice(chunk);
+
+        match self.scan()
+        {
+            Some(end) => self.take(end).map(Status::Done),
+            // no point buffering more, the parser stops at the limit and says why
+            None if self.buffer.len() > self.options.max_document_size =>
+            {
+                let end = self.buffer.len();
+
+                self.take(end).map(Status::Done)
+            },
+            None => Ok(Status::NeedMoreData)
+        }
+    }
+
+    // a number or literal on its own can only be known to be over once the input ends
+    pub fn finish(mut self) -> Result<ObjectValue, ParseError>
+    {
+        let end = self.scan().unwrap_or(self.buffer.len());
+
+        self.take(end)
+    }
+
+    // bytes fed but not part of a finished document yet
+    pub fn buffered(&self) -> usize
+    {
+        self.buffer.len()
+    }
+
+    // for whoever reads the chunks, placed after everything buffered so far
+    #[cfg(feature = "async")]
+    pub(crate) fn read_error(&self, err: &io::Error) -> ParseError
+    {
+        let text = String::from_utf8_lossy(&self.buffer);
+
+        ParseError::io(err, self.buffer.len(), Position::from_offset(&text, text.len()))
+    }
+
+    fn take(&mut self, end: usize) -> Result<ObjectValue, ParseError>
+    {
+        let value = Parser::from_bytes(&self.buffer[..end]).options(self.options.clone()).parse();
+
+        self.buffer.drain(..end);
+
+        self.scanned = 0;
+        self.depth = 0;
+        self.started = false;
+        self.in_text = false;
+        self.escaped = false;
+        self.slash = false;
+        self.comment = None;
+        self.star = false;
+
+        value
+    }
+
+    // where the document ends if its over yet, picks up where the last call stopped
+    fn scan(&mut self) -> Option<usize>
+    {
+        while self.scanned < self.buffer.len()
+        {
+            #[cfg(feature = "simd")]
+            {
+                self.scanned += self.skippable();
+
+                if self.scanned == self.buffer.len()
+                {
+                    break;
+                }
+            }
+
+            let byte = self.buffer[self.scanned];
+            self.scanned += 1;
+
+            if self.in_text
+            {
+                match byte
+                {
+                    b'\\' => self.escaped = !self.escaped,
+                    byte if byte == self.quote && !self.escaped =>
+                    {
+                        self.in_text = false;
+
+                        if self.depth == 0
+                        {
+                            return Some(self.scanned);
+                        }
+                    },
+                    _ => self.escaped = false
+                }
+
+                continue;
+            }
+
+            if self.skip_comment(byte)
+            {
+                continue;
+            }
+
+            // the parser skips it too, but only right at the start
+            if !self.started && self.options.byte_order_mark && BYTE_ORDER_MARK.starts_with(&self.buffer[..self.scanned])
+            {
+                continue;
+            }
+
+            let whitespace = matches!(byte, b' ' | b'\t' | b'\n' | b'\r');
+            let quote = byte == b'"' || (byte == b'\'' && self.options.allows_single_quotes());
+            let delimiter = quote || matches!(byte, b'[' | b'{' | b',' | b']' | b'}') || (byte == b'/' && self.options.allows_comments());
+
+            if !self.started
+            {
+                self.started = !whitespace;
+            } else if self.depth == 0 && (whitespace || delimiter)
+            {
+                // a number or literal at the top level, it ended right before this byte
+                self.scanned -= 1;
+
+                return Some(self.scanned);
+            }
+
+            if quote
+            {
+                self.in_text = true;
+                self.quote = byte;
+
+                continue;
+            }
+
+            match byte
+            {
+                b'[' | b'{' => self.depth += 1,
+                b']' | b'}' =>
+                {
+                    self.depth = self.depth.saturating_sub(1);
+
+                    if self.depth == 0
+                    {
+                        return Some(self.scanned);
+                    }
+                },
+                _ => ()
+            }
+        }
+
+        None
+    }
+
+    // bytes that cant change anything about where the document ends, inside a string
+    // thats everything but the quote and escapes and inside a container its whatever isnt
+    // structural, comments and the top level still go a byte at a time
+    #[cfg(feature = "simd")]
+    fn skippable(&self) -> usize
+    {
+        let rest = &self.buffer[self.scanned..];
+
+        if self.in_text
+        {
+            if self.escaped
+            {
+                return 0;
+            }
+
+            return simd::plain_text_len(rest, self.quote);
+        }
+
+        if self.depth > 0 && self.comment.is_none() && !self.slash
+        {
+            return simd::unstructural_len(rest);
+        }
+
+        0
+    }
+
+    // true if the byte is part of a comment, only looked at outside of strings
+    fn skip_comment(&mut self, byte: u8) -> bool
+    {
+        if !self.options.allows_comments()
+        {
+            return false;
+        }
+
+        match self.comment
+        {
+            Some(Comment::Line) =>
+            {
+                if byte == b'\n'
+                {
+                    self.comment = None;
+                }
+
+                return true;
+            },
+            Some(Comment::Block) =>
+            {
+                if self.star && byte == b'/'
+                {
+                    self.comment = None;
+                }
+
+                self.star = byte == b'*';
+
+                return true;
+            },
+            None => ()
+        }
+
+        if self.slash
+        {
+            self.slash = false;
+
+            self.comment = match byte
+            {
+                b'/' => Some(Comment::Line),
+                b'*' => Some(Comment::Block),
+                _ => None
+            };
+
+            self.star = false;
+
+            if self.comment.is_some()
+            {
+                return true;
+            }
+        }
+
+        // a number or literal at the top level ends right before it, the scan takes care of that
+        if byte == b'/' && !(self.started && self.depth == 0)
+        {
+            self.slash = true;
+
+            return true;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+
+    fn comments() -> ParserOptions
+    {
+        ParserOptions::default().comments(true).trailing_content(false)
+    }
+
+    #[test]
+    fn comments_split_across_chunks()
+    {
+        let mut feeder = FeedParser::new().options(comments());
+
+        for chunk in ["/", "* ] */ [1", "/", "/ ]\n", ", 2"]
+        {
+            assert!(matches!(feeder.feed(chunk.as_bytes()), Ok(Status::NeedMoreData)), "{chunk}");
+        }
+
+        match feeder.feed(b"]")
+        {
+            Ok(Status::Done(value)) => assert_eq!(value, ObjectValue::from(vec![ObjectValue::from(1), ObjectValue::from(2)])),
+            status => panic!("{status:?}")
+        }
+    }
+
+    #[test]
+    fn unterminated_block_comment()
+    {
+        let mut feeder = FeedParser::new().options(comments());
+
+        for chunk in ["[1, /", "* a ]"]
+        {
+            assert!(matches!(feeder.feed(chunk.as_bytes()), Ok(Status::NeedMoreData)), "{chunk}");
+        }
+
+        let err = feeder.finish().unwrap_err();
+
+        assert_eq!((err.message.as_str(), err.offset), ("unterminated block comment", 4));
+    }
+}
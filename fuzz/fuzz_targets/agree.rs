@@ -0,0 +1,62 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use cringejsonparser::{Parser, ParserOptions, Dialect, ParseError, ObjectValue, writer};
+
+
+// every way of getting a tree out of the same text has to end up with the same tree or
+// the same error, and recovering from a valid document cant find anything wrong with it
+fuzz_target!(|data: &[u8]|
+{
+    let text = match std::str::from_utf8(data)
+    {
+        Ok(text) => text,
+        Err(_) => return
+    };
+
+    for options in [ParserOptions::strict(), ParserOptions::default().dialect(Dialect::Json5)]
+    {
+        let parsed = Parser::new(text.chars()).options(options.clone()).parse();
+
+        let from_bytes = Parser::from_bytes(text.as_bytes()).options(options.clone()).parse();
+        let from_reader = Parser::from_reader(text.as_bytes()).options(options.clone()).parse();
+
+        let borrowed = Parser::new(text.chars()).options(options.clone()).parse_borrowed()
+            .map(|value| value.into_owned());
+
+        let raw = Parser::new(text.chars()).options(options.clone()).raw_below(1).parse_borrowed()
+            .map(|value| value.into_owned());
+
+        let tape = Parser::new(text.chars()).options(options.clone()).parse_tape()
+            .map(|tape| tape.root().to_value());
+
+        let recovered = Parser::new(text.chars()).options(options.clone()).parse_recovering()
+            .expect("theres no reader or cancel token to fail");
+
+        // readers only keep track of lines close to where theyre reading so errors
+        // are compared by where they are in bytes instead
+        let compact = |result: &Result<ObjectValue, ParseError>|
+        {
+            result.as_ref().map(writer::to_compact).map_err(|err| (err.message.clone(), err.expected.clone(), err.offset))
+        };
+
+        let expected = compact(&parsed);
+
+        assert_eq!(compact(&from_bytes), expected);
+        assert_eq!(compact(&from_reader), expected);
+        assert_eq!(compact(&borrowed), expected);
+        assert_eq!(compact(&raw), expected);
+        assert_eq!(compact(&tape), expected);
+
+        match parsed
+        {
+            Ok(value) =>
+            {
+                assert!(recovered.errors.is_empty(), "{:?}", recovered.errors);
+                assert_eq!(recovered.value.map(|value| writer::to_compact(&value)), Some(writer::to_compact(&value)));
+            },
+            Err(err) => assert!(!recovered.errors.is_empty(), "recovering missed {err}")
+        }
+    }
+});
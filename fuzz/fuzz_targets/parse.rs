@@ -0,0 +1,81 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use cringejsonparser::{Parser, ParserOptions, Dialect, DuplicateKeys, writer};
+
+
+// nothing here checks what comes out, only that every way of parsing returns instead of
+// panicking no matter what the bytes are, the first byte picks the options so one corpus
+// goes through every dialect and limit
+fuzz_target!(|data: &[u8]|
+{
+    let (options, data) = match data.split_first()
+    {
+        Some((byte, rest)) => (options(*byte), rest),
+        None => return
+    };
+
+    let _ = Parser::from_bytes(data).options(options.clone()).parse();
+    let _ = Parser::from_reader(data).options(options.clone()).parse_recovering();
+
+    let text = match std::str::from_utf8(data)
+    {
+        Ok(text) => text,
+        Err(_) => return
+    };
+
+    // json5 lets nan and infinity into the tree, writing has to cope with them too
+    if let Ok(value) = Parser::new(text.chars()).options(options.clone()).parse()
+    {
+        let _ = writer::to_canonical(&value);
+    }
+
+    let _ = Parser::new(text.chars()).options(options.clone()).sorted_keys(true).parse_spanned();
+    let _ = Parser::new(text.chars()).options(options.clone()).parse_reported();
+    let _ = Parser::new(text.chars()).options(options.clone()).raw_below(1).parse_borrowed();
+    let _ = Parser::new(text.chars()).options(options.clone()).parse_recovering();
+    let _ = Parser::new(text.chars()).options(options.clone()).parse_tape();
+    let _ = Parser::new(text.chars()).options(options.clone()).parse_deduplicated();
+
+    for token in Parser::new(text.chars()).options(options.clone()).tokenize()
+    {
+        if token.is_err()
+        {
+            break;
+        }
+    }
+
+    for document in Parser::new(text.chars()).options(options).parse_stream()
+    {
+        if document.is_err()
+        {
+            break;
+        }
+    }
+});
+
+// small limits are the interesting ones, theyre where the parser stops in the middle of things
+fn options(byte: u8) -> ParserOptions
+{
+    let options = match byte % 3
+    {
+        0 => ParserOptions::default(),
+        1 => ParserOptions::strict(),
+        _ => ParserOptions::default().dialect(Dialect::Json5)
+    };
+
+    let limit = 1 + (byte >> 5) as usize;
+
+    match (byte >> 2) % 8
+    {
+        0 => options.max_depth(limit),
+        1 => options.max_document_size(limit * 4),
+        2 => options.max_string_length(limit),
+        3 => options.max_elements(limit),
+        4 => options.max_fields(limit),
+        5 => options.duplicate_keys(DuplicateKeys::Error),
+        6 => options.lossy_utf8(true),
+        _ => options
+    }
+}
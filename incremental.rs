@@ -0,0 +1,351 @@
+use std::{
+    fmt,
+    ops::Range
+};
+
+use crate::{
+    parser::{self, Parser, ParserOptions, ParseError, ObjectValue, SpanTree},
+    writer
+};
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError
+{
+    // theres nothing at the pointer or nowhere to add it
+    NotFound(String),
+    // the edited text didnt parse, like going over a limit, the text is left like it was
+    Invalid(ParseError),
+    // the range to edit goes backwards or past the end of the text
+    OutOfBounds(Range<usize>),
+    // the range would cut a character in half, like with offsets counted in utf16
+    NotCharBoundary(Range<usize>)
+}
+
+impl fmt::Display for EditError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::NotFound(pointer) => write!(f, "nothing at {pointer:?}"),
+            Self::Invalid(err) => write!(f, "{err}"),
+            Self::OutOfBounds(range) => write!(f, "edit range {range:?} is out of bounds"),
+            Self::NotCharBoundary(range) => write!(f, "edit range {range:?} isnt on character boundaries")
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+// keeps the source text and spans of the last parse around so an edit only
+// reparses the innermost list or object that fully contains it
+#[derive(Debug, Clone)]
+pub struct IncrementalDocument
+{
+    text: String,
+    value: ObjectValue,
+    spans: SpanTree,
+    options: ParserOptions
+}
+
+impl IncrementalDocument
+{
+    pub fn parse(text: String) -> Result<Self, ParseError>
+    {
+        Self::parse_with_options(text, ParserOptions::default())
+    }
+
+    // edits get reparsed with the same options
+    pub fn parse_with_options(text: String, options: ParserOptions) -> Result<Self, ParseError>
+    {
+        let (value, spans) = Parser::new(text.chars()).options(options.clone()).parse_spanned()?;
+
+        Ok(Self{text, value, spans, options})
+    }
+
+    pub fn text(&self) -> &str
+    {
+        &self.text
+    }
+
+    pub fn value(&self) -> &ObjectValue
+    {
+        &self.value
+    }
+
+    pub fn spans(&self) -> &SpanTree
+    {
+        &self.spans
+    }
+
+    // returns the amount of bytes that had to be reparsed, an edit that breaks the document is undone
+    pub fn edit(&mut self, range: Range<usize>, replacement: &str) -> Result<usize, EditError>
+    {
+        if range.start > range.end || range.end > self.text.len()
+        {
+            return Err(EditError::OutOfBounds(range));
+        }
+
+        if !self.text.is_char_boundary(range.start) || !self.text.is_char_boundary(range.end)
+        {
+            return Err(EditError::NotCharBoundary(range));
+        }
+
+        let path = self.affected_path(&range);
+
+        let removed = self.text[range.clone()].to_owned();
+        self.text.replace_range(range.clone(), replacement);
+
+        let delta = replacement.len() as isize - range.len() as isize;
+
+        if let Some(path) = path
+        {
+            let old_span = path.iter().fold(&self.spans, |node, id| &node.children[*id]).span.clone();
+
+            let start = old_span.start;
+            let end = old_span.end.wrapping_add_signed(delta);
+
+            let reparsed = parser::parse_spanned_at(&self.text[start..end], start, &self.options).ok()
+                .filter(|(_, _, consumed)| *consumed == end);
+
+            // the edit could have closed the container early or broken it, then only a full parse will do
+            if let Some((value, spans, _)) = reparsed
+            {
+                let target = path.iter().try_fold(&mut self.value, |value, id| value.child_make_mut(*id))
+                    .expect("spans must match the values");
+
+                *target = value;
+
+                patch_spans(&mut self.spans, &path, spans, delta);
+
+                return Ok(end - start);
+            }
+        }
+
+        match Parser::new(self.text.chars()).options(self.options.clone()).parse_spanned()
+        {
+            Ok((value, spans)) =>
+            {
+                self.value = value;
+                self.spans = spans;
+
+                Ok(self.text.len())
+            },
+            Err(err) =>
+            {
+                self.text.replace_range(range.start..range.start + replacement.len(), &removed);
+
+                Err(EditError::Invalid(err))
+            }
+        }
+    }
+
+    // replaces whats at the pointer or adds it as the last field or element of its parent (- or
+    // the length for lists), only those bytes change so whitespace, key order and comments
+    // stay as they were, new fields copy the indentation of the ones before them
+    pub fn set(&mut self, pointer: &str, value: &ObjectValue) -> Result<(), EditError>
+    {
+        let written = writer::to_compact(value);
+
+        if let Some(path) = self.path(pointer)
+        {
+            let span = self.node(&path).span.clone();
+
+            return self.apply(span, &written);
+        }
+
+        let not_found = || EditError::NotFound(pointer.to_owned());
+
+        let (parent, token) = parser::split_pointer(pointer).ok_or_else(not_found)?;
+        let path = self.path(parent).ok_or_else(not_found)?;
+
+        let node = self.node(&path);
+
+        let entry = match self.value.pointer(parent)
+        {
+            Some(ObjectValue::Object(_)) =>
+            {
+                // the same colon and spacing as the field before it
+                let colon = node.children.last().and_then(|last| Some(&self.text[last.key.as_ref()?.end..last.span.start]));
+
+                let mut entry = String::new();
+                writer::write_text(&mut entry, &token);
+                entry.push_str(colon.unwrap_or(": "));
+                entry.push_str(&written);
+
+                entry
+            },
+            Some(ObjectValue::List(list)) if token == "-" || token.parse() == Ok(list.len()) => written,
+            _ => return Err(not_found())
+        };
+
+        let (range, replacement) = match node.children.len()
+        {
+            0 => (node.span.start + 1..node.span.end - 1, entry),
+            amount =>
+            {
+                let last = &node.children[amount - 1];
+
+                // whatever is between the comma before the last one and it, or the bracket for the first one
+                let gap = if amount == 1
+                {
+                    &self.text[node.span.start + 1..item_start(last)]
+                } else
+                {
+                    let gap = &self.text[node.children[amount - 2].span.end..item_start(last)];
+
+                    gap.split_once(',').map_or(gap, |(_, after)| after)
+                };
+
+                (last.span.end..last.span.end, format!(",{}{entry}", leading_whitespace(gap)))
+            }
+        };
+
+        self.apply(range, &replacement)
+    }
+
+    // takes out the field or element along with the comma that went with it
+    pub fn remove(&mut self, pointer: &str) -> Result<ObjectValue, EditError>
+    {
+        let not_found = || EditError::NotFound(pointer.to_owned());
+
+        let removed = self.value.pointer(pointer).filter(|_| !pointer.is_empty()).cloned().ok_or_else(not_found)?;
+
+        let mut path = self.path(pointer).ok_or_else(not_found)?;
+        let id = path.pop().expect("the root cant be removed");
+
+        let node = self.node(&path);
+        let children = &node.children;
+
+        let range = if children.len() == 1
+        {
+            node.span.start + 1..node.span.end - 1
+        } else if id + 1 < children.len()
+        {
+            item_start(&children[id])..item_start(&children[id + 1])
+        } else
+        {
+            children[id - 1].span.end..children[id].span.end
+        };
+
+        self.apply(range, "")?;
+
+        Ok(removed)
+    }
+
+    fn apply(&mut self, range: Range<usize>, replacement: &str) -> Result<(), EditError>
+    {
+        self.edit(range, replacement).map(|_| ())
+    }
+
+    // child ids down to the value at the pointer, duplicate keys go to the first one like get does
+    fn path(&self, pointer: &str) -> Option<Vec<usize>>
+    {
+        if pointer.is_empty()
+        {
+            return Some(Vec::new());
+        }
+
+        let mut path = Vec::new();
+
+        parser::pointer_tokens(pointer)?.try_fold(&self.value, |value, token|
+        {
+            let (id, child) = match value
+            {
+                ObjectValue::List(list) =>
+                {
+                    let id = token.parse::<usize>().ok()?;
+
+                    (id, list.get(id)?)
+                },
+                ObjectValue::Object(object) =>
+                {
+                    let id = object.fields().iter().position(|field| field.key() == token)?;
+
+                    (id, object.fields()[id].value())
+                },
+                _ => return None
+            };
+
+            path.push(id);
+
+            Some(child)
+        })?;
+
+        Some(path)
+    }
+
+    fn node(&self, path: &[usize]) -> &SpanTree
+    {
+        path.iter().fold(&self.spans, |node, id| &node.children[*id])
+    }
+
+    fn is_container(&self, node: &SpanTree) -> bool
+    {
+        matches!(self.text.as_bytes().get(node.span.start), Some(b'[' | b'{'))
+    }
+
+    // child indices down to the deepest non root container whose brackets the edit doesnt touch
+    fn affected_path(&self, range: &Range<usize>) -> Option<Vec<usize>>
+    {
+        let inside = |node: &SpanTree| node.span.start < range.start && range.end < node.span.end;
+
+        let mut path = Vec::new();
+        let mut node = &self.spans;
+
+        while let Some((id, child)) = node.children.iter().enumerate().find(|(_, child)|
+        {
+            inside(child) && self.is_container(child)
+        })
+        {
+            path.push(id);
+            node = child;
+        }
+
+        (!path.is_empty()).then_some(path)
+    }
+}
+
+// fields start at their key
+fn item_start(node: &SpanTree) -> usize
+{
+    node.key.as_ref().map_or(node.span.start, |key| key.start)
+}
+
+// the line break and indentation in front of an item without any comments around it
+fn leading_whitespace(gap: &str) -> String
+{
+    match gap.rfind('\n')
+    {
+        Some(id) =>
+        {
+            let indentation: String = gap[id + 1..].chars().take_while(|c| c.is_whitespace()).collect();
+
+            format!("\n{indentation}")
+        },
+        None if gap.is_empty() => String::new(),
+        None => " ".to_owned()
+    }
+}
+
+fn patch_spans(node: &mut SpanTree, path: &[usize], replacement: SpanTree, delta: isize)
+{
+    match path.split_first()
+    {
+        Some((id, rest)) =>
+        {
+            node.span.end = node.span.end.wrapping_add_signed(delta);
+            node.children[id + 1..].iter_mut().for_each(|child| child.shift(delta));
+
+            patch_spans(&mut node.children[*id], rest, replacement, delta);
+        },
+        None =>
+        {
+            let key = node.key.take();
+
+            *node = replacement;
+            node.key = key;
+        }
+    }
+}
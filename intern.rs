@@ -0,0 +1,83 @@
+use std::{
+    sync::{Arc, RwLock},
+    hash::{BuildHasher, RandomState},
+    collections::HashSet
+};
+
+
+const SHARDS: usize = 16;
+
+// keys and short string values shared between any number of parsers, even on
+// different threads, entries live as long as the interner
+pub struct SharedInterner
+{
+    shards: Box<[RwLock<HashSet<Arc<str>>>]>,
+    hasher: RandomState,
+    max_value_len: usize
+}
+
+impl Default for SharedInterner
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl SharedInterner
+{
+    pub fn new() -> Self
+    {
+        Self{
+            shards: (0..SHARDS).map(|_| RwLock::new(HashSet::new())).collect(),
+            hasher: RandomState::new(),
+            max_value_len: 32
+        }
+    }
+
+    // string values longer than this are rarely repeated so they dont get interned, keys always are
+    pub fn with_max_value_len(mut self, max_value_len: usize) -> Self
+    {
+        self.max_value_len = max_value_len;
+
+        self
+    }
+
+    pub fn max_value_len(&self) -> usize
+    {
+        self.max_value_len
+    }
+
+    pub fn intern(&self, text: &str) -> Arc<str>
+    {
+        let shard = &self.shards[self.hasher.hash_one(text) as usize % SHARDS];
+
+        if let Some(found) = shard.read().unwrap_or_else(|err| err.into_inner()).get(text)
+        {
+            return found.clone();
+        }
+
+        let mut strings = shard.write().unwrap_or_else(|err| err.into_inner());
+
+        // another thread might have added it while we were waiting for the lock
+        if let Some(found) = strings.get(text)
+        {
+            return found.clone();
+        }
+
+        let interned: Arc<str> = Arc::from(text);
+        strings.insert(interned.clone());
+
+        interned
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.shards.iter().map(|shard| shard.read().unwrap_or_else(|err| err.into_inner()).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+}
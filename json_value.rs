@@ -0,0 +1,108 @@
+use serde_json::{Value, Number, Map};
+
+use crate::parser::{ObjectValue, Object, ObjectField};
+
+
+// for codebases that already pass serde_json::Value around, the trees convert both ways
+// without going through text, numbers that serde_json only has as a u64 become floats
+// like they would when parsed here
+impl From<Value> for ObjectValue
+{
+    fn from(value: Value) -> Self
+    {
+        match value
+        {
+            Value::Null => Self::Null,
+            Value::Bool(x) => Self::Bool(x),
+            Value::Number(x) => match (x.as_i64(), x.as_u64())
+            {
+                (Some(x), _) => Self::Number(x),
+                (None, Some(x)) => x.into(),
+                (None, None) => Self::Float(x.as_f64().unwrap_or(f64::NAN))
+            },
+            Value::String(text) => text.into(),
+            Value::Array(values) => values.into_iter().map(Self::from).collect::<Vec<_>>().into(),
+            Value::Object(fields) =>
+            {
+                let fields = fields.into_iter().map(|(key, value)| ObjectField::new(key, value.into())).collect();
+
+                Object::from_fields(fields).into()
+            }
+        }
+    }
+}
+
+// serde_json has no nan or infinity so they turn into null like the writer does,
+// with duplicate keys the last one wins
+impl From<&ObjectValue> for Value
+{
+    fn from(value: &ObjectValue) -> Self
+    {
+        match value
+        {
+            ObjectValue::Null => Self::Null,
+            ObjectValue::Bool(x) => Self::Bool(*x),
+            ObjectValue::Number(x) => Self::Number(Number::from(*x)),
+            ObjectValue::Float(x) => Number::from_f64(*x).map(Self::Number).unwrap_or(Self::Null),
+            ObjectValue::Text(text) => Self::String(text.to_string()),
+            ObjectValue::List(values) => Self::Array(values.iter().map(Self::from).collect()),
+            ObjectValue::Object(object) =>
+            {
+                let mut fields = Map::new();
+
+                object.fields().iter().for_each(|field|
+                {
+                    fields.insert(field.key().to_owned(), field.value().into());
+                });
+
+                Self::Object(fields)
+            }
+        }
+    }
+}
+
+impl From<ObjectValue> for Value
+{
+    fn from(value: ObjectValue) -> Self
+    {
+        Self::from(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use crate::Parser;
+
+
+    fn parse(text: &str) -> ObjectValue
+    {
+        Parser::new(text.chars()).parse().unwrap()
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let text = r#"{"a": [1, -2, 2.5, "x", null, true], "b": {"c": {}}}"#;
+
+        let value: Value = serde_json::from_str(text).unwrap();
+
+        assert_eq!(ObjectValue::from(value.clone()), parse(text));
+        assert_eq!(Value::from(parse(text)), value);
+    }
+
+    #[test]
+    fn numbers()
+    {
+        assert_eq!(ObjectValue::from(serde_json::json!(u64::MAX)), ObjectValue::Float(u64::MAX as f64));
+        assert_eq!(Value::from(ObjectValue::Float(f64::NAN)), Value::Null);
+    }
+
+    #[test]
+    fn last_duplicate_wins()
+    {
+        assert_eq!(Value::from(parse(r#"{"a": 1, "a": 2}"#)), serde_json::json!({"a": 2}));
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    fmt,
+    ops::Range,
+    iter::Peekable,
+    str::CharIndices
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind
+{
+    BeginObject,
+    EndObject,
+    BeginList,
+    EndList,
+    Colon,
+    Comma,
+    Text,
+    Number,
+    Literal,
+    Comment,
+    Unknown
+}
+
+impl fmt::Display for TokenKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let name = match self
+        {
+            Self::BeginObject => "begin-object",
+            Self::EndObject => "end-object",
+            Self::BeginList => "begin-array",
+            Self::EndList => "end-array",
+            Self::Colon => "colon",
+            Self::Comma => "comma",
+            Self::Text => "string",
+            Self::Number => "number",
+            Self::Literal => "literal",
+            Self::Comment => "comment",
+            Self::Unknown => "unknown"
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a>
+{
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Range<usize>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position
+{
+    pub line: usize,
+    pub column: usize
+}
+
+impl Position
+{
+    // both start at 1, columns count characters not bytes, an offset inside of a character
+    // or past the end counts as the character its in or the end
+    pub fn from_offset(text: &str, offset: usize) -> Self
+    {
+        let mut offset = offset.min(text.len());
+
+        while !text.is_char_boundary(offset)
+        {
+            offset -= 1;
+        }
+
+        let before = &text[..offset];
+
+        let line_start = before.rfind('\n').map(|id| id + 1).unwrap_or(0);
+
+        Self{
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1
+        }
+    }
+}
+
+impl fmt::Display for Position
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+// splits text into tokens without checking how they fit together, anything it
+// doesnt recognize becomes an unknown token so it never gives up on broken input
+pub struct Lexer<'a>
+{
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>
+}
+
+impl<'a> Lexer<'a>
+{
+    pub fn new(text: &'a str) -> Self
+    {
+        Self{text, chars: text.char_indices().peekable()}
+    }
+
+    fn offset(&mut self) -> usize
+    {
+        self.chars.peek().map(|(id, _)| *id).unwrap_or(self.text.len())
+    }
+
+    fn skip_while(&mut self, mut predicate: impl FnMut(char) -> bool)
+    {
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+    }
+
+    fn skip_text(&mut self, quote: char)
+    {
+        let mut escaped = false;
+
+        for (_, c) in self.chars.by_ref()
+        {
+            match c
+            {
+                c if c == quote && !escaped => return,
+                '\\' => escaped = !escaped,
+                _ => escaped = false
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a>
+{
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.skip_while(char::is_whitespace);
+
+        let (start, c) = self.chars.next()?;
+
+        let kind = match c
+        {
+            '{' => TokenKind::BeginObject,
+            '}' => TokenKind::EndObject,
+            '[' => TokenKind::BeginList,
+            ']' => TokenKind::EndList,
+            ':' => TokenKind::Colon,
+            ',' => TokenKind::Comma,
+            // single quotes and the rest of json5 get lexed too, its up to whoever checks the tokens
+            '"' | '\'' =>
+            {
+                self.skip_text(c);
+
+                TokenKind::Text
+            },
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') =>
+            {
+                self.skip_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'));
+
+                TokenKind::Number
+            },
+            c if c.is_alphabetic() || matches!(c, '$' | '_') =>
+            {
+                self.skip_while(|c| c.is_alphanumeric() || matches!(c, '$' | '_'));
+
+                TokenKind::Literal
+            },
+            '/' if self.chars.next_if(|(_, c)| *c == '/').is_some() =>
+            {
+                self.skip_while(|c| c != '\n');
+
+                TokenKind::Comment
+            },
+            // one that never ends takes the rest of the text but isnt a comment
+            '/' if self.chars.next_if(|(_, c)| *c == '*').is_some() =>
+            {
+                let mut kind = TokenKind::Unknown;
+
+                let mut previous = ' ';
+                for (_, c) in self.chars.by_ref()
+                {
+                    if previous == '*' && c == '/'
+                    {
+                        kind = TokenKind::Comment;
+
+                        break;
+                    }
+
+                    previous = c;
+                }
+
+                kind
+            },
+            _ => TokenKind::Unknown
+        };
+
+        let end = self.offset();
+
+        Some(Token{kind, text: &self.text[start..end], span: start..end})
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category
+{
+    Key,
+    Text,
+    Number,
+    Literal,
+    Punctuation,
+    Comment,
+    Invalid
+}
+
+// tokens tagged with what they mean for highlighting, keys are told apart from
+// string values by the colon after them
+pub struct Classifier<'a>
+{
+    tokens: Peekable<Lexer<'a>>
+}
+
+impl<'a> Classifier<'a>
+{
+    pub fn new(text: &'a str) -> Self
+    {
+        Self{tokens: Lexer::new(text).peekable()}
+    }
+}
+
+impl<'a> Iterator for Classifier<'a>
+{
+    type Item = (Category, Token<'a>);
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let token = self.tokens.next()?;
+
+        let category = match token.kind
+        {
+            TokenKind::BeginObject
+                | TokenKind::EndObject
+                | TokenKind::BeginList
+                | TokenKind::EndList
+                | TokenKind::Colon
+                | TokenKind::Comma => Category::Punctuation,
+            TokenKind::Text =>
+            {
+                let is_key = self.tokens.peek().map(|next| next.kind == TokenKind::Colon).unwrap_or(false);
+
+                if is_key { Category::Key } else { Category::Text }
+            },
+            TokenKind::Number => Category::Number,
+            TokenKind::Literal =>
+            {
+                let known = ["true", "false", "null"].iter().any(|name| token.text.eq_ignore_ascii_case(name));
+
+                if known { Category::Literal } else { Category::Invalid }
+            },
+            TokenKind::Comment => Category::Comment,
+            TokenKind::Unknown => Category::Invalid
+        };
+
+        Some((category, token))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+
+    fn kinds(text: &str) -> Vec<TokenKind>
+    {
+        Lexer::new(text).map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn comments()
+    {
+        assert_eq!(kinds("// a\n/* b */1"), [TokenKind::Comment, TokenKind::Comment, TokenKind::Number]);
+    }
+
+    #[test]
+    fn unterminated_block_comment()
+    {
+        assert_eq!(kinds("1 /* a"), [TokenKind::Number, TokenKind::Unknown]);
+        assert_eq!(kinds("/*/"), [TokenKind::Unknown]);
+    }
+}
@@ -0,0 +1,41 @@
+pub mod parser;
+pub mod borrowed;
+pub mod builder;
+pub mod convert;
+pub mod dedup;
+pub mod document;
+pub mod dump;
+pub mod events;
+pub mod feed;
+pub mod incremental;
+pub mod intern;
+pub mod lexer;
+mod macros;
+pub mod query;
+mod reader;
+pub mod redact;
+pub mod stream;
+pub mod tape;
+pub mod tokenizer;
+pub mod transform;
+pub mod validate;
+pub mod writer;
+#[cfg(feature = "verify")]
+pub mod verify;
+#[cfg(feature = "async")]
+pub mod async_reader;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "serde_json")]
+mod json_value;
+
+pub use parser::{Parser, ParserOptions, Dialect, DuplicateKeys, MergeStrategy, ParseError, TypeError, ObjectValue, Object, ObjectField, Entry};
+pub use borrowed::{BorrowedValue, RawValue};
+pub use builder::{ObjectBuilder, ListBuilder};
+pub use convert::{ToJson, FromJson, FromJsonError};
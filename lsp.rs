@@ -0,0 +1,383 @@
+use std::{
+    io::{self, Write, BufRead, BufReader},
+    collections::HashMap
+};
+
+use cringejsonparser::{
+    writer::{self, WriteOptions, write_text},
+    parser::{Parser, ParserOptions, ObjectValue, SpanTree},
+    validate::{self, Severity}
+};
+
+
+// lsp positions count utf16 code units, not bytes or chars
+fn lsp_position(text: &str, offset: usize) -> String
+{
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|id| id + 1).unwrap_or(0);
+
+    let line = before.matches('\n').count();
+    let character = before[line_start..].encode_utf16().count();
+
+    format!("{{\"line\":{line},\"character\":{character}}}")
+}
+
+fn lsp_range(text: &str, start: usize, end: usize) -> String
+{
+    format!("{{\"start\":{},\"end\":{}}}", lsp_position(text, start), lsp_position(text, end))
+}
+
+fn lsp_offset(text: &str, line: u64, character: u64) -> usize
+{
+    let line_start = if line == 0
+    {
+        0
+    } else
+    {
+        match text.match_indices('\n').nth(line as usize - 1)
+        {
+            Some((id, _)) => id + 1,
+            None => return text.len()
+        }
+    };
+
+    let mut units = 0;
+    for (id, c) in text[line_start..].char_indices()
+    {
+        if units >= character || c == '\n'
+        {
+            return line_start + id;
+        }
+
+        units += c.len_utf16() as u64;
+    }
+
+    text.len()
+}
+
+fn quoted(text: &str) -> String
+{
+    let mut out = String::new();
+    write_text(&mut out, text);
+
+    out
+}
+
+// only well formed documents get symbols, formatting and hovers, the diagnostics cover the rest
+fn parse_valid(text: &str) -> Option<(ObjectValue, SpanTree)>
+{
+    validate::validate(text, &ParserOptions::default()).ok()?;
+
+    Parser::new(text.chars()).parse_spanned().ok()
+}
+
+fn unsigned(value: &ObjectValue) -> Option<u64>
+{
+    value.get_number().and_then(|x| u64::try_from(x).ok())
+}
+
+fn symbol_kind(value: &ObjectValue) -> u32
+{
+    match value
+    {
+        ObjectValue::Null => 21,
+        ObjectValue::Text(_) => 15,
+        ObjectValue::Number(_) | ObjectValue::Float(_) => 16,
+        ObjectValue::Bool(_) => 17,
+        ObjectValue::List(_) => 18,
+        ObjectValue::Object(_) => 19
+    }
+}
+
+fn symbols(text: &str, value: &ObjectValue, spans: &SpanTree) -> String
+{
+    let children: Vec<(String, &ObjectValue)> = match value
+    {
+        ObjectValue::List(list) => list.iter().enumerate().map(|(id, value)| (id.to_string(), value)).collect(),
+        ObjectValue::Object(object) =>
+        {
+            object.fields().iter().map(|field| (field.key().to_owned(), field.value())).collect()
+        },
+        _ => Vec::new()
+    };
+
+    let symbols: Vec<String> = children.into_iter().zip(spans.children.iter()).map(|((name, value), spans)|
+    {
+        let selection = spans.key.clone().unwrap_or_else(|| spans.span.clone());
+        let start = selection.start.min(spans.span.start);
+
+        format!(
+            "{{\"name\":{},\"kind\":{},\"range\":{},\"selectionRange\":{},\"children\":{}}}",
+            quoted(&name),
+            symbol_kind(value),
+            lsp_range(text, start, spans.span.end),
+            lsp_range(text, selection.start, selection.end),
+            symbols(text, value, spans)
+        )
+    }).collect();
+
+    format!("[{}]", symbols.join(","))
+}
+
+// pointer of the deepest value or key under the offset
+fn pointer_at(value: &ObjectValue, spans: &SpanTree, offset: usize) -> Option<String>
+{
+    let mut found = None;
+    value.walk_spanned(spans, |pointer, _, spans|
+    {
+        let in_key = spans.key.as_ref().map(|key| key.contains(&offset)).unwrap_or(false);
+
+        if in_key || spans.span.contains(&offset)
+        {
+            found = Some(pointer.to_owned());
+        }
+    });
+
+    found
+}
+
+struct Server
+{
+    documents: HashMap<String, String>,
+    output: io::Stdout
+}
+
+impl Server
+{
+    fn send(&mut self, body: &str) -> io::Result<()>
+    {
+        let mut output = self.output.lock();
+
+        write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+
+        output.flush()
+    }
+
+    fn respond(&mut self, id: &ObjectValue, result: &str) -> io::Result<()>
+    {
+        self.send(&format!("{{\"jsonrpc\":\"2.0\",\"id\":{id},\"result\":{result}}}"))
+    }
+
+    fn notify(&mut self, method: &str, params: &str) -> io::Result<()>
+    {
+        self.send(&format!("{{\"jsonrpc\":\"2.0\",\"method\":\"{method}\",\"params\":{params}}}"))
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> io::Result<()>
+    {
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or_default();
+
+        let diagnostics: Vec<String> = validate::lint(text, &ParserOptions::default()).into_iter().map(|diagnostic|
+        {
+            let severity = match diagnostic.severity
+            {
+                Severity::Error => 1,
+                Severity::Warning => 2
+            };
+
+            let mut message = diagnostic.message;
+            if let Some(suggestion) = diagnostic.suggestion
+            {
+                message.push_str("\nhelp: ");
+                message.push_str(&suggestion);
+            }
+
+            format!(
+                "{{\"range\":{},\"severity\":{severity},\"source\":\"cringejson\",\"message\":{}}}",
+                lsp_range(text, diagnostic.span.start, diagnostic.span.end),
+                quoted(&message)
+            )
+        }).collect();
+
+        let params = format!("{{\"uri\":{},\"diagnostics\":[{}]}}", quoted(uri), diagnostics.join(","));
+
+        self.notify("textDocument/publishDiagnostics", &params)
+    }
+
+    fn document(&self, message: &ObjectValue) -> Option<&str>
+    {
+        let uri = message.pointer("/params/textDocument/uri")?.get_text()?;
+
+        self.documents.get(uri).map(String::as_str)
+    }
+
+    fn request(&mut self, method: &str, message: &ObjectValue) -> Option<String>
+    {
+        let result = match method
+        {
+            "initialize" =>
+            {
+                "{\"capabilities\":{\"textDocumentSync\":1,\"documentSymbolProvider\":true,\
+                    \"documentFormattingProvider\":true,\"hoverProvider\":true},\
+                    \"serverInfo\":{\"name\":\"cringejson\"}}".to_owned()
+            },
+            "shutdown" => "null".to_owned(),
+            "textDocument/documentSymbol" =>
+            {
+                self.document(message).and_then(|text|
+                {
+                    parse_valid(text).map(|(value, spans)| symbols(text, &value, &spans))
+                }).unwrap_or_else(|| "null".to_owned())
+            },
+            "textDocument/formatting" =>
+            {
+                let tab_size = message.pointer("/params/options/tabSize").and_then(unsigned).unwrap_or(4);
+                let spaces = message.pointer("/params/options/insertSpaces")
+                    .map(|value| !matches!(value, ObjectValue::Bool(false)))
+                    .unwrap_or(true);
+
+                let indent = if spaces { " ".repeat(tab_size as usize) } else { "\t".to_owned() };
+
+                self.document(message).and_then(|text|
+                {
+                    let (value, _) = parse_valid(text)?;
+
+                    let options = WriteOptions{indent: indent.clone(), ..Default::default()};
+                    let formatted = writer::to_pretty(&value, &options) + "\n";
+
+                    Some(format!("[{{\"range\":{},\"newText\":{}}}]", lsp_range(text, 0, text.len()), quoted(&formatted)))
+                }).unwrap_or_else(|| "null".to_owned())
+            },
+            "textDocument/hover" =>
+            {
+                let line = message.pointer("/params/position/line").and_then(unsigned).unwrap_or(0);
+                let character = message.pointer("/params/position/character").and_then(unsigned).unwrap_or(0);
+
+                self.document(message).and_then(|text|
+                {
+                    let (value, spans) = parse_valid(text)?;
+
+                    let pointer = pointer_at(&value, &spans, lsp_offset(text, line, character))?;
+                    let pointer = if pointer.is_empty() { "(root)".to_owned() } else { pointer };
+
+                    let contents = format!("{{\"kind\":\"markdown\",\"value\":{}}}", quoted(&format!("`{pointer}`")));
+
+                    Some(format!("{{\"contents\":{contents}}}"))
+                }).unwrap_or_else(|| "null".to_owned())
+            },
+            _ => return None
+        };
+
+        Some(result)
+    }
+
+    fn handle(&mut self, message: ObjectValue) -> io::Result<bool>
+    {
+        let method = message.pointer("/method").and_then(ObjectValue::get_text).unwrap_or_default().to_owned();
+
+        if let Some(id) = message.pointer("/id")
+        {
+            match self.request(&method, &message)
+            {
+                Some(result) => self.respond(id, &result)?,
+                None =>
+                {
+                    let error = format!("{{\"code\":-32601,\"message\":{}}}", quoted(&format!("{method} isnt supported")));
+
+                    self.send(&format!("{{\"jsonrpc\":\"2.0\",\"id\":{id},\"error\":{error}}}"))?;
+                }
+            }
+
+            return Ok(true);
+        }
+
+        let uri = message.pointer("/params/textDocument/uri").and_then(ObjectValue::get_text).map(str::to_owned);
+
+        match (method.as_str(), uri)
+        {
+            ("exit", _) => return Ok(false),
+            ("textDocument/didOpen", Some(uri)) =>
+            {
+                let text = message.pointer("/params/textDocument/text").and_then(ObjectValue::get_text).unwrap_or_default();
+
+                self.documents.insert(uri.clone(), text.to_owned());
+                self.publish_diagnostics(&uri)?;
+            },
+            ("textDocument/didChange", Some(uri)) =>
+            {
+                // full sync so the last change has the whole text
+                let text = message.pointer("/params/contentChanges").and_then(|changes|
+                {
+                    match changes
+                    {
+                        ObjectValue::List(changes) => changes.last()?.pointer("/text")?.get_text(),
+                        _ => None
+                    }
+                });
+
+                if let Some(text) = text
+                {
+                    self.documents.insert(uri.clone(), text.to_owned());
+                    self.publish_diagnostics(&uri)?;
+                }
+            },
+            ("textDocument/didClose", Some(uri)) =>
+            {
+                self.documents.remove(&uri);
+                self.publish_diagnostics(&uri)?;
+            },
+            _ => ()
+        }
+
+        Ok(true)
+    }
+}
+
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>>
+{
+    let mut length = None;
+
+    loop
+    {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0
+        {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty()
+        {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':')
+        {
+            if name.eq_ignore_ascii_case("content-length")
+            {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "message without a content-length"))?;
+
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+
+    String::from_utf8(body).map(Some).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+// minimal language server over stdio
+pub fn run() -> io::Result<()>
+{
+    let mut input = BufReader::new(io::stdin());
+
+    let mut server = Server{documents: HashMap::new(), output: io::stdout()};
+
+    while let Some(body) = read_message(&mut input)?
+    {
+        let message = match Parser::new(body.chars()).parse()
+        {
+            Ok(x) => x,
+            Err(_) => continue
+        };
+
+        if !server.handle(message)?
+        {
+            break;
+        }
+    }
+
+    Ok(())
+}
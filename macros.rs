@@ -0,0 +1,90 @@
+// builds an ObjectValue out of something that looks like json, lists, objects and null are
+// written like they would be in the text and anything else is an expression that gets turned
+// into a value with From, so variables can go anywhere a value can
+//
+//     let name = "main";
+//     let symbol = json!({"name": name, "vaddr": 0x401000, "sections": [".text", null]});
+//
+// keys are anything that turns into an Arc<str>, a string literal, a variable or an
+// expression in parentheses, a key thats there twice keeps the last value like insert does
+#[macro_export]
+macro_rules! json
+{
+    (null) =>
+    {
+        $crate::ObjectValue::Null
+    };
+    ([$($tokens:tt)*]) =>
+    {
+        $crate::ObjectValue::List(::std::sync::Arc::new($crate::json_internal!(@list [] $($tokens)*)))
+    };
+    ({$($tokens:tt)*}) =>
+    {{
+        let mut object = $crate::Object::default();
+        $crate::json_internal!(@object object $($tokens)*);
+
+        $crate::ObjectValue::Object(::std::sync::Arc::new(object))
+    }};
+    ($value:expr) =>
+    {
+        $crate::ObjectValue::from($value)
+    };
+}
+
+// goes through the tokens one value at a time, lists collect the values built so far in the
+// brackets and objects insert every field as soon as its whole
+#[doc(hidden)]
+#[macro_export]
+macro_rules! json_internal
+{
+    (@list [$($values:expr,)*]) =>
+    {
+        ::std::vec![$($values,)*]
+    };
+    (@list [$($values:expr,)*] null $(, $($rest:tt)*)?) =>
+    {
+        $crate::json_internal!(@list [$($values,)* $crate::json!(null),] $($($rest)*)?)
+    };
+    (@list [$($values:expr,)*] [$($list:tt)*] $(, $($rest:tt)*)?) =>
+    {
+        $crate::json_internal!(@list [$($values,)* $crate::json!([$($list)*]),] $($($rest)*)?)
+    };
+    (@list [$($values:expr,)*] {$($object:tt)*} $(, $($rest:tt)*)?) =>
+    {
+        $crate::json_internal!(@list [$($values,)* $crate::json!({$($object)*}),] $($($rest)*)?)
+    };
+    (@list [$($values:expr,)*] $value:expr, $($rest:tt)*) =>
+    {
+        $crate::json_internal!(@list [$($values,)* $crate::json!($value),] $($rest)*)
+    };
+    (@list [$($values:expr,)*] $value:expr) =>
+    {
+        $crate::json_internal!(@list [$($values,)* $crate::json!($value),])
+    };
+
+    (@object $object:ident) => {};
+    (@object $object:ident $key:tt: null $(, $($rest:tt)*)?) =>
+    {
+        $object.insert($key, $crate::json!(null));
+        $crate::json_internal!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt: [$($list:tt)*] $(, $($rest:tt)*)?) =>
+    {
+        $object.insert($key, $crate::json!([$($list)*]));
+        $crate::json_internal!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt: {$($inner:tt)*} $(, $($rest:tt)*)?) =>
+    {
+        $object.insert($key, $crate::json!({$($inner)*}));
+        $crate::json_internal!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt: $value:expr, $($rest:tt)*) =>
+    {
+        $object.insert($key, $crate::json!($value));
+        $crate::json_internal!(@object $object $($rest)*);
+    };
+    (@object $object:ident $key:tt: $value:expr) =>
+    {
+        $object.insert($key, $crate::json!($value));
+    };
+}
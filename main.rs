@@ -1,12 +1,31 @@
 use std::{
     fs,
     env,
-    process
+    process,
+    io::{self, Write, BufWriter},
+    collections::HashMap
 };
 
-use parser::Parser;
+use cringejsonparser::{
+    query,
+    redact,
+    incremental::IncrementalDocument,
+    parser::{self, Parser, ParserOptions, Dialect, ParseError, ObjectValue, Matcher, SpanTree},
+    lexer::{Lexer, Classifier, Category, TokenKind, Position},
+    validate::{self, Severity},
+    transform::{Case, KeyRename, PruneOptions},
+    writer::{self, WriteOptions}
+};
+#[cfg(feature = "verify")]
+use cringejsonparser::verify;
 
-mod parser;
+mod bench;
+mod batch;
+mod conformance;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod mock;
+mod random;
 
 
 fn complain(message: &str) -> !
@@ -16,17 +35,1114 @@ fn complain(message: &str) -> !
     process::exit(1)
 }
 
-fn main()
+fn parsed<T>(result: Result<T, ParseError>) -> T
 {
-    let filepath = env::args().nth(1)
-        .unwrap_or_else(|| complain("pls provide a path as argument"));
+    result.unwrap_or_else(|err| complain(&format!("error parsing json: {err}")))
+}
 
-    let data = fs::read_to_string(filepath)
+fn read_file(filepath: &str) -> String
+{
+    fs::read_to_string(filepath)
+        .unwrap_or_else(|err| complain(&format!("error reading file: {err:?}")))
+}
+
+// for everything that only needs the value, the text never gets read into memory all at once
+// and keys are only kept once no matter how many objects repeat them
+fn parse_file(filepath: &str) -> ObjectValue
+{
+    parse_file_with(filepath, ParserOptions::default())
+}
+
+fn parse_file_with(filepath: &str, options: ParserOptions) -> ObjectValue
+{
+    let file = fs::File::open(filepath)
         .unwrap_or_else(|err| complain(&format!("error reading file: {err:?}")));
 
-    let parser = Parser::new(data.chars());
+    parsed(Parser::from_reader(file).options(options).intern_keys(true).parse())
+}
+
+#[cfg(feature = "verify")]
+fn verify_files(filepaths: impl Iterator<Item=String>)
+{
+    let mut diverged = false;
+    for filepath in filepaths
+    {
+        match verify::verify(&read_file(&filepath))
+        {
+            Ok(()) => println!("{filepath}: ok"),
+            Err(divergence) =>
+            {
+                diverged = true;
+
+                println!("{filepath}: {divergence}");
+            }
+        }
+    }
+
+    if diverged
+    {
+        process::exit(1)
+    }
+}
+
+fn print_tree(text: &str, value: &ObjectValue, spans: &SpanTree, depth: usize)
+{
+    let indent = "    ".repeat(depth);
+
+    let key = spans.key.clone().map(|key| format!("{}: ", &text[key])).unwrap_or_default();
+
+    let position = Position::from_offset(text, spans.span.start);
+    let span = format!("{}..{}", spans.span.start, spans.span.end);
+
+    let children: Vec<&ObjectValue> = match value
+    {
+        ObjectValue::List(list) => list.iter().collect(),
+        ObjectValue::Object(object) => object.fields().iter().map(|field| field.value()).collect(),
+        _ =>
+        {
+            println!("{indent}{key}{} {} ({position} {span})", value.type_name(), &text[spans.span.clone()]);
+
+            return;
+        }
+    };
+
+    println!("{indent}{key}{} ({position} {span})", value.type_name());
+
+    children.into_iter().zip(spans.children.iter()).for_each(|(value, spans)|
+    {
+        print_tree(text, value, spans, depth + 1);
+    });
+}
+
+struct StreamFrame
+{
+    is_object: bool,
+    // raw key text with its quotes, they are already valid json
+    key: Option<String>,
+    index: usize,
+    empty: bool
+}
+
+// jq --stream style [path, leaf] and [path] closer lines straight from the tokens
+fn print_stream_events(text: &str)
+{
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+
+    let mut frames: Vec<StreamFrame> = Vec::new();
+    let mut expect_key = false;
+
+    let path = |frames: &[StreamFrame]| -> String
+    {
+        let tokens: Vec<String> = frames.iter().map(|frame|
+        {
+            if frame.is_object
+            {
+                frame.key.clone().unwrap_or_default()
+            } else
+            {
+                frame.index.to_string()
+            }
+        }).collect();
+
+        format!("[{}]", tokens.join(","))
+    };
+
+    let mut emit = |line: String|
+    {
+        if let Err(err) = writeln!(writer, "{line}")
+        {
+            complain(&format!("error writing events: {err}"))
+        }
+    };
+
+    for token in Lexer::new(text)
+    {
+        let unexpected = ||
+        {
+            let position = Position::from_offset(text, token.span.start);
+
+            complain(&format!("unexpected {} {:?} at {position}", token.kind, token.text))
+        };
+
+        match token.kind
+        {
+            TokenKind::Text if expect_key =>
+            {
+                if let Some(frame) = frames.last_mut()
+                {
+                    frame.key = Some(token.text.to_owned());
+                }
+
+                expect_key = false;
+            },
+            TokenKind::Text | TokenKind::Number | TokenKind::Literal =>
+            {
+                if let Some(frame) = frames.last_mut()
+                {
+                    frame.empty = false;
+                }
+
+                emit(format!("[{},{}]", path(&frames), token.text));
+            },
+            TokenKind::BeginObject | TokenKind::BeginList =>
+            {
+                if let Some(frame) = frames.last_mut()
+                {
+                    frame.empty = false;
+                }
+
+                let is_object = token.kind == TokenKind::BeginObject;
+                frames.push(StreamFrame{is_object, key: None, index: 0, empty: true});
+
+                expect_key = is_object;
+            },
+            TokenKind::EndObject | TokenKind::EndList =>
+            {
+                let is_object = token.kind == TokenKind::EndObject;
+                if frames.last().map(|frame| frame.is_object) != Some(is_object)
+                {
+                    unexpected();
+                }
+
+                let frame = frames.last().unwrap();
+                if frame.empty
+                {
+                    frames.pop();
+
+                    let empty = if is_object { "{}" } else { "[]" };
+                    emit(format!("[{},{empty}]", path(&frames)));
+                } else
+                {
+                    emit(format!("[{}]", path(&frames)));
+
+                    frames.pop();
+                }
+
+                expect_key = false;
+            },
+            TokenKind::Comma =>
+            {
+                match frames.last_mut()
+                {
+                    Some(frame) if frame.is_object => expect_key = true,
+                    Some(frame) => frame.index += 1,
+                    None => unexpected()
+                }
+            },
+            TokenKind::Colon => (),
+            TokenKind::Comment | TokenKind::Unknown => unexpected()
+        }
+    }
+
+    if !frames.is_empty()
+    {
+        complain("unexpected end of input, some containers are still open");
+    }
+
+    if let Err(err) = writer.flush()
+    {
+        complain(&format!("error writing events: {err}"))
+    }
+}
+
+fn print_highlighted(text: &str)
+{
+    let mut out = String::with_capacity(text.len());
+
+    let mut last = 0;
+    for (category, token) in Classifier::new(text)
+    {
+        out.push_str(&text[last..token.span.start]);
+
+        let color = match category
+        {
+            Category::Key => Some("34"),
+            Category::Text => Some("32"),
+            Category::Number => Some("36"),
+            Category::Literal => Some("35"),
+            Category::Punctuation => None,
+            Category::Comment => Some("90"),
+            Category::Invalid => Some("41")
+        };
+
+        match color
+        {
+            Some(color) => out.push_str(&format!("\x1b[{color}m{}\x1b[0m", token.text)),
+            None => out.push_str(token.text)
+        }
+
+        last = token.span.end;
+    }
+
+    out.push_str(&text[last..]);
+
+    print!("{out}");
+}
+
+fn print_tokens(args: impl Iterator<Item=String>)
+{
+    let mut tree = false;
+    let mut events = false;
+    let mut highlight = false;
+    let mut filepath = None;
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--tree" => tree = true,
+            "--stream" => events = true,
+            "--highlight" => highlight = true,
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let text = read_file(&filepath.unwrap_or_else(|| complain("pls provide a path to tokenize")));
+
+    if events
+    {
+        print_stream_events(&text);
+
+        return;
+    }
+
+    if highlight
+    {
+        print_highlighted(&text);
+
+        return;
+    }
+
+    if tree
+    {
+        let (value, spans) = parsed(Parser::new(text.chars()).parse_spanned());
+
+        print_tree(&text, &value, &spans, 0);
+
+        return;
+    }
+
+    for token in Lexer::new(&text)
+    {
+        let position = Position::from_offset(&text, token.span.start);
+
+        println!("{position} {}..{} {} {}", token.span.start, token.span.end, token.kind, token.text);
+    }
+}
 
-    let json = parser.parse();
+fn lint_files(args: impl Iterator<Item=String>)
+{
+    let mut options = ParserOptions::default();
+    let mut filepaths = Vec::new();
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--strict" => options = ParserOptions::strict(),
+            "--comments" => options.comments = true,
+            "--trailing-commas" => options.trailing_commas = true,
+            "--unquoted-keys" => options.unquoted_keys = true,
+            "--single-quotes" => options.single_quotes = true,
+            "--hex-numbers" => options.hex_numbers = true,
+            "--nan-and-infinity" => options.nan_and_infinity = true,
+            "--json5" => options.dialect = Dialect::Json5,
+            _ => filepaths.push(arg)
+        }
+    }
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide a path to lint");
+    }
+
+    let mut failed = false;
+    for filepath in filepaths
+    {
+        let text = read_file(&filepath);
+
+        for diagnostic in validate::lint(&text, &options)
+        {
+            failed |= diagnostic.severity == Severity::Error;
+
+            let position = Position::from_offset(&text, diagnostic.span.start);
+
+            println!("{filepath}:{position}: {}: {}", diagnostic.severity, diagnostic.message);
+
+            if let Some(suggestion) = diagnostic.suggestion
+            {
+                println!("    help: {suggestion}");
+            }
+        }
+    }
+
+    if failed
+    {
+        process::exit(1)
+    }
+}
+
+// every syntax error in the files instead of just the first one like the parser would stop at
+fn print_errors(args: impl Iterator<Item=String>)
+{
+    // a bracket closing the root too early would hide everything after it otherwise
+    let mut options = ParserOptions::default().trailing_content(false);
+    let mut filepaths = Vec::new();
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--comments" => options.comments = true,
+            "--trailing-commas" => options.trailing_commas = true,
+            "--json5" => options.dialect = Dialect::Json5,
+            _ => filepaths.push(arg)
+        }
+    }
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide a path to check for errors");
+    }
+
+    let mut failed = false;
+    for filepath in filepaths
+    {
+        let text = read_file(&filepath);
+
+        let recovered = parsed(Parser::new(text.chars()).options(options.clone()).parse_recovering());
+
+        for err in recovered.errors
+        {
+            failed = true;
+
+            let expected = err.expected.map(|expected| format!(", expected {expected}")).unwrap_or_default();
+
+            println!("{filepath}:{}: {}{expected}", err.position, err.message);
+        }
+    }
+
+    if failed
+    {
+        process::exit(1)
+    }
+}
+
+fn print_duplicates(args: impl Iterator<Item=String>)
+{
+    let filepaths: Vec<String> = args.collect();
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide a path to check for duplicate keys");
+    }
+
+    let mut found = false;
+    for filepath in filepaths
+    {
+        let text = read_file(&filepath);
+
+        let (value, spans) = parsed(Parser::new(text.chars()).parse_spanned());
+
+        value.walk_spanned(&spans, |pointer, value, spans|
+        {
+            let object = match value.get_object()
+            {
+                Some(x) if x.has_duplicate_keys() => x,
+                _ => return
+            };
+
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            object.fields().iter().for_each(|field| *counts.entry(field.key()).or_default() += 1);
+
+            for field in object.fields()
+            {
+                // only report each key once, at its first occurrence
+                if counts.remove(field.key()).filter(|count| *count > 1).is_none()
+                {
+                    continue;
+                }
+
+                found = true;
+
+                println!("{filepath}: object at \"{pointer}\" has duplicate key {:?}", field.key());
+
+                object.fields().iter().zip(spans.children.iter())
+                    .filter(|(other, _)| other.key() == field.key())
+                    .for_each(|(_, spans)|
+                    {
+                        let position = Position::from_offset(&text, spans.span.start);
+
+                        println!("    {position}: {}", &text[spans.span.clone()]);
+                    });
+            }
+        });
+    }
+
+    if found
+    {
+        process::exit(1)
+    }
+}
+
+// where in the file the pointer points, for pointing editors and people at a value
+fn print_location(args: impl Iterator<Item=String>)
+{
+    let positional: Vec<String> = args.collect();
+
+    let (filepath, pointer) = match positional.as_slice()
+    {
+        [filepath, pointer] => (filepath, pointer.as_str()),
+        [filepath] => (filepath, ""),
+        _ => complain("pls provide a path and a pointer to locate")
+    };
+
+    let text = read_file(filepath);
+
+    let (value, spans) = parsed(Parser::new(text.chars()).parse_spanned());
+
+    let spanned = value.spanned(&spans, &text).pointer(pointer)
+        .unwrap_or_else(|| complain(&format!("nothing at {pointer:?}")));
+
+    println!("{filepath}:{}..{}: {}", spanned.position(), spanned.end_position(), spanned.value().type_name());
+}
+
+fn print_paths(args: impl Iterator<Item=String>)
+{
+    let mut values = false;
+    let mut types = false;
+    let mut filepath = None;
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--values" => values = true,
+            "--types" => types = true,
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let text = read_file(&filepath.unwrap_or_else(|| complain("pls provide a path to list the paths of")));
+
+    let (value, spans) = parsed(Parser::new(text.chars()).parse_spanned());
+
+    value.walk_spanned(&spans, |pointer, value, spans|
+    {
+        let is_leaf = match value
+        {
+            ObjectValue::List(list) => list.is_empty(),
+            ObjectValue::Object(object) => object.fields().is_empty(),
+            _ => true
+        };
+
+        if !is_leaf
+        {
+            return;
+        }
+
+        let mut line = pointer.to_owned();
+
+        if types
+        {
+            line.push('\t');
+            line.push_str(value.type_name());
+        }
+
+        if values
+        {
+            line.push('\t');
+            line.push_str(&text[spans.span.clone()]);
+        }
+
+        println!("{line}");
+    });
+}
+
+#[derive(Default)]
+struct TypeMap
+{
+    paths: Vec<(String, Vec<&'static str>)>,
+    ids: HashMap<String, usize>
+}
+
+impl TypeMap
+{
+    fn add(&mut self, path: &mut String, value: &ObjectValue)
+    {
+        let id = match self.ids.get(path.as_str())
+        {
+            Some(id) => *id,
+            None =>
+            {
+                self.ids.insert(path.clone(), self.paths.len());
+                self.paths.push((path.clone(), Vec::new()));
+
+                self.paths.len() - 1
+            }
+        };
+
+        let types = &mut self.paths[id].1;
+        if !types.contains(&value.type_name())
+        {
+            types.push(value.type_name());
+        }
+
+        let length = path.len();
+        match value
+        {
+            ObjectValue::List(list) =>
+            {
+                path.push_str("[]");
+                list.iter().for_each(|value| self.add(path, value));
+            },
+            ObjectValue::Object(object) =>
+            {
+                for field in object.fields()
+                {
+                    path.push('/');
+                    path.push_str(&parser::escape_pointer_token(field.key()));
+
+                    self.add(path, field.value());
+
+                    path.truncate(length);
+                }
+            },
+            _ => ()
+        }
+
+        path.truncate(length);
+    }
+}
+
+fn print_types(args: impl Iterator<Item=String>)
+{
+    let mut mixed = false;
+    let mut filepath = None;
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--mixed" => mixed = true,
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let value = parse_file(&filepath.unwrap_or_else(|| complain("pls provide a path to map the types of")));
+
+    let mut types = TypeMap::default();
+    types.add(&mut String::new(), &value);
+
+    for (path, types) in types.paths
+    {
+        if mixed && types.len() < 2
+        {
+            continue;
+        }
+
+        let path = if path.is_empty() { "(root)" } else { &path };
+        println!("{path}: {}", types.join("|"));
+    }
+}
+
+fn tsv_cell(value: &ObjectValue) -> String
+{
+    match value
+    {
+        ObjectValue::Text(x) =>
+        {
+            x.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+        },
+        ObjectValue::Null => String::new(),
+        ObjectValue::Number(x) => x.to_string(),
+        ObjectValue::Bool(x) => x.to_string(),
+        x => writer::to_compact(x)
+    }
+}
+
+fn print_query(mut args: impl Iterator<Item=String>)
+{
+    let mut tsv = false;
+    let mut jq = false;
+    let mut fields = Vec::new();
+    let mut filters = Vec::new();
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next()
+    {
+        match arg.as_str()
+        {
+            "--jq-compat" => jq = true,
+            "--where" =>
+            {
+                let condition = args.next().unwrap_or_else(|| complain("--where needs a pointer=value"));
+
+                filters.push(query::parse_where(&condition)
+                    .unwrap_or_else(|| complain(&format!("{condition:?} should look like pointer=value"))));
+            },
+            "--format" => match args.next().as_deref()
+            {
+                Some("tsv") => tsv = true,
+                Some("json") => tsv = false,
+                x => complain(&format!("unknown format {x:?}, expected json or tsv"))
+            },
+            "--field" => fields.push(args.next().unwrap_or_else(|| complain("--field needs a pointer"))),
+            _ => positional.push(arg)
+        }
+    }
+
+    let (filepath, path) = match positional.as_slice()
+    {
+        [filepath, path] => (filepath, path.as_str()),
+        [filepath] => (filepath, ""),
+        _ => complain("pls provide a path to query and a pointer (* matches everything)")
+    };
+
+    let mut stages = if jq
+    {
+        query::parse_jq(path).unwrap_or_else(|err| complain(&format!("error in jq filter: {err}")))
+    } else
+    {
+        vec![query::Stage::Path(path.to_owned())]
+    };
+
+    stages.extend(filters);
+
+    let value = parse_file(filepath);
+
+    for value in query::run(&stages, value).iter()
+    {
+        let selected: Vec<Option<&ObjectValue>> = if fields.is_empty()
+        {
+            vec![Some(value)]
+        } else
+        {
+            fields.iter().map(|field| value.pointer(field)).collect()
+        };
+
+        let line = if tsv
+        {
+            // missing fields become empty columns so they stay aligned
+            let cells: Vec<String> = selected.into_iter()
+                .map(|value| value.map(tsv_cell).unwrap_or_default())
+                .collect();
+
+            cells.join("\t")
+        } else if fields.is_empty()
+        {
+            writer::to_compact(value)
+        } else
+        {
+            let cells: Vec<String> = selected.into_iter()
+                .map(|value| value.map(writer::to_compact).unwrap_or_else(|| "null".to_owned()))
+                .collect();
+
+            format!("[{}]", cells.join(","))
+        };
+
+        println!("{line}");
+    }
+}
+
+// replaces $name outside of strings with the bound json text
+fn fill_template(template: &str, bindings: &HashMap<String, String>) -> String
+{
+    let mut out = String::with_capacity(template.len());
+
+    let mut in_text = false;
+    let mut escaped = false;
+
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next()
+    {
+        if in_text
+        {
+            match c
+            {
+                '\\' => escaped = !escaped,
+                '"' if !escaped => in_text = false,
+                _ => escaped = false
+            }
+
+            out.push(c);
+
+            continue;
+        }
+
+        match c
+        {
+            '"' =>
+            {
+                in_text = true;
+                out.push(c);
+            },
+            '$' =>
+            {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    name.push(c);
+                }
+
+                let value = bindings.get(&name)
+                    .unwrap_or_else(|| complain(&format!("${name} isnt bound, pass it with --arg or --argjson")));
+
+                out.push_str(value);
+            },
+            c => out.push(c)
+        }
+    }
+
+    out
+}
+
+fn build_document(mut args: impl Iterator<Item=String>)
+{
+    let mut bindings = HashMap::new();
+    let mut template = None;
+
+    while let Some(arg) = args.next()
+    {
+        let is_json = match arg.as_str()
+        {
+            "--arg" => false,
+            "--argjson" => true,
+            _ =>
+            {
+                template = Some(arg);
+
+                continue;
+            }
+        };
+
+        let binding = args.next().unwrap_or_else(|| complain(&format!("{arg} needs a name=value")));
+
+        let (name, value) = binding.split_once('=')
+            .unwrap_or_else(|| complain(&format!("{binding:?} should look like name=value")));
+
+        let value = if is_json
+        {
+            writer::to_compact(&parsed(Parser::new(value.chars()).parse()))
+        } else
+        {
+            let mut text = String::new();
+            writer::write_text(&mut text, value);
+
+            text
+        };
+
+        bindings.insert(name.to_owned(), value);
+    }
+
+    let template = template.unwrap_or_else(|| complain("pls provide a template to fill"));
+
+    let text = fill_template(&template, &bindings);
+
+    println!("{}", parsed(Parser::new(text.chars()).parse()));
+}
+
+fn redact_file(mut args: impl Iterator<Item=String>)
+{
+    let mut patterns = Vec::new();
+    let mut hash = false;
+    let mut filepath = None;
+
+    while let Some(arg) = args.next()
+    {
+        match arg.as_str()
+        {
+            "--paths" =>
+            {
+                let paths = args.next().unwrap_or_else(|| complain("--paths needs a comma separated list"));
+
+                patterns.extend(paths.split(',').map(str::to_owned));
+            },
+            "--hash" => hash = true,
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to redact"));
+
+    if patterns.is_empty()
+    {
+        complain("pls provide the --paths to redact");
+    }
+
+    print!("{}", parsed(redact::redact(&read_file(&filepath), &patterns, hash)));
+}
+
+// stable output for git textconv, only semantic changes show up in diffs
+fn print_canonical(args: impl Iterator<Item=String>)
+{
+    let mut jcs = false;
+    let mut filepaths = Vec::new();
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--jcs" => jcs = true,
+            _ => filepaths.push(arg)
+        }
+    }
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide a path to canonicalize");
+    }
+
+    for filepath in filepaths
+    {
+        let text = read_file(&filepath);
+
+        let value = parsed(Parser::new(text.chars()).parse());
+
+        if jcs
+        {
+            match writer::to_canonical(&value)
+            {
+                Ok(text) => println!("{text}"),
+                Err(err) => complain(&format!("{filepath}: {err}"))
+            }
+        } else
+        {
+            print!("{}", value.to_snapshot_string());
+        }
+    }
+}
+
+// changes one value and prints the file back out with everything else exactly like it was
+fn edit_file(args: impl Iterator<Item=String>)
+{
+    let mut options = ParserOptions::default();
+    let mut remove = false;
+    let mut positional = Vec::new();
+
+    for arg in args
+    {
+        match arg.as_str()
+        {
+            "--remove" => remove = true,
+            "--comments" => options.comments = true,
+            "--json5" => options.dialect = Dialect::Json5,
+            _ => positional.push(arg)
+        }
+    }
+
+    let (filepath, pointer, value) = match (positional.as_slice(), remove)
+    {
+        ([filepath, pointer], true) => (filepath, pointer, None),
+        ([filepath, pointer, value], false) => (filepath, pointer, Some(value)),
+        _ => complain("pls provide a path, a pointer and either a json value or --remove")
+    };
+
+    let mut document = parsed(IncrementalDocument::parse_with_options(read_file(filepath), options));
+
+    let result = match value
+    {
+        Some(value) => document.set(pointer, &parsed(Parser::new(value.chars()).parse())),
+        None => document.remove(pointer).map(|_| ())
+    };
+
+    if let Err(err) = result
+    {
+        complain(&format!("cant edit {filepath}: {err}"))
+    }
+
+    print!("{}", document.text());
+}
+
+fn format_file(mut args: impl Iterator<Item=String>)
+{
+    let mut options = WriteOptions::default();
+    let mut renames = Vec::new();
+    let mut mapping = HashMap::new();
+    let mut prune = false;
+    let mut compact = false;
+    let mut lossy = false;
+    let mut filepath = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut number = |name: &str|
+        {
+            args.next().and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or_else(|| complain(&format!("{name} needs a number")))
+        };
+
+        match arg.as_str()
+        {
+            "--indent" => options.indent = " ".repeat(number("--indent")),
+            "--tabs" => options.indent = "\t".to_owned(),
+            "--width" => options.width = Some(number("--width")),
+            "--precision" => options.floats.precision = Some(number("--precision")),
+            "--prune" => prune = true,
+            "--compact" => compact = true,
+            "--lossy" => lossy = true,
+            "--rename-case" =>
+            {
+                let name = args.next().unwrap_or_default();
+
+                let case = Case::from_name(&name)
+                    .unwrap_or_else(|| complain(&format!("unknown case {name:?}, expected snake, camel, pascal or kebab")));
+
+                renames.push(KeyRename::Case(case));
+            },
+            "--rename" =>
+            {
+                let rename = args.next().unwrap_or_default();
+
+                let (from, to) = rename.split_once('=')
+                    .unwrap_or_else(|| complain(&format!("{rename:?} should look like old=new")));
+
+                mapping.insert(from.to_owned(), to.to_owned());
+            },
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to format"));
+
+    // for salvaging whatever is left of a file with invalid utf8 in it
+    let mut value = parse_file_with(&filepath, ParserOptions::default().lossy_utf8(lossy));
+
+    // explicit renames apply to the original keys, before any case conversion
+    if !mapping.is_empty()
+    {
+        renames.insert(0, KeyRename::Map(mapping));
+    }
+
+    renames.iter().try_for_each(|rename| value.rename_keys(rename))
+        .unwrap_or_else(|err| complain(&format!("cant rename keys: {err}")));
+
+    if prune
+    {
+        value.prune(&PruneOptions::default());
+    }
+
+    if compact
+    {
+        let stdout = io::stdout();
+        let mut writer = stdout.lock();
+
+        if let Err(err) = value.write_json(&mut writer).and_then(|_| writeln!(writer))
+        {
+            complain(&format!("error writing json: {err}"));
+        }
+
+        return;
+    }
+
+    println!("{}", writer::to_pretty(&value, &options));
+}
+
+fn print_preview(mut args: impl Iterator<Item=String>)
+{
+    let mut max_depth = 3;
+    let mut max_items = 5;
+    let mut max_string_len = 40;
+    let mut filepath = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut number = |name: &str|
+        {
+            args.next().and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or_else(|| complain(&format!("{name} needs a number")))
+        };
+
+        match arg.as_str()
+        {
+            "--depth" => max_depth = number("--depth"),
+            "--items" => max_items = number("--items"),
+            "--string" => max_string_len = number("--string"),
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let value = parse_file(&filepath.unwrap_or_else(|| complain("pls provide a path to preview")));
+
+    println!("{}", value.preview(max_depth, max_items, max_string_len));
+}
+
+fn print_stats(args: impl Iterator<Item=String>)
+{
+    let filepaths: Vec<String> = args.collect();
+
+    if filepaths.is_empty()
+    {
+        complain("pls provide a path to parse");
+    }
+
+    for filepath in filepaths
+    {
+        let text = read_file(&filepath);
+
+        let (_, report) = parsed(Parser::new(text.chars()).parse_reported());
+
+        println!("{filepath}:");
+        println!("    bytes: {}", report.bytes);
+        println!("    tokens: {}", report.tokens);
+        println!(
+            "    values: {} nulls, {} strings, {} numbers, {} bools, {} arrays, {} objects",
+            report.nulls, report.texts, report.numbers, report.bools, report.lists, report.objects
+        );
+        println!("    peak depth: {}", report.peak_depth);
+        println!("    lex time: {:?}", report.lex_time);
+        println!("    build time: {:?}", report.build_time);
+    }
+}
+
+fn replace_values(mut args: impl Iterator<Item=String>)
+{
+    let mut matcher = None;
+    let mut replacement = None;
+    let mut filepath = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut value = |name: &str|
+        {
+            args.next().unwrap_or_else(|| complain(&format!("{name} needs a value")))
+        };
+
+        match arg.as_str()
+        {
+            "--value" => matcher = Some(Matcher::Value(parsed(Parser::new(value("--value").chars()).parse()))),
+            #[cfg(feature = "regex")]
+            "--regex" =>
+            {
+                let regex = regex::Regex::new(&value("--regex"))
+                    .unwrap_or_else(|err| complain(&format!("invalid regex: {err}")));
+
+                matcher = Some(Matcher::Regex(regex));
+            },
+            "--with" => replacement = Some(parsed(Parser::new(value("--with").chars()).parse())),
+            "--with-text" => replacement = Some(ObjectValue::Text(value("--with-text").into())),
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to replace values in"));
+
+    let matcher = matcher.unwrap_or_else(|| complain("pls provide what to replace with --value (or --regex)"));
+    let replacement = replacement.unwrap_or_else(|| complain("pls provide a replacement with --with or --with-text"));
+
+    let mut value = parse_file(&filepath);
+
+    let amount = value.replace_all(&matcher, &replacement);
+
+    eprintln!("replaced {amount} values");
+    println!("{}", writer::to_pretty(&value, &WriteOptions::default()));
+}
+
+fn print_symbols(filepath: &str)
+{
+    // symbol dumps tend to write addresses in hex
+    let json = parse_file_with(filepath, ParserOptions::default().hex_numbers(true));
 
     for object in json.get_list().unwrap()
     {
@@ -45,3 +1161,48 @@ fn main()
         println!();*/
     }
 }
+
+fn main()
+{
+    let mut args = env::args().skip(1);
+
+    let first = args.next()
+        .unwrap_or_else(|| complain("pls provide a path as argument"));
+
+    match first.as_str()
+    {
+        "tokens" => print_tokens(args),
+        "bench" => bench::bench(args),
+        "lint" => lint_files(args),
+        "errors" => print_errors(args),
+        "conformance" => conformance::conformance(args),
+        "dupes" => print_duplicates(args),
+        "paths" => print_paths(args),
+        "locate" => print_location(args),
+        "types" => print_types(args),
+        "query" => print_query(args),
+        "new" => build_document(args),
+        "redact" => redact_file(args),
+        "canon" => print_canonical(args),
+        "fmt" => format_file(args),
+        "set" => edit_file(args),
+        "replace" => replace_values(args),
+        "preview" => print_preview(args),
+        "stats" => print_stats(args),
+        "chunk" => batch::chunk(args),
+        "concat" => batch::concat(args),
+        "sample" => batch::sample(args),
+        "mock" => mock::mock(args),
+        #[cfg(feature = "verify")]
+        "verify" => verify_files(args),
+        #[cfg(feature = "lsp")]
+        "lsp" =>
+        {
+            if let Err(err) = lsp::run()
+            {
+                complain(&format!("language server stopped: {err}"))
+            }
+        },
+        _ => print_symbols(&first)
+    }
+}
@@ -0,0 +1,560 @@
+use std::io::{self, Write, BufWriter};
+
+use cringejsonparser::{
+    parser::{Parser, ObjectValue},
+    writer::{self, write_text, write_compact}
+};
+
+use crate::{
+    complain,
+    parsed,
+    read_file,
+    random::Rng
+};
+
+
+fn keyword<'a>(schema: &'a ObjectValue, name: &str) -> Option<&'a ObjectValue>
+{
+    schema.get_object()?.get(name)
+}
+
+// bounds can be floats even for integers, rounded to the closest integer still inside them
+fn integer_keyword(schema: &ObjectValue, name: &str, round: fn(f64) -> f64) -> Option<i64>
+{
+    match keyword(schema, name)?
+    {
+        ObjectValue::Number(x) => Some(*x),
+        value => value.as_f64().map(|x| round(x) as i64)
+    }
+}
+
+fn number_keyword(schema: &ObjectValue, name: &str) -> Option<u32>
+{
+    keyword(schema, name).and_then(ObjectValue::get_number).and_then(|x| u32::try_from(x).ok())
+}
+
+fn float_keyword(schema: &ObjectValue, name: &str) -> Option<f64>
+{
+    keyword(schema, name).and_then(ObjectValue::as_f64)
+}
+
+// past this its almost certainly a schema that refers to itself with nothing optional to stop it
+const MAX_REF_DEPTH: usize = 64;
+
+struct Generator<'a>
+{
+    rng: &'a mut Rng,
+    // refs point into the whole schema
+    root: &'a ObjectValue,
+    depth: usize,
+    out: String
+}
+
+impl<'r> Generator<'r>
+{
+    fn between(&mut self, low: u32, high: u32) -> u32
+    {
+        if high <= low
+        {
+            return low;
+        }
+
+        low + self.rng.below(u64::from(high - low) + 1) as u32
+    }
+
+    fn between_signed(&mut self, low: i64, high: i64) -> i64
+    {
+        if high <= low
+        {
+            return low;
+        }
+
+        low.wrapping_add(self.rng.below(high.abs_diff(low).saturating_add(1)) as i64)
+    }
+
+    fn chance(&mut self) -> bool
+    {
+        self.rng.below(2) == 0
+    }
+
+    fn pick<'a, T>(&mut self, values: &'a [T]) -> Option<&'a T>
+    {
+        if values.is_empty()
+        {
+            return None;
+        }
+
+        values.get(self.rng.below(values.len() as u64) as usize)
+    }
+
+    fn word(&mut self, low: u32, high: u32) -> String
+    {
+        (0..self.between(low, high)).map(|_| (b'a' + self.rng.below(26) as u8) as char).collect()
+    }
+
+    fn hex(&mut self, amount: usize) -> String
+    {
+        (0..amount).map(|_| char::from_digit(self.rng.below(16) as u32, 16).unwrap()).collect()
+    }
+
+    fn date(&mut self) -> String
+    {
+        format!("{}-{:02}-{:02}", self.between(1970, 2030), self.between(1, 12), self.between(1, 28))
+    }
+
+    fn formatted(&mut self, format: &str) -> Option<String>
+    {
+        let text = match format
+        {
+            "date" => self.date(),
+            "time" => format!("{:02}:{:02}:{:02}Z", self.between(0, 23), self.between(0, 59), self.between(0, 59)),
+            "date-time" | "timestamp" =>
+            {
+                let date = self.date();
+
+                format!("{date}T{:02}:{:02}:{:02}Z", self.between(0, 23), self.between(0, 59), self.between(0, 59))
+            },
+            "email" => format!("{}@{}.com", self.word(3, 10), self.word(3, 8)),
+            "hostname" => format!("{}.{}.com", self.word(3, 8), self.word(3, 8)),
+            "uri" | "url" => format!("https://{}.com/{}", self.word(3, 8), self.word(1, 10)),
+            "uuid" =>
+            {
+                format!("{}-{}-4{}-{}{}-{}",
+                    self.hex(8), self.hex(4), self.hex(3),
+                    ['8', '9', 'a', 'b'][self.rng.below(4) as usize], self.hex(3),
+                    self.hex(12))
+            },
+            "ipv4" => format!("{}.{}.{}.{}",
+                self.between(1, 254), self.between(0, 255), self.between(0, 255), self.between(1, 254)),
+            _ => return None
+        };
+
+        Some(text)
+    }
+
+    fn text(&mut self, schema: &ObjectValue)
+    {
+        let format = keyword(schema, "format").and_then(ObjectValue::get_text);
+
+        let text = format.and_then(|format| self.formatted(format)).unwrap_or_else(||
+        {
+            let low = number_keyword(schema, "minLength").unwrap_or(1);
+            let high = number_keyword(schema, "maxLength").unwrap_or(low.max(12));
+
+            self.word(low, high)
+        });
+
+        write_text(&mut self.out, &text);
+    }
+
+    fn integer(&mut self, schema: &ObjectValue, (min, max): (i64, i64))
+    {
+        let minimum = integer_keyword(schema, "minimum", f64::ceil)
+            .or_else(|| integer_keyword(schema, "exclusiveMinimum", f64::floor).map(|x| x.saturating_add(1)));
+
+        let maximum = integer_keyword(schema, "maximum", f64::floor)
+            .or_else(|| integer_keyword(schema, "exclusiveMaximum", f64::ceil).map(|x| x.saturating_sub(1)));
+
+        // without bounds the numbers stay small, the type limits still apply
+        let (low, high) = match (minimum, maximum)
+        {
+            (Some(low), Some(high)) => (low, high),
+            (Some(low), None) => (low, low.saturating_add(1000)),
+            (None, Some(high)) => (high.saturating_sub(1000), high),
+            (None, None) => (-1000, 1000)
+        };
+
+        let (low, high) = (low.clamp(min, max), high.clamp(min, max));
+
+        if high < low
+        {
+            complain(&format!("no integer fits between the bounds in {}", writer::to_compact(schema)));
+        }
+
+        let number = self.between_signed(low, high);
+
+        self.out.push_str(&number.to_string());
+    }
+
+    fn float(&mut self, schema: &ObjectValue, limit: f64)
+    {
+        let minimum = float_keyword(schema, "minimum").map(|x| (x, false))
+            .or_else(|| float_keyword(schema, "exclusiveMinimum").map(|x| (x, true)));
+
+        let maximum = float_keyword(schema, "maximum").map(|x| (x, false))
+            .or_else(|| float_keyword(schema, "exclusiveMaximum").map(|x| (x, true)));
+
+        let ((low, low_exclusive), (high, high_exclusive)) = match (minimum, maximum)
+        {
+            (Some(low), Some(high)) => (low, high),
+            (Some(low), None) => (low, (low.0 + 1000.0, false)),
+            (None, Some(high)) => ((high.0 - 1000.0, false), high),
+            (None, None) => ((-1000.0, false), (1000.0, false))
+        };
+
+        let (low, high) = (low.clamp(-limit, limit), high.clamp(-limit, limit));
+
+        if high < low || (high == low && (low_exclusive || high_exclusive))
+        {
+            complain(&format!("no number fits between the bounds in {}", writer::to_compact(schema)));
+        }
+
+        // strictly between the two so exclusive bounds hold too, mixed so huge ranges dont overflow
+        let unit = (self.rng.below(1 << 53) + 1) as f64 / ((1_u64 << 53) + 1) as f64;
+        let number = if high == low { low } else { low * (1.0 - unit) + high * unit };
+
+        write_compact(&mut self.out, &ObjectValue::Float(number.clamp(low, high)));
+    }
+
+    fn list(&mut self, schema: &ObjectValue)
+    {
+        let items = keyword(schema, "items").or_else(|| keyword(schema, "elements"));
+
+        self.out.push('[');
+
+        // a list of schemas is a tuple
+        if let Some(ObjectValue::List(items)) = items
+        {
+            items.iter().enumerate().for_each(|(id, item)|
+            {
+                if id != 0
+                {
+                    self.out.push(',');
+                }
+
+                self.generate(item);
+            });
+        } else
+        {
+            let low = number_keyword(schema, "minItems").unwrap_or(0);
+            let high = number_keyword(schema, "maxItems").unwrap_or(low + 5);
+
+            (0..self.between(low, high)).for_each(|id|
+            {
+                if id != 0
+                {
+                    self.out.push(',');
+                }
+
+                match items
+                {
+                    Some(items) => self.generate(items),
+                    None => self.any()
+                }
+            });
+        }
+
+        self.out.push(']');
+    }
+
+    fn object(&mut self, schema: &ObjectValue, extra: Option<(&str, &str)>)
+    {
+        let required: Vec<&str> = keyword(schema, "required")
+            .and_then(ObjectValue::get_list)
+            .map(|required| required.iter().filter_map(ObjectValue::get_text).collect())
+            .unwrap_or_default();
+
+        let mut fields: Vec<(&str, &ObjectValue)> = Vec::new();
+
+        if let Some(properties) = keyword(schema, "properties").and_then(ObjectValue::get_object)
+        {
+            // jtd properties are always required, json schema ones only when listed
+            let jtd = keyword(schema, "required").is_none() && keyword(schema, "type").is_none();
+
+            properties.fields().iter().for_each(|field|
+            {
+                if jtd || required.contains(&field.key()) || self.chance()
+                {
+                    fields.push((field.key(), field.value()));
+                }
+            });
+        }
+
+        if let Some(optional) = keyword(schema, "optionalProperties").and_then(ObjectValue::get_object)
+        {
+            optional.fields().iter().for_each(|field|
+            {
+                if self.chance()
+                {
+                    fields.push((field.key(), field.value()));
+                }
+            });
+        }
+
+        let values = keyword(schema, "values");
+        let generated: Vec<String> = values.map(|_|
+        {
+            let amount = self.between(0, 4);
+
+            (0..amount).map(|_| self.word(3, 8)).collect()
+        }).unwrap_or_default();
+
+        self.out.push('{');
+
+        let mut first = true;
+        let mut separate = |out: &mut String|
+        {
+            if !first
+            {
+                out.push(',');
+            }
+
+            first = false;
+        };
+
+        if let Some((key, value)) = extra
+        {
+            separate(&mut self.out);
+            write_text(&mut self.out, key);
+            self.out.push(':');
+            write_text(&mut self.out, value);
+        }
+
+        fields.into_iter().for_each(|(key, value)|
+        {
+            separate(&mut self.out);
+            write_text(&mut self.out, key);
+            self.out.push(':');
+            self.generate(value);
+        });
+
+        if let Some(values) = values
+        {
+            generated.iter().for_each(|key|
+            {
+                separate(&mut self.out);
+                write_text(&mut self.out, key);
+                self.out.push(':');
+                self.generate(values);
+            });
+        }
+
+        self.out.push('}');
+    }
+
+    fn any(&mut self)
+    {
+        match self.rng.below(3)
+        {
+            0 =>
+            {
+                let word = self.word(1, 12);
+
+                write_text(&mut self.out, &word);
+            },
+            1 =>
+            {
+                let number = self.between(0, 1000);
+
+                self.out.push_str(&number.to_string());
+            },
+            _ =>
+            {
+                let value = self.chance();
+
+                self.out.push_str(if value { "true" } else { "false" });
+            }
+        }
+    }
+
+    fn typed(&mut self, schema: &ObjectValue, name: &str)
+    {
+        match name
+        {
+            "string" => self.text(schema),
+            "timestamp" =>
+            {
+                let text = self.formatted("date-time").unwrap();
+
+                write_text(&mut self.out, &text);
+            },
+            "integer" => self.integer(schema, (i64::MIN, i64::MAX)),
+            "number" | "float64" => self.float(schema, f64::MAX),
+            "float32" => self.float(schema, f32::MAX.into()),
+            "uint32" => self.integer(schema, (0, u32::MAX.into())),
+            "int32" => self.integer(schema, (i32::MIN.into(), i32::MAX.into())),
+            "uint16" => self.integer(schema, (0, u16::MAX.into())),
+            "int16" => self.integer(schema, (i16::MIN.into(), i16::MAX.into())),
+            "uint8" => self.integer(schema, (0, u8::MAX.into())),
+            "int8" => self.integer(schema, (i8::MIN.into(), i8::MAX.into())),
+            "boolean" =>
+            {
+                let value = self.chance();
+
+                self.out.push_str(if value { "true" } else { "false" });
+            },
+            "null" => self.out.push_str("null"),
+            "array" => self.list(schema),
+            "object" => self.object(schema, None),
+            x => complain(&format!("unknown schema type {x:?}"))
+        }
+    }
+
+    // json schema refs are pointers into the document, jtd ones are names in its definitions,
+    // anything that doesnt resolve would only make documents that dont fit the schema
+    fn resolve(&self, schema: &ObjectValue) -> Option<&'r ObjectValue>
+    {
+        if let Some(reference) = keyword(schema, "$ref")
+        {
+            let reference = reference.get_text()
+                .unwrap_or_else(|| complain(&format!("$ref has to be a string in {}", writer::to_compact(schema))));
+
+            let found = reference.strip_prefix('#').and_then(|pointer| self.root.pointer(pointer));
+
+            return Some(found.unwrap_or_else(|| complain(&format!("cant resolve $ref {reference:?}, only ones inside the schema work"))));
+        }
+
+        let name = keyword(schema, "ref")?.get_text()
+            .unwrap_or_else(|| complain(&format!("ref has to be a string in {}", writer::to_compact(schema))));
+
+        let found = keyword(self.root, "definitions").and_then(|definitions| keyword(definitions, name));
+
+        Some(found.unwrap_or_else(|| complain(&format!("cant resolve ref {name:?}, its not in the definitions"))))
+    }
+
+    fn generate(&mut self, schema: &ObjectValue)
+    {
+        if let Some(target) = self.resolve(schema)
+        {
+            if self.depth == MAX_REF_DEPTH
+            {
+                complain(&format!("refs nest deeper than {MAX_REF_DEPTH}, does the schema always refer to itself?"));
+            }
+
+            self.depth += 1;
+            self.generate(target);
+            self.depth -= 1;
+
+            return;
+        }
+
+        if let Some(value) = keyword(schema, "const")
+        {
+            return write_compact(&mut self.out, value);
+        }
+
+        if let Some(values) = keyword(schema, "enum").and_then(ObjectValue::get_list)
+        {
+            if let Some(value) = self.pick(values)
+            {
+                return write_compact(&mut self.out, value);
+            }
+        }
+
+        let choices = keyword(schema, "oneOf").or_else(|| keyword(schema, "anyOf"))
+            .and_then(ObjectValue::get_list);
+
+        if let Some(choice) = choices.and_then(|choices| self.pick(choices))
+        {
+            return self.generate(choice);
+        }
+
+        if let Some(first) = keyword(schema, "allOf").and_then(ObjectValue::get_list).and_then(|x| x.first())
+        {
+            return self.generate(first);
+        }
+
+        // jtd tagged unions, the tag goes in front of the mapped properties
+        let discriminator = keyword(schema, "discriminator").and_then(ObjectValue::get_text);
+        let mapping = keyword(schema, "mapping").and_then(ObjectValue::get_object);
+
+        if let (Some(discriminator), Some(mapping)) = (discriminator, mapping)
+        {
+            if let Some(field) = self.pick(mapping.fields())
+            {
+                return self.object(field.value(), Some((discriminator, field.key())));
+            }
+        }
+
+        match keyword(schema, "type")
+        {
+            Some(ObjectValue::Text(name)) => self.typed(schema, name),
+            Some(ObjectValue::List(names)) =>
+            {
+                let names: Vec<&str> = names.iter().filter_map(ObjectValue::get_text).collect();
+
+                match self.pick(&names)
+                {
+                    Some(name) => self.typed(schema, name),
+                    None => self.any()
+                }
+            },
+            _ =>
+            {
+                let is_list = keyword(schema, "items").is_some() || keyword(schema, "elements").is_some();
+                let is_object = ["properties", "optionalProperties", "values"].into_iter()
+                    .any(|name| keyword(schema, name).is_some());
+
+                if is_list
+                {
+                    self.list(schema)
+                } else if is_object
+                {
+                    self.object(schema, None)
+                } else
+                {
+                    self.any()
+                }
+            }
+        }
+    }
+}
+
+// makes one random document that fits a json schema or a jtd schema
+pub fn generate(schema: &ObjectValue, rng: &mut Rng) -> String
+{
+    let mut generator = Generator{rng, root: schema, depth: 0, out: String::new()};
+
+    generator.generate(schema);
+
+    generator.out
+}
+
+pub fn mock(mut args: impl Iterator<Item=String>)
+{
+    let mut filepath = None;
+    let mut amount: usize = 1;
+    let mut seed = None;
+
+    while let Some(arg) = args.next()
+    {
+        let mut value = |name: &str|
+        {
+            args.next().unwrap_or_else(|| complain(&format!("{name} needs a value")))
+        };
+
+        match arg.as_str()
+        {
+            "-n" =>
+            {
+                amount = value("-n").parse()
+                    .unwrap_or_else(|_| complain("-n must be a number"));
+            },
+            "--seed" =>
+            {
+                seed = Some(value("--seed").parse::<u64>()
+                    .unwrap_or_else(|_| complain("seed must be a number")));
+            },
+            _ => filepath = Some(arg)
+        }
+    }
+
+    let filepath = filepath.unwrap_or_else(|| complain("pls provide a path to a schema"));
+
+    let schema = parsed(Parser::new(read_file(&filepath).chars()).parse());
+
+    let mut rng = seed.map(Rng::new).unwrap_or_else(Rng::from_time);
+
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+
+    let result = (0..amount).try_for_each(|_|
+    {
+        writeln!(writer, "{}", generate(&schema, &mut rng))
+    }).and_then(|_| writer.flush());
+
+    if let Err(err) = result
+    {
+        complain(&format!("error writing documents: {err}"))
+    }
+}
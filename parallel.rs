@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+use crate::parser::ParserOptions;
+
+
+// below this many bytes a chunk isnt worth handing to another thread
+const MIN_CHUNK_SIZE: usize = 1 << 16;
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+
+// splits the elements of a list at the root into about pieces chunks at commas between them,
+// the first chunk starts right after the opening bracket, every other one right after a comma
+// and the last one ends right after the closing bracket, nothing if the root isnt a list or
+// theres not enough of it to split, the scan only has to be right for valid text since
+// the parser checks every chunk anyway
+pub fn split(text: &[u8], options: &ParserOptions, pieces: usize) -> Option<Vec<Range<usize>>>
+{
+    let mut start = 0;
+
+    if options.byte_order_mark && text.starts_with(BYTE_ORDER_MARK)
+    {
+        start = BYTE_ORDER_MARK.len();
+    }
+
+    start += text[start..].iter().position(|byte| !is_whitespace(*byte))?;
+
+    if text[start] != b'['
+    {
+        return None;
+    }
+
+    let size = (text.len() / pieces.max(1)).max(MIN_CHUNK_SIZE);
+
+    let mut ranges = Vec::new();
+    let mut chunk_start = start + 1;
+    let mut depth = 0_usize;
+
+    let mut id = chunk_start;
+    while id < text.len()
+    {
+        match text[id]
+        {
+            b'"' => id = text_end(text, id)?,
+            b'\'' if options.allows_single_quotes() => id = text_end(text, id)?,
+            b'/' if options.allows_comments() => id = comment_end(text, id)?,
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' if depth == 0 =>
+            {
+                ranges.push(chunk_start..id + 1);
+
+                return (ranges.len() > 1).then_some(ranges);
+            },
+            b']' | b'}' => depth -= 1,
+            b',' if depth == 0 && id - chunk_start >= size =>
+            {
+                ranges.push(chunk_start..id);
+                chunk_start = id + 1;
+            },
+            _ => ()
+        }
+
+        id += 1;
+    }
+
+    None
+}
+
+fn is_whitespace(byte: u8) -> bool
+{
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+// where the string starting with the quote at start ends
+fn text_end(text: &[u8], start: usize) -> Option<usize>
+{
+    let quote = text[start];
+
+    let mut id = start + 1;
+    while id < text.len()
+    {
+        match text[id]
+        {
+            b'\\' => id += 1,
+            byte if byte == quote => return Some(id),
+            _ => ()
+        }
+
+        id += 1;
+    }
+
+    None
+}
+
+// the last byte of the comment starting with the slash at start, a lone slash is left
+// for the parser to complain about
+fn comment_end(text: &[u8], start: usize) -> Option<usize>
+{
+    let rest = &text[start + 1..];
+
+    match rest.first()
+    {
+        Some(b'/') => rest.iter().position(|byte| *byte == b'\n').map(|id| start + 1 + id),
+        Some(b'*') => rest[1..].windows(2).position(|pair| pair == b"*/").map(|id| start + 3 + id),
+        _ => Some(start)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::Parser;
+
+    use super::*;
+
+
+    // enough elements that it gets cut into a few chunks, with brackets and commas in strings
+    // and nested values so a split in the wrong place would show
+    fn big_list() -> String
+    {
+        let elements: Vec<String> = (0..20_000).map(|id|
+        {
+            format!(r#"{{"id": {id}, "name": "a, [b] {{c}} \"{id}\"", "tags": [{id}, [], {{}}]}}"#)
+        }).collect();
+
+        format!(" [{}] ", elements.join(",\n"))
+    }
+
+    #[test]
+    fn splits_at_the_root_commas()
+    {
+        let text = big_list();
+        let ranges = split(text.as_bytes(), &ParserOptions::default(), 4).unwrap();
+
+        assert!(ranges.len() > 1);
+        assert_eq!(ranges[0].start, 2);
+        assert_eq!(ranges.last().unwrap().end, text.len() - 1);
+
+        ranges.windows(2).for_each(|pair|
+        {
+            assert_eq!(text.as_bytes()[pair[0].end], b',');
+            assert_eq!(pair[0].end + 1, pair[1].start);
+        });
+    }
+
+    #[test]
+    fn nothing_to_split()
+    {
+        let options = ParserOptions::default();
+
+        assert_eq!(split(b"{\"a\": [1, 2]}", &options, 4), None);
+        assert_eq!(split(b"[1, 2]", &options, 4), None);
+        assert_eq!(split(b"[\"unterminated", &options, 4), None);
+    }
+
+    #[test]
+    fn same_as_parse()
+    {
+        let text = big_list();
+
+        assert_eq!(Parser::new(text.chars()).parse_parallel(), Parser::new(text.chars()).parse());
+
+        let broken = text.replacen("19999", "1x", 1);
+
+        let err = Parser::new(broken.chars()).parse_parallel();
+
+        assert!(err.is_err());
+        assert_eq!(err, Parser::new(broken.chars()).parse());
+
+        // past the closing bracket only the last chunk can see
+        let trailing = format!("{text} 1");
+
+        assert_eq!(Parser::new(trailing.chars()).parse_parallel(), Parser::new(trailing.chars()).parse());
+    }
+}
@@ -1,318 +1,4798 @@
 use std::{
-    ops::Index,
+    fmt,
+    cmp,
+    borrow::Cow,
+    io::{self, Read},
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher, DefaultHasher},
+    ops::{Index, IndexMut, Range},
     str::Chars,
-    iter::Peekable
+    sync::{Arc, OnceLock, atomic::{AtomicBool, Ordering}},
+    time::{Duration, Instant}
 };
 
+use crate::{
+    lexer::Position,
+    reader::ReaderChars,
+    borrowed::{BorrowedValue, RawValue},
+    builder::ObjectBuilder,
+    events::Handler,
+    tape::{Tape, TapeBuilder},
+    tokenizer::Tokenizer,
+    stream::Documents,
+    intern::SharedInterner,
+    dedup::{Deduplicator, DedupReport}
+};
+
+#[cfg(feature = "async")]
+use crate::async_reader::AsyncParser;
+
+#[cfg(feature = "simd")]
+use crate::simd;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+use crate::parallel;
+
+
+pub(crate) fn verify_char(text: &mut TextIter, expected: char) -> Result<(), ParseError>
+{
+    text.skip_whitespace();
+
+    if text.peek() != Some(&expected)
+    {
+        return Err(text.unexpected(&format!("{expected:?}")));
+    }
+
+    text.next();
+
+    // quotes are part of the string token
+    if expected != '"'
+    {
+        text.record(|report| report.report.tokens += 1);
+    }
+
+    Ok(())
+}
+
+// a string or, if the options allow it, a bare identifier
+pub(crate) fn parse_key<'a>(text: &mut TextIter<'a>) -> Result<Cow<'a, str>, ParseError>
+{
+    text.skip_whitespace();
+
+    match text.peek().copied()
+    {
+        Some(c) if text.options.allows_unquoted_keys() && is_identifier_start(c) => parse_identifier(text),
+        _ => parse_text_borrowed(text)
+    }
+}
+
+// a quoted key by itself with its escapes decoded, none if its broken
+pub(crate) fn decode_key(raw: &str) -> Option<Cow<'_, str>>
+{
+    let mut text = TextIter::new(raw, 0, ParserOptions::default().dialect(Dialect::Json5));
+
+    let key = parse_text_borrowed(&mut text).ok()?;
+
+    text.peek().is_none().then_some(key)
+}
+
+// close enough to what javascript allows, without the escapes
+fn is_identifier_start(c: char) -> bool
+{
+    c.is_alphabetic() || matches!(c, '$' | '_')
+}
+
+fn is_identifier_part(c: char) -> bool
+{
+    c.is_alphanumeric() || matches!(c, '$' | '_')
+}
+
+fn parse_identifier<'a>(text: &mut TextIter<'a>) -> Result<Cow<'a, str>, ParseError>
+{
+    let start = text.offset();
+
+    if text.slice(start, start).is_none()
+    {
+        let mut identifier = String::new();
+        while let Some(c) = text.next_if(|c| is_identifier_part(*c))
+        {
+            identifier.push(c);
+        }
+
+        return Ok(Cow::Owned(identifier));
+    }
+
+    while text.next_if(|c| is_identifier_part(*c)).is_some() {}
+
+    let end = text.offset();
+
+    text.text_slice(start, end).map(Cow::Borrowed)
+}
+
+fn take_word(text: &mut TextIter) -> String
+{
+    let mut word = String::new();
+    while let Some(c) = text.next_if(char::is_ascii_alphabetic)
+    {
+        word.push(c);
+    }
+
+    word
+}
+
+// the way python writes them, without a sign
+pub(crate) fn non_finite(word: &str, case_insensitive: bool) -> Option<f64>
+{
+    let is = |name: &str| if case_insensitive { word.eq_ignore_ascii_case(name) } else { word == name };
+
+    if is("NaN")
+    {
+        Some(f64::NAN)
+    } else if is("Infinity")
+    {
+        Some(f64::INFINITY)
+    } else
+    {
+        None
+    }
+}
+
+// like verify_char for whichever quotes the options allow, gives back the one it found
+fn verify_quote(text: &mut TextIter) -> Result<char, ParseError>
+{
+    text.skip_whitespace();
+
+    match text.peek().copied()
+    {
+        Some(c) if text.options.is_quote(c) =>
+        {
+            text.next();
+
+            Ok(c)
+        },
+        _ => Err(text.unexpected("'\"'"))
+    }
+}
+
+fn parse_text(text: &mut TextIter) -> Result<String, ParseError>
+{
+    let quote = verify_quote(text)?;
+
+    parse_text_rest(text, String::new(), quote)
+}
+
+// strings without escapes are left pointing into the text, only a reader source always copies
+pub(crate) fn parse_text_borrowed<'a>(text: &mut TextIter<'a>) -> Result<Cow<'a, str>, ParseError>
+{
+    let quote = verify_quote(text)?;
+
+    let start = text.offset();
+
+    if text.slice(start, start).is_none()
+    {
+        return parse_text_rest(text, String::new(), quote).map(Cow::Owned);
+    }
+
+    let control_characters = text.options.control_characters;
+    let max_length = text.options.max_string_length;
+
+    loop
+    {
+        #[cfg(feature = "simd")]
+        {
+            let limit = max_length.saturating_add(1).saturating_sub(text.offset() - start);
+
+            text.skip_bytes(limit, |bytes| simd::plain_text_len(bytes, quote as u8));
+        }
+
+        let offset = text.offset();
+
+        // the slow way is what reports it
+        if offset - start > max_length
+        {
+            break;
+        }
+
+        match text.peek().copied()
+        {
+            Some(c) if c == quote =>
+            {
+                text.next();
+
+                return text.text_slice(start, offset).map(Cow::Borrowed);
+            },
+            // escapes and errors go the slow way
+            Some('\\') | None => break,
+            Some(c) if (c as u32) < 0x20 && !control_characters => break,
+            Some(_) =>
+            {
+                text.next();
+            }
+        }
+    }
+
+    let offset = text.offset();
+    let value = text.text_slice(start, offset)?.to_owned();
+
+    parse_text_rest(text, value, quote).map(Cow::Owned)
+}
+
+// everything after the opening quote
+fn parse_text_rest(text: &mut TextIter, mut value: String, quote: char) -> Result<String, ParseError>
+{
+    loop
+    {
+        #[cfg(feature = "simd")]
+        {
+            let limit = text.options.max_string_length.saturating_add(1).saturating_sub(value.len());
+
+            value.push_str(text.skip_bytes(limit, |bytes| simd::plain_text_len(bytes, quote as u8)));
+        }
+
+        let offset = text.offset();
+
+        if value.len() > text.options.max_string_length
+        {
+            let message = format!("string is longer than the limit of {} bytes", text.options.max_string_length);
+
+            return Err(text.limit_error(message));
+        }
+
+        match text.next()
+        {
+            Some(c) if c == quote => return Ok(value),
+            Some('\\') => parse_escape(text, &mut value)?,
+            Some(c) if (c as u32) < 0x20 && !text.options.control_characters =>
+            {
+                return Err(text.error_at(offset, Some(c), format!("unescaped control character in string: {:#04x}", c as u32)));
+            },
+            Some(c) => value.push(c),
+            None => return Err(text.error_at(offset, None, "unterminated string"))
+        }
+    }
+}
+
+fn parse_escape(text: &mut TextIter, value: &mut String) -> Result<(), ParseError>
+{
+    let offset = text.offset();
+
+    let c = match text.next()
+    {
+        Some('"') => '"',
+        Some('\'') if text.options.allows_single_quotes() => '\'',
+        Some('\\') => '\\',
+        Some('/') => '/',
+        Some('b') => '\u{8}',
+        Some('f') => '\u{c}',
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('t') => '\t',
+        Some('u') =>
+        {
+            let code = parse_hex4(text)?;
+
+            return parse_code_point(text, value, code, offset);
+        },
+        Some(c) => return Err(text.error_at(offset, Some(c), format!("invalid escape in string: '\\{c}'"))),
+        None => return Err(text.error_at(offset, None, "unterminated string"))
+    };
+
+    value.push(c);
+
+    Ok(())
+}
+
+// characters outside the basic plane come as a high surrogate escape followed right away by a low one
+fn parse_code_point(text: &mut TextIter, value: &mut String, code: u32, offset: usize) -> Result<(), ParseError>
+{
+    if let Some(c) = char::from_u32(code)
+    {
+        value.push(c);
+
+        return Ok(());
+    }
+
+    let lone = |text: &TextIter|
+    {
+        if text.options.lone_surrogates
+        {
+            Ok(char::REPLACEMENT_CHARACTER)
+        } else
+        {
+            Err(text.error_at(offset, Some('u'), format!("lone surrogate in string: \\u{code:04x}")))
+        }
+    };
+
+    if !(0xd800..0xdc00).contains(&code) || text.peek() != Some(&'\\')
+    {
+        value.push(lone(text)?);
+
+        return Ok(());
+    }
+
+    text.next();
+
+    let next_offset = text.offset();
+    if text.next_if(|c| *c == 'u').is_none()
+    {
+        value.push(lone(text)?);
+
+        // the backslash starts some other escape
+        return parse_escape(text, value);
+    }
+
+    let low = parse_hex4(text)?;
+
+    if (0xdc00..0xe000).contains(&low)
+    {
+        let combined = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+        match char::from_u32(combined)
+        {
+            Some(c) => value.push(c),
+            None => return Err(text.internal_error("surrogate pair didnt combine into a character"))
+        }
+
+        return Ok(());
+    }
+
+    value.push(lone(text)?);
+
+    parse_code_point(text, value, low, next_offset)
+}
+
+fn parse_hex4(text: &mut TextIter) -> Result<u32, ParseError>
+{
+    (0..4).try_fold(0, |code, _|
+    {
+        let offset = text.offset();
+        let found = text.next();
+
+        let digit = found.and_then(|c| c.to_digit(16))
+            .ok_or_else(|| text.error_at(offset, found, "\\u escape must have 4 hex digits"))?;
+
+        Ok((code << 4) | digit)
+    })
+}
+
+pub(crate) fn pointer_tokens(pointer: &str) -> Option<impl Iterator<Item=String> + '_>
+{
+    let tokens = pointer.strip_prefix('/')?.split('/').map(|token|
+    {
+        token.replace("~1", "/").replace("~0", "~")
+    });
+
+    Some(tokens)
+}
+
+pub fn escape_pointer_token(token: &str) -> String
+{
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub(crate) fn split_pointer(pointer: &str) -> Option<(&str, String)>
+{
+    let (parent, token) = pointer.rsplit_once('/')?;
+
+    Some((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+// nan is equal to itself so a value is always equal to its clone, 0.0 and -0.0 are equal like with ==
+fn same_float(a: f64, b: f64) -> bool
+{
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+// structural equality, key order matters
+fn same_value(a: &ObjectValue, b: &ObjectValue) -> bool
+{
+    match (a, b)
+    {
+        (ObjectValue::Null, ObjectValue::Null) => true,
+        (ObjectValue::Text(a), ObjectValue::Text(b)) => a == b,
+        (ObjectValue::Number(a), ObjectValue::Number(b)) => a == b,
+        (ObjectValue::Float(a), ObjectValue::Float(b)) => same_float(*a, *b),
+        (ObjectValue::Bool(a), ObjectValue::Bool(b)) => a == b,
+        (ObjectValue::List(a), ObjectValue::List(b)) =>
+        {
+            Arc::ptr_eq(a, b) || (a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b)))
+        },
+        (ObjectValue::Object(a), ObjectValue::Object(b)) =>
+        {
+            Arc::ptr_eq(a, b) || (a.fields.len() == b.fields.len() && a.fields.iter().zip(b.fields.iter()).all(|(a, b)|
+            {
+                a.key == b.key && same_value(&a.value, &b.value)
+            }))
+        },
+        _ => false
+    }
+}
+
+pub enum Matcher<'a>
+{
+    Value(ObjectValue),
+    Predicate(&'a dyn Fn(&ObjectValue) -> bool),
+    // only matches strings, a string replacement can use the capture groups like $1
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex)
+}
+
+impl Matcher<'_>
+{
+    fn replacement(&self, value: &ObjectValue, replacement: &ObjectValue) -> Option<ObjectValue>
+    {
+        match self
+        {
+            Self::Value(x) => same_value(x, value).then(|| replacement.clone()),
+            Self::Predicate(predicate) => predicate(value).then(|| replacement.clone()),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) =>
+            {
+                let text = value.get_text().filter(|text| regex.is_match(text))?;
+
+                let replaced = match replacement
+                {
+                    ObjectValue::Text(template) => ObjectValue::Text(regex.replace_all(text, &**template).into()),
+                    x => x.clone()
+                };
+
+                Some(replaced)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ObjectValue
+{
+    Null,
+    Text(Arc<str>),
+    Number(i64),
+    // anything with a fraction or an exponent
+    Float(f64),
+    Bool(bool),
+    List(Arc<Vec<ObjectValue>>),
+    Object(Arc<Object>)
+}
+
+impl ObjectValue
+{
+    pub fn get_text(&self) -> Option<&str>
+    {
+        match self
+        {
+            ObjectValue::Text(x) => Some(&**x),
+            _ => None
+        }
+    }
+
+    pub fn get_number(&self) -> Option<i64>
+    {
+        match self
+        {
+            ObjectValue::Number(x) => Some(*x),
+            _ => None
+        }
+    }
+
+    // integers convert too, big ones lose precision
+    pub fn get_float(&self) -> Option<f64>
+    {
+        match self
+        {
+            ObjectValue::Float(x) => Some(*x),
+            ObjectValue::Number(x) => Some(*x as f64),
+            _ => None
+        }
+    }
+
+    // unlike get_number floats work too as long as they are whole and fit
+    pub fn as_i64(&self) -> Option<i64>
+    {
+        match self
+        {
+            ObjectValue::Number(x) => Some(*x),
+            ObjectValue::Float(x) if x.fract() == 0.0 && (-I64_LIMIT..I64_LIMIT).contains(x) => Some(*x as i64),
+            _ => None
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64>
+    {
+        match self
+        {
+            ObjectValue::Number(x) => u64::try_from(*x).ok(),
+            ObjectValue::Float(x) if x.fract() == 0.0 && (0.0..U64_LIMIT).contains(x) => Some(*x as u64),
+            _ => None
+        }
+    }
+
+    // unlike get_float integers only convert if no precision gets lost
+    pub fn as_f64(&self) -> Option<f64>
+    {
+        match self
+        {
+            ObjectValue::Float(x) => Some(*x),
+            ObjectValue::Number(x) =>
+            {
+                let float = *x as f64;
+
+                // i64::MAX rounds up to 2^63, which would saturate back to i64::MAX
+                (float < I64_LIMIT && float as i64 == *x).then_some(float)
+            },
+            _ => None
+        }
+    }
+
+    pub fn is_null(&self) -> bool
+    {
+        matches!(self, ObjectValue::Null)
+    }
+
+    pub fn get_null(&self) -> Option<()>
+    {
+        match self
+        {
+            ObjectValue::Null => Some(()),
+            _ => None
+        }
+    }
+
+    pub fn get_bool(&self) -> Option<bool>
+    {
+        match self
+        {
+            ObjectValue::Bool(x) => Some(*x),
+            _ => None
+        }
+    }
+
+    pub fn get_list(&self) -> Option<&[ObjectValue]>
+    {
+        match self
+        {
+            ObjectValue::List(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_object(&self) -> Option<&Object>
+    {
+        match self
+        {
+            ObjectValue::Object(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_number_mut(&mut self) -> Option<&mut i64>
+    {
+        match self
+        {
+            ObjectValue::Number(x) => Some(x),
+            _ => None
+        }
+    }
+
+    // only actual floats, an integer stays an integer
+    pub fn get_float_mut(&mut self) -> Option<&mut f64>
+    {
+        match self
+        {
+            ObjectValue::Float(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn get_bool_mut(&mut self) -> Option<&mut bool>
+    {
+        match self
+        {
+            ObjectValue::Bool(x) => Some(x),
+            _ => None
+        }
+    }
+
+    // lists and objects shared with other values get copied first so only this one changes
+    pub fn get_list_mut(&mut self) -> Option<&mut [ObjectValue]>
+    {
+        match self
+        {
+            ObjectValue::List(x) => Some(Arc::make_mut(x).as_mut_slice()),
+            _ => None
+        }
+    }
+
+    pub fn get_object_mut(&mut self) -> Option<&mut Object>
+    {
+        match self
+        {
+            ObjectValue::Object(x) => Some(Arc::make_mut(x)),
+            _ => None
+        }
+    }
+
+    // the list methods panic on anything that isnt a list, same as indexing
+    pub fn push(&mut self, value: ObjectValue)
+    {
+        self.list_mut("push to").push(value);
+    }
+
+    pub fn pop(&mut self) -> Option<ObjectValue>
+    {
+        self.list_mut("pop from").pop()
+    }
+
+    pub fn insert(&mut self, id: usize, value: ObjectValue)
+    {
+        self.list_mut("insert into").insert(id, value);
+    }
+
+    pub fn remove(&mut self, id: usize) -> ObjectValue
+    {
+        self.list_mut("remove from").remove(id)
+    }
+
+    fn list_mut(&mut self, action: &str) -> &mut Vec<ObjectValue>
+    {
+        match self
+        {
+            ObjectValue::List(list) => Arc::make_mut(list),
+            x => panic!("cant {action} a value of type: {:?}", x)
+        }
+    }
+
+    pub fn try_get_text(&self) -> Result<&str, TypeError>
+    {
+        self.get_text().ok_or_else(|| self.type_error("string"))
+    }
+
+    pub fn try_get_number(&self) -> Result<i64, TypeError>
+    {
+        self.get_number().ok_or_else(|| self.type_error("integer"))
+    }
+
+    pub fn try_get_float(&self) -> Result<f64, TypeError>
+    {
+        self.get_float().ok_or_else(|| self.type_error("number"))
+    }
+
+    pub fn try_get_null(&self) -> Result<(), TypeError>
+    {
+        self.get_null().ok_or_else(|| self.type_error("null"))
+    }
+
+    pub fn try_get_bool(&self) -> Result<bool, TypeError>
+    {
+        self.get_bool().ok_or_else(|| self.type_error("bool"))
+    }
+
+    pub fn try_get_list(&self) -> Result<&[ObjectValue], TypeError>
+    {
+        self.get_list().ok_or_else(|| self.type_error("array"))
+    }
+
+    pub fn try_get_object(&self) -> Result<&Object, TypeError>
+    {
+        self.get_object().ok_or_else(|| self.type_error("object"))
+    }
+
+    pub(crate) fn type_error(&self, expected: &'static str) -> TypeError
+    {
+        // integers and floats are both numbers to type_name but not to get_number
+        let found = match self
+        {
+            ObjectValue::Number(_) => "integer",
+            ObjectValue::Float(_) => "float",
+            x => x.type_name()
+        };
+
+        TypeError{expected, found}
+    }
+
+    pub fn type_name(&self) -> &'static str
+    {
+        match self
+        {
+            ObjectValue::Null => "null",
+            ObjectValue::Text(_) => "string",
+            ObjectValue::Number(_) | ObjectValue::Float(_) => "number",
+            ObjectValue::Bool(_) => "bool",
+            ObjectValue::List(_) => "array",
+            ObjectValue::Object(_) => "object"
+        }
+    }
+
+    // like == except objects need their keys in the same order too
+    pub fn eq_ordered(&self, other: &ObjectValue) -> bool
+    {
+        same_value(self, other)
+    }
+
+    pub fn pointer(&self, pointer: &str) -> Option<&ObjectValue>
+    {
+        if pointer.is_empty()
+        {
+            return Some(self);
+        }
+
+        pointer_tokens(pointer)?.try_fold(self, |value, token|
+        {
+            match value
+            {
+                ObjectValue::List(list) => token.parse::<usize>().ok().and_then(|id| list.get(id)),
+                ObjectValue::Object(object) => object.get(&token),
+                _ => None
+            }
+        })
+    }
+
+    // like a pointer but a * token matches every child of a list or object
+    pub fn query(&self, path: &str) -> Vec<&ObjectValue>
+    {
+        if path.is_empty()
+        {
+            return vec![self];
+        }
+
+        let tokens = match pointer_tokens(path)
+        {
+            Some(x) => x,
+            None => return Vec::new()
+        };
+
+        tokens.fold(vec![self], |values, token|
+        {
+            values.into_iter().flat_map(|value|
+            {
+                let children: Vec<&ObjectValue> = match value
+                {
+                    ObjectValue::List(list) if token == "*" => list.iter().collect(),
+                    ObjectValue::Object(object) if token == "*" =>
+                    {
+                        object.fields.iter().map(|field| &field.value).collect()
+                    },
+                    ObjectValue::List(list) => token.parse::<usize>().ok().and_then(|id| list.get(id))
+                        .into_iter().collect(),
+                    ObjectValue::Object(object) => object.get(&token).into_iter().collect(),
+                    _ => Vec::new()
+                };
+
+                children
+            }).collect()
+        })
+    }
+
+    pub fn with_pointer(&self, pointer: &str, value: ObjectValue) -> Option<ObjectValue>
+    {
+        let mut this = self.clone();
+
+        this.set_pointer(pointer, value)?;
+
+        Some(this)
+    }
+
+    pub fn set_pointer(&mut self, pointer: &str, value: ObjectValue) -> Option<()>
+    {
+        let (parent, token) = match split_pointer(pointer)
+        {
+            Some(x) => x,
+            None if pointer.is_empty() =>
+            {
+                *self = value;
+
+                return Some(());
+            },
+            None => return None
+        };
+
+        match self.pointer_make_mut(parent)?
+        {
+            ObjectValue::List(list) =>
+            {
+                let id = token.parse::<usize>().ok().filter(|id| *id < list.len())?;
+
+                Arc::make_mut(list)[id] = value;
+            },
+            ObjectValue::Object(object) =>
+            {
+                Arc::make_mut(object).insert(token, value);
+            },
+            _ => return None
+        }
+
+        Some(())
+    }
+
+    pub fn remove_pointer(&mut self, pointer: &str) -> Option<ObjectValue>
+    {
+        let (parent, token) = split_pointer(pointer)?;
+
+        match self.pointer_make_mut(parent)?
+        {
+            ObjectValue::List(list) =>
+            {
+                let id = token.parse::<usize>().ok().filter(|id| *id < list.len())?;
+
+                Some(Arc::make_mut(list).remove(id))
+            },
+            ObjectValue::Object(object) =>
+            {
+                object.position(&token)?;
+
+                Arc::make_mut(object).remove(&token)
+            },
+            _ => None
+        }
+    }
+
+    // layers other on top of this like a config file over the defaults, objects get merged key by
+    // key all the way down and anything else from other replaces whats here, null included
+    pub fn merge(&mut self, other: ObjectValue, strategy: MergeStrategy)
+    {
+        match (self, other)
+        {
+            (ObjectValue::Object(this), ObjectValue::Object(other)) =>
+            {
+                let this = Arc::make_mut(this);
+
+                other.fields.iter().for_each(|field|
+                {
+                    match this.entry(field.key.clone())
+                    {
+                        Entry::Occupied(mut entry) => entry.get_mut().merge(field.value.clone(), strategy),
+                        Entry::Vacant(entry) =>
+                        {
+                            entry.insert(field.value.clone());
+                        }
+                    }
+                });
+            },
+            (ObjectValue::List(this), ObjectValue::List(other)) if strategy != MergeStrategy::Replace =>
+            {
+                let this = Arc::make_mut(this);
+
+                match strategy
+                {
+                    MergeStrategy::Append => this.extend(other.iter().cloned()),
+                    _ =>
+                    {
+                        let overlap = this.len().min(other.len());
+
+                        this.iter_mut().zip(other.iter()).for_each(|(value, other)| value.merge(other.clone(), strategy));
+                        this.extend(other[overlap..].iter().cloned());
+                    }
+                }
+            },
+            (this, other) => *this = other
+        }
+    }
+
+    // json merge patch from rfc 7386, an object patch goes key by key with null removing the key
+    // and anything else replaces the whole value, lists included
+    pub fn apply_merge_patch(&mut self, patch: &ObjectValue)
+    {
+        let patch = match patch
+        {
+            ObjectValue::Object(patch) => patch,
+            patch =>
+            {
+                *self = patch.clone();
+
+                return;
+            }
+        };
+
+        if !matches!(self, ObjectValue::Object(_))
+        {
+            *self = Object::default().into();
+        }
+
+        let this = match self
+        {
+            ObjectValue::Object(this) => Arc::make_mut(this),
+            _ => return
+        };
+
+        patch.fields.iter().for_each(|field|
+        {
+            if field.value.is_null()
+            {
+                // every duplicate goes, after the patch the key isnt there at all
+                while this.remove(&field.key).is_some() {}
+
+                return;
+            }
+
+            match this.entry(field.key.clone())
+            {
+                Entry::Occupied(mut entry) => entry.get_mut().apply_merge_patch(&field.value),
+                Entry::Vacant(entry) => entry.insert(ObjectValue::Null).apply_merge_patch(&field.value)
+            }
+        });
+    }
+
+    // the patch that apply_merge_patch needs to turn this into target, a null inside of an object
+    // in target cant be written as a merge patch so those keys end up missing instead
+    pub fn create_merge_patch(&self, target: &ObjectValue) -> ObjectValue
+    {
+        let (this, target) = match (self, target)
+        {
+            (ObjectValue::Object(this), ObjectValue::Object(target)) => (this, target),
+            (_, target) => return target.clone()
+        };
+
+        let mut patch = Object::default();
+
+        this.fields.iter().filter(|field| target.get(&field.key).is_none()).for_each(|field|
+        {
+            patch.insert(field.key.clone(), ObjectValue::Null);
+        });
+
+        target.fields.iter().for_each(|field|
+        {
+            match this.get(&field.key)
+            {
+                Some(value) if *value == field.value => (),
+                Some(value) =>
+                {
+                    patch.insert(field.key.clone(), value.create_merge_patch(&field.value));
+                },
+                None =>
+                {
+                    patch.insert(field.key.clone(), field.value.clone());
+                }
+            }
+        });
+
+        patch.into()
+    }
+
+    pub fn sort_keys(&mut self)
+    {
+        match self
+        {
+            ObjectValue::List(list) =>
+            {
+                Arc::make_mut(list).iter_mut().for_each(ObjectValue::sort_keys);
+            },
+            ObjectValue::Object(object) =>
+            {
+                let object = Arc::make_mut(object);
+
+                object.sort_keys();
+                object.fields.iter_mut().for_each(|field| field.value.sort_keys());
+            },
+            _ => ()
+        }
+    }
+
+    // same as sort_keys but keeps the span children lined up with the fields
+    fn sort_keys_spanned(&mut self, spans: &mut SpanTree)
+    {
+        match self
+        {
+            ObjectValue::List(list) =>
+            {
+                Arc::make_mut(list).iter_mut().zip(spans.children.iter_mut()).for_each(|(value, spans)|
+                {
+                    value.sort_keys_spanned(spans);
+                });
+            },
+            ObjectValue::Object(object) =>
+            {
+                let object = Arc::make_mut(object);
+
+                let fields = std::mem::take(&mut object.fields);
+                let children = std::mem::take(&mut spans.children);
+
+                let mut pairs: Vec<_> = fields.into_iter().zip(children).collect();
+                pairs.sort_by(|(a, _), (b, _)| a.key.cmp(&b.key));
+
+                let (fields, children): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+
+                object.fields = fields;
+                object.sorted = true;
+                object.index = OnceLock::new();
+
+                spans.children = children;
+
+                object.fields.iter_mut().zip(spans.children.iter_mut()).for_each(|(field, spans)|
+                {
+                    field.value.sort_keys_spanned(spans);
+                });
+            },
+            _ => ()
+        }
+    }
+
+    // same as pointer_make_mut but by position, so duplicate keys can still be told apart
+    pub(crate) fn child_make_mut(&mut self, id: usize) -> Option<&mut ObjectValue>
+    {
+        match self
+        {
+            ObjectValue::List(list) if id < list.len() => Some(&mut Arc::make_mut(list)[id]),
+            ObjectValue::Object(object) if id < object.fields.len() =>
+            {
+                Some(&mut Arc::make_mut(object).fields[id].value)
+            },
+            _ => None
+        }
+    }
+
+    // clones any node shared with another version on the way down, so edits only copy the touched path
+    fn pointer_make_mut(&mut self, pointer: &str) -> Option<&mut ObjectValue>
+    {
+        if pointer.is_empty()
+        {
+            return Some(self);
+        }
+
+        pointer_tokens(pointer)?.try_fold(self, |value, token|
+        {
+            match value
+            {
+                ObjectValue::List(list) =>
+                {
+                    let id = token.parse::<usize>().ok().filter(|id| *id < list.len())?;
+
+                    Some(&mut Arc::make_mut(list)[id])
+                },
+                ObjectValue::Object(object) =>
+                {
+                    let id = object.position(&token)?;
+
+                    Some(&mut Arc::make_mut(object).fields[id].value)
+                },
+                _ => None
+            }
+        })
+    }
+
+    // spans has to be what parse_spanned gave for this value and text what it parsed
+    pub fn spanned<'a>(&'a self, spans: &'a SpanTree, text: &'a str) -> Spanned<'a>
+    {
+        Spanned{value: self, spans, text}
+    }
+
+    // calls f on every value in the tree depth first along with its json pointer
+    pub fn walk(&self, mut f: impl FnMut(&str, &ObjectValue))
+    {
+        self.walk_inner(None, &mut String::new(), &mut |pointer, value, _| f(pointer, value));
+    }
+
+    pub fn walk_spanned(&self, spans: &SpanTree, mut f: impl FnMut(&str, &ObjectValue, &SpanTree))
+    {
+        self.walk_inner(Some(spans), &mut String::new(), &mut |pointer, value, spans|
+        {
+            f(pointer, value, spans.expect("spans are always given"))
+        });
+    }
+
+    fn walk_inner(
+        &self,
+        spans: Option<&SpanTree>,
+        pointer: &mut String,
+        f: &mut dyn FnMut(&str, &ObjectValue, Option<&SpanTree>)
+    )
+    {
+        f(pointer, self, spans);
+
+        let mut walk_child = |token: &str, value: &ObjectValue, id: usize|
+        {
+            let length = pointer.len();
+
+            pointer.push('/');
+            pointer.push_str(&escape_pointer_token(token));
+
+            value.walk_inner(spans.map(|spans| &spans.children[id]), pointer, f);
+
+            pointer.truncate(length);
+        };
+
+        match self
+        {
+            ObjectValue::List(list) =>
+            {
+                list.iter().enumerate().for_each(|(id, value)| walk_child(&id.to_string(), value, id));
+            },
+            ObjectValue::Object(object) =>
+            {
+                object.fields.iter().enumerate().for_each(|(id, field)| walk_child(&field.key, &field.value, id));
+            },
+            _ => ()
+        }
+    }
+
+    // replaced values arent searched again, returns how many got replaced
+    pub fn replace_all(&mut self, matcher: &Matcher, replacement: &ObjectValue) -> usize
+    {
+        let mut found = Vec::new();
+        self.find_matches(matcher, replacement, &mut Vec::new(), &mut found);
+
+        // the paths were just found in this tree so they all resolve, but a miss is skipped
+        // instead of counted rather than trusting that
+        found.into_iter().filter_map(|(path, value)|
+        {
+            let target = path.iter().try_fold(&mut *self, |value, id| value.child_make_mut(*id))?;
+
+            *target = value;
+
+            Some(())
+        }).count()
+    }
+
+    fn find_matches(
+        &self,
+        matcher: &Matcher,
+        replacement: &ObjectValue,
+        path: &mut Vec<usize>,
+        found: &mut Vec<(Vec<usize>, ObjectValue)>
+    )
+    {
+        if let Some(value) = matcher.replacement(self, replacement)
+        {
+            found.push((path.clone(), value));
+
+            return;
+        }
+
+        let mut visit = |id: usize, value: &ObjectValue|
+        {
+            path.push(id);
+            value.find_matches(matcher, replacement, path, found);
+            path.pop();
+        };
+
+        match self
+        {
+            ObjectValue::List(list) => list.iter().enumerate().for_each(|(id, value)| visit(id, value)),
+            ObjectValue::Object(object) => object.fields.iter().enumerate().for_each(|(id, field)| visit(id, &field.value)),
+            _ => ()
+        }
+    }
+
+    pub fn filter_list<P>(&self, mut predicate: P) -> Option<impl Iterator<Item=&ObjectValue>>
+    where
+        P: FnMut(&ObjectValue) -> bool
+    {
+        self.get_list().map(|list| list.iter().filter(move |value| predicate(value)))
+    }
+
+    pub fn select<P>(&self, pointer: &str, predicate: P) -> Option<impl Iterator<Item=&ObjectValue>>
+    where
+        P: FnMut(&ObjectValue) -> bool
+    {
+        self.pointer(pointer)?.filter_list(predicate)
+    }
+
+    pub fn parse(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        text.check_interrupt()?;
+
+        text.skip_whitespace();
+
+        let beginning = match text.peek()
+        {
+            Some(c) => *c,
+            None => return Err(text.unexpected("a value"))
+        };
+
+        text.begin_span();
+
+        let case_insensitive = text.options.case_insensitive_literals;
+
+        let value = match if case_insensitive { beginning.to_ascii_lowercase() } else { beginning }
+        {
+            c @ ('[' | '{') =>
+            {
+                text.enter()?;
+                text.record(ReportRecorder::enter);
+
+                let value = if c == '[' { Self::parse_list(text)? } else { Self::parse_object(text)? };
+
+                text.record(|report| report.depth -= 1);
+                text.leave();
+
+                value
+            },
+            c =>
+            {
+                let started = text.lex_start();
+
+                let json5 = text.options.dialect == Dialect::Json5;
+                let non_finite = text.options.allows_nan_and_infinity();
+
+                let value = match c
+                {
+                    c if text.options.is_quote(c) => Self::parse_text(text)?,
+                    'n' | 'N' | 'i' | 'I' if non_finite => Self::parse_named(text)?,
+                    'f' | 't' => Self::parse_bool(text)?,
+                    'n' => Self::parse_null(text)?,
+                    n if n.is_ascii_digit() || n == '-' => Self::parse_number(text)?,
+                    '+' | '.' if json5 => Self::parse_number(text)?,
+                    _ => return Err(text.unexpected("a value"))
+                };
+
+                text.lex_end(started);
+
+                value
+            }
+        };
+
+        text.record(|report| report.count(&value));
+        text.end_span();
+
+        Ok(value)
+    }
+
+    fn parse_text(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let value = parse_text(text)?;
+
+        Ok(Self::Text(text.intern_text(value)))
+    }
+
+    fn parse_number(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let start = text.offset();
+
+        let leading_zeros = text.options.leading_zeros;
+        let json5 = text.options.dialect == Dialect::Json5;
+
+        let mut number = String::new();
+
+        match text.peek()
+        {
+            Some('-') =>
+            {
+                number.push('-');
+                text.next();
+            },
+            Some('+') if json5 =>
+            {
+                text.next();
+            },
+            _ => ()
+        }
+
+        if text.options.allows_nan_and_infinity() && text.peek().map(char::is_ascii_alphabetic).unwrap_or(false)
+        {
+            let word = take_word(text);
+
+            return match non_finite(&word, text.options.case_insensitive_literals)
+            {
+                Some(x) => Ok(Self::Float(if number.is_empty() { x } else { -x })),
+                None => Err(text.error_at(start, number.chars().next(), format!("invalid number: {number}{word}")))
+            };
+        }
+
+        let digits_start = number.len();
+
+        while let Some(c) = text.next_if(|c| c.is_ascii_digit())
+        {
+            if &number[digits_start..] == "0" && !leading_zeros
+            {
+                return Err(text.error_at(start, Some('0'), "numbers cant have leading zeros"));
+            }
+
+            number.push(c);
+        }
+
+        if text.options.allows_hex_numbers() && &number[digits_start..] == "0" && matches!(text.peek(), Some('x' | 'X'))
+        {
+            text.next();
+
+            return Self::parse_hex(text, start, number);
+        }
+
+        // json5 allows either side of the dot to be empty but not both
+        let no_integer = number.len() == digits_start;
+
+        if no_integer && !(json5 && text.peek() == Some(&'.'))
+        {
+            return Err(text.unexpected("a digit"));
+        }
+
+        let mut is_float = false;
+
+        if text.peek() == Some(&'.')
+        {
+            is_float = true;
+            number.push('.');
+            text.next();
+
+            if json5 && !no_integer
+            {
+                while let Some(c) = text.next_if(|c| c.is_ascii_digit())
+                {
+                    number.push(c);
+                }
+            } else
+            {
+                Self::push_digits(text, &mut number)?;
+            }
+        }
+
+        if matches!(text.peek(), Some('e' | 'E'))
+        {
+            is_float = true;
+            number.push('e');
+            text.next();
+
+            if let Some(sign @ ('+' | '-')) = text.peek().copied()
+            {
+                number.push(sign);
+                text.next();
+            }
+
+            Self::push_digits(text, &mut number)?;
+        }
+
+        if !is_float
+        {
+            // json doesnt limit how big integers get, past 64 bits they become the closest float
+            if let Ok(number) = number.parse()
+            {
+                return Ok(Self::Number(number));
+            }
+        }
+
+        let number: f64 = match number.parse()
+        {
+            Ok(number) => number,
+            Err(_) => return Err(text.internal_error(&format!("checked number {number:?} isnt a valid float")))
+        };
+
+        if !number.is_finite()
+        {
+            return Err(text.error_at(start, None, "number is too large for a float"));
+        }
+
+        Ok(Self::Float(number))
+    }
+
+    // number has the sign if there was one, the 0x is already gone
+    fn parse_hex(text: &mut TextIter, start: usize, mut number: String) -> Result<Self, ParseError>
+    {
+        number.pop();
+
+        let sign = number.len();
+
+        while let Some(c) = text.next_if(|c| c.is_ascii_hexdigit())
+        {
+            number.push(c);
+        }
+
+        if number.len() == sign
+        {
+            return Err(text.unexpected("a hex digit"));
+        }
+
+        i64::from_str_radix(&number, 16).map(Self::Number).map_err(|_|
+        {
+            text.error_at(start, number.chars().next(), format!("number 0x{} doesnt fit in 64 bits", &number[sign..]))
+        })
+    }
+
+    // at least one digit for fractions and exponents
+    fn push_digits(text: &mut TextIter, number: &mut String) -> Result<(), ParseError>
+    {
+        let length = number.len();
+
+        while let Some(c) = text.next_if(|c| c.is_ascii_digit())
+        {
+            number.push(c);
+        }
+
+        if number.len() == length
+        {
+            return Err(text.unexpected("a digit"));
+        }
+
+        Ok(())
+    }
+
+    fn parse_bool(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let start = text.offset();
+
+        let case_insensitive = text.options.case_insensitive_literals;
+        let beginning = match text.next()
+        {
+            Some(c) => c,
+            None => return Err(text.unexpected("a value"))
+        };
+
+        let keyword_length = match beginning.to_ascii_lowercase()
+        {
+            't' => 3,
+            'f' => 4,
+            _ => return Err(text.error_at(start, Some(beginning), "expected a boolean"))
+        };
+
+        let mut value = beginning.to_string();
+        value.extend(text.take(keyword_length));
+
+        if case_insensitive
+        {
+            value.make_ascii_lowercase();
+        }
+
+        let value = match value.as_ref()
+        {
+            "true" => true,
+            "false" => false,
+            x => return Err(text.error_at(start, Some(beginning), format!("invalid boolean value: {x}")))
+        };
+
+        Ok(Self::Bool(value))
+    }
+
+    // null, nan or infinity, only called when the last two are allowed
+    fn parse_named(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let start = text.offset();
+
+        let case_insensitive = text.options.case_insensitive_literals;
+        let word = take_word(text);
+
+        let is_null = if case_insensitive { word.eq_ignore_ascii_case("null") } else { word == "null" };
+
+        if is_null
+        {
+            return Ok(Self::Null);
+        }
+
+        non_finite(&word, case_insensitive).map(Self::Float).ok_or_else(||
+        {
+            text.error_at(start, word.chars().next(), format!("invalid literal: {word}"))
+        })
+    }
+
+    fn parse_null(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let start = text.offset();
+
+        let case_insensitive = text.options.case_insensitive_literals;
+        let beginning = match text.next()
+        {
+            Some(c) => c,
+            None => return Err(text.unexpected("a value"))
+        };
+
+        let mut value = beginning.to_string();
+        value.extend(text.take(3));
+
+        if case_insensitive
+        {
+            value.make_ascii_lowercase();
+        }
+
+        if value != "null"
+        {
+            return Err(text.error_at(start, Some(beginning), format!("invalid null value: {value}")));
+        }
+
+        Ok(Self::Null)
+    }
+
+    fn parse_list(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        verify_char(text, '[')?;
+
+        let mut values = Vec::new();
+
+        while let Some(c) = text.skip_whitespace().peek()
+        {
+            if *c == ']'
+            {
+                break;
+            }
+
+            if !values.is_empty()
+            {
+                verify_char(text, ',')?;
+
+                if text.trailing_comma(']')
+                {
+                    break;
+                }
+            }
+
+            text.check_elements(values.len())?;
+            let value = ObjectValue::parse(text)?;
+
+            values.push(value);
+        }
+
+        verify_char(text, ']')?;
+
+        Ok(Self::List(values.into()))
+    }
+
+    fn parse_object(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        Ok(Self::Object(Arc::new(Object::parse(text)?)))
+    }
+}
+
+impl Index<usize> for ObjectValue
+{
+    type Output = ObjectValue;
+
+    fn index(&self, id: usize) -> &Self::Output
+    {
+        match self
+        {
+            ObjectValue::List(list) => &list[id],
+            x => panic!("cant index into a value of type: {:?}", x)
+        }
+    }
+}
+
+impl IndexMut<usize> for ObjectValue
+{
+    fn index_mut(&mut self, id: usize) -> &mut Self::Output
+    {
+        match self
+        {
+            ObjectValue::List(list) => &mut Arc::make_mut(list)[id],
+            x => panic!("cant index into a value of type: {:?}", x)
+        }
+    }
+}
+
+// integers and floats are different types here so 1 and 1.0 arent equal, objects compare
+// without caring about the order of their keys (eq_ordered does care)
+impl PartialEq for ObjectValue
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        match (self, other)
+        {
+            (ObjectValue::Null, ObjectValue::Null) => true,
+            (ObjectValue::Text(a), ObjectValue::Text(b)) => a == b,
+            (ObjectValue::Number(a), ObjectValue::Number(b)) => a == b,
+            (ObjectValue::Float(a), ObjectValue::Float(b)) => same_float(*a, *b),
+            (ObjectValue::Bool(a), ObjectValue::Bool(b)) => a == b,
+            (ObjectValue::List(a), ObjectValue::List(b)) => Arc::ptr_eq(a, b) || a == b,
+            (ObjectValue::Object(a), ObjectValue::Object(b)) => Arc::ptr_eq(a, b) || a == b,
+            _ => false
+        }
+    }
+}
+
+impl Eq for ObjectValue {}
+
+// agrees with ==, so every nan hashes the same and so do 0.0 and -0.0
+impl Hash for ObjectValue
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        std::mem::discriminant(self).hash(state);
+
+        match self
+        {
+            ObjectValue::Null => (),
+            ObjectValue::Text(x) => x.hash(state),
+            ObjectValue::Number(x) => x.hash(state),
+            ObjectValue::Float(x) => normal_float(*x).to_bits().hash(state),
+            ObjectValue::Bool(x) => x.hash(state),
+            ObjectValue::List(x) => x.hash(state),
+            ObjectValue::Object(x) => x.hash(state)
+        }
+    }
+}
+
+// a total order for sorting values deterministically, first by type in the order null, bool,
+// number, string, list, object and then by the value, integers and floats are ordered by what
+// they are as numbers with the integer first when theyre the same number, nan is above every
+// other number and objects go by their fields in key order
+impl Ord for ObjectValue
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering
+    {
+        match (self, other)
+        {
+            (ObjectValue::Null, ObjectValue::Null) => cmp::Ordering::Equal,
+            (ObjectValue::Bool(a), ObjectValue::Bool(b)) => a.cmp(b),
+            (ObjectValue::Number(a), ObjectValue::Number(b)) => a.cmp(b),
+            (ObjectValue::Float(a), ObjectValue::Float(b)) => normal_float(*a).total_cmp(&normal_float(*b)),
+            (ObjectValue::Number(a), ObjectValue::Float(b)) => cmp_integer_float(*a, *b).then(cmp::Ordering::Less),
+            (ObjectValue::Float(a), ObjectValue::Number(b)) => cmp_integer_float(*b, *a).reverse().then(cmp::Ordering::Greater),
+            (ObjectValue::Text(a), ObjectValue::Text(b)) => a.cmp(b),
+            (ObjectValue::List(a), ObjectValue::List(b)) => a.cmp(b),
+            (ObjectValue::Object(a), ObjectValue::Object(b)) => a.cmp(b),
+            (a, b) => type_rank(a).cmp(&type_rank(b))
+        }
+    }
+}
+
+impl PartialOrd for ObjectValue
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering>
+    {
+        Some(self.cmp(other))
+    }
+}
+
+fn type_rank(value: &ObjectValue) -> u8
+{
+    match value
+    {
+        ObjectValue::Null => 0,
+        ObjectValue::Bool(_) => 1,
+        ObjectValue::Number(_) | ObjectValue::Float(_) => 2,
+        ObjectValue::Text(_) => 3,
+        ObjectValue::List(_) => 4,
+        ObjectValue::Object(_) => 5
+    }
+}
+
+// every nan is the same one and -0.0 is 0.0, same as == treats them
+fn normal_float(x: f64) -> f64
+{
+    if x.is_nan() { f64::NAN } else { x + 0.0 }
+}
+
+// exact even where the integer doesnt fit in a float
+fn cmp_integer_float(a: i64, b: f64) -> cmp::Ordering
+{
+    // 2^63 as a float, i64::MAX as f64 rounds up to it
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+    if b.is_nan() || b >= LIMIT
+    {
+        cmp::Ordering::Less
+    } else if b < -LIMIT
+    {
+        cmp::Ordering::Greater
+    } else
+    {
+        let whole = b.trunc();
+
+        a.cmp(&(whole as i64)).then_with(|| 0.0_f64.total_cmp(&(b - whole)))
+    }
+}
+
+// what the json macro and the builders turn the values given to them into
+impl From<bool> for ObjectValue
+{
+    fn from(value: bool) -> Self
+    {
+        Self::Bool(value)
+    }
+}
+
+macro_rules! from_integers
+{
+    ($($integer:ty),*) =>
+    {
+        $(
+            impl From<$integer> for ObjectValue
+            {
+                fn from(value: $integer) -> Self
+                {
+                    Self::Number(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from_integers!(i8, i16, i32, i64, u8, u16, u32);
+
+// same as parsing them, whatever doesnt fit in an i64 becomes the closest float
+impl From<u64> for ObjectValue
+{
+    fn from(value: u64) -> Self
+    {
+        i64::try_from(value).map(Self::Number).unwrap_or(Self::Float(value as f64))
+    }
+}
+
+impl From<usize> for ObjectValue
+{
+    fn from(value: usize) -> Self
+    {
+        (value as u64).into()
+    }
+}
+
+impl From<f32> for ObjectValue
+{
+    fn from(value: f32) -> Self
+    {
+        Self::Float(value.into())
+    }
+}
+
+impl From<f64> for ObjectValue
+{
+    fn from(value: f64) -> Self
+    {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for ObjectValue
+{
+    fn from(value: &str) -> Self
+    {
+        Self::Text(value.into())
+    }
+}
+
+impl From<String> for ObjectValue
+{
+    fn from(value: String) -> Self
+    {
+        Self::Text(value.into())
+    }
+}
+
+impl From<Arc<str>> for ObjectValue
+{
+    fn from(value: Arc<str>) -> Self
+    {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<ObjectValue>> for ObjectValue
+{
+    fn from(value: Vec<ObjectValue>) -> Self
+    {
+        Self::List(Arc::new(value))
+    }
+}
+
+// every pair becomes a field in the same order, repeated keys are all kept like a parse would
+impl<K: Into<Arc<str>>> From<Vec<(K, ObjectValue)>> for ObjectValue
+{
+    fn from(value: Vec<(K, ObjectValue)>) -> Self
+    {
+        let fields = value.into_iter().map(|(key, value)| ObjectField::new(key, value)).collect();
+
+        Self::Object(Arc::new(Object::from_fields(fields)))
+    }
+}
+
+impl From<Object> for ObjectValue
+{
+    fn from(value: Object) -> Self
+    {
+        Self::Object(Arc::new(value))
+    }
+}
+
+// none is null
+impl<T: Into<ObjectValue>> From<Option<T>> for ObjectValue
+{
+    fn from(value: Option<T>) -> Self
+    {
+        value.map(Into::into).unwrap_or(Self::Null)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectField
+{
+    key: Arc<str>,
+    value: ObjectValue
+}
+
+impl ObjectField
+{
+    pub(crate) fn new(key: impl Into<Arc<str>>, value: ObjectValue) -> Self
+    {
+        Self{key: key.into(), value}
+    }
+
+    pub fn parse(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        let start = text.skip_whitespace().offset();
+
+        let started = text.lex_start();
+        let key = parse_key(text)?.into_owned();
+        let key = text.intern_key(key);
+        text.lex_end(started);
+
+        text.record(|report| report.report.tokens += 1);
+
+        let end = text.offset();
+        text.key_span(start..end);
+
+        verify_char(text, ':')?;
+
+        let value = ObjectValue::parse(text)?;
+
+        Ok(Self{key, value})
+    }
+
+    pub fn key(&self) -> &str
+    {
+        &self.key
+    }
+
+    pub fn value(&self) -> &ObjectValue
+    {
+        &self.value
+    }
+
+    pub(crate) fn value_mut(&mut self) -> &mut ObjectValue
+    {
+        &mut self.value
+    }
+}
+
+// objects with at least this many fields build a key index on their first lookup
+const KEY_INDEX_THRESHOLD: usize = 32;
+
+#[derive(Debug, Clone, Default)]
+pub struct Object
+{
+    fields: Vec<ObjectField>,
+    sorted: bool,
+    index: OnceLock<HashMap<Arc<str>, usize>>
+}
+
+impl Object
+{
+    pub fn parse(text: &mut TextIter) -> Result<Self, ParseError>
+    {
+        verify_char(text, '{')?;
+
+        let mut fields = Vec::new();
+
+        // only filled when duplicates get looked for
+        let mut seen: HashMap<Arc<str>, usize> = HashMap::new();
+
+        while let Some(c) = text.skip_whitespace().peek()
+        {
+            if *c == '}'
+            {
+                break;
+            }
+
+            if !fields.is_empty()
+            {
+                verify_char(text, ',')?;
+
+                if text.trailing_comma('}')
+                {
+                    break;
+                }
+            }
+
+            text.check_fields(fields.len())?;
+
+            let start = text.skip_whitespace().offset();
+            let field = ObjectField::parse(text)?;
+
+            if text.options.duplicate_keys == DuplicateKeys::Keep
+            {
+                fields.push(field);
+
+                continue;
+            }
+
+            match seen.get(&field.key).copied()
+            {
+                None =>
+                {
+                    seen.insert(field.key.clone(), fields.len());
+
+                    fields.push(field);
+                },
+                Some(_) if text.options.duplicate_keys == DuplicateKeys::Error =>
+                {
+                    return Err(text.error_at(start, Some('"'), format!("duplicate key {:?}", field.key)));
+                },
+                Some(_) if text.options.duplicate_keys == DuplicateKeys::FirstWins =>
+                {
+                    text.drop_span(None);
+                },
+                Some(id) =>
+                {
+                    // stays where the key was first seen
+                    fields[id].value = field.value;
+
+                    text.drop_span(Some(id));
+                }
+            }
+        }
+
+        verify_char(text, '}')?;
+
+        let mut object = Self::from_fields(fields);
+
+        // looking for duplicates already built the index
+        if seen.len() >= KEY_INDEX_THRESHOLD
+        {
+            object.index = OnceLock::from(seen);
+        }
+
+        Ok(object)
+    }
+
+    pub(crate) fn from_fields(fields: Vec<ObjectField>) -> Self
+    {
+        Self{fields, sorted: false, index: OnceLock::new()}
+    }
+
+    pub fn builder() -> ObjectBuilder
+    {
+        ObjectBuilder::new()
+    }
+
+    pub fn fields(&self) -> &[ObjectField]
+    {
+        &self.fields
+    }
+
+    pub(crate) fn fields_mut(&mut self) -> &mut [ObjectField]
+    {
+        &mut self.fields
+    }
+
+    // the first field with the key if there are duplicates
+    pub fn get(&self, key: &str) -> Option<&ObjectValue>
+    {
+        self.position(key).map(|id| &self.fields[id].value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut ObjectValue>
+    {
+        self.position(key).map(|id| &mut self.fields[id].value)
+    }
+
+    pub fn is_sorted(&self) -> bool
+    {
+        self.sorted
+    }
+
+    pub fn has_duplicate_keys(&self) -> bool
+    {
+        if self.sorted
+        {
+            return self.fields.windows(2).any(|pair| pair[0].key == pair[1].key);
+        }
+
+        let mut seen = HashSet::with_capacity(self.fields.len());
+
+        !self.fields.iter().all(|field| seen.insert(&field.key))
+    }
+
+    // what comparing objects goes by, duplicates stay in the order theyre in
+    fn fields_by_key(&self) -> Vec<&ObjectField>
+    {
+        let mut fields: Vec<_> = self.fields.iter().collect();
+
+        if !self.sorted
+        {
+            fields.sort_by(|a, b| a.key.cmp(&b.key));
+        }
+
+        fields
+    }
+
+    // keeps fields ordered by key so lookups can binary search, duplicates keep their relative order
+    pub fn sort_keys(&mut self)
+    {
+        if self.sorted
+        {
+            return;
+        }
+
+        self.fields.sort_by(|a, b| a.key.cmp(&b.key));
+        self.sorted = true;
+        self.index = OnceLock::new();
+    }
+
+    // an existing key keeps its place and gives back the old value
+    pub fn insert(&mut self, key: impl Into<Arc<str>>, value: ObjectValue) -> Option<ObjectValue>
+    {
+        match self.entry(key)
+        {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) =>
+            {
+                entry.insert(value);
+
+                None
+            }
+        }
+    }
+
+    // with duplicate keys only the first one goes
+    pub fn remove(&mut self, key: &str) -> Option<ObjectValue>
+    {
+        let id = self.position(key)?;
+
+        Some(self.remove_at(id))
+    }
+
+    pub fn entry(&mut self, key: impl Into<Arc<str>>) -> Entry<'_>
+    {
+        let key = key.into();
+
+        match self.position(&key)
+        {
+            Some(id) => Entry::Occupied(OccupiedEntry{object: self, id}),
+            None => Entry::Vacant(VacantEntry{object: self, key})
+        }
+    }
+
+    // sorted objects keep the new key in order, everything else gets it at the end
+    fn insert_new(&mut self, key: Arc<str>, value: ObjectValue) -> usize
+    {
+        let id = if self.sorted
+        {
+            self.fields.partition_point(|field| field.key < key)
+        } else
+        {
+            self.fields.len()
+        };
+
+        if let Some(index) = self.index.get_mut()
+        {
+            index.entry(key.clone()).or_insert(id);
+        }
+
+        self.fields.insert(id, ObjectField{key, value});
+
+        id
+    }
+
+    // the index stays valid so removing and looking up in a loop doesnt rebuild it every time
+    fn remove_at(&mut self, id: usize) -> ObjectValue
+    {
+        let removed = self.fields.remove(id);
+
+        if let Some(index) = self.index.get_mut()
+        {
+            index.values_mut().filter(|position| **position > id).for_each(|position| *position -= 1);
+
+            // a duplicate of the key is what lookups find now, its always after the removed one
+            match self.fields[id..].iter().position(|field| field.key == removed.key)
+            {
+                Some(duplicate) => index.insert(removed.key.clone(), id + duplicate),
+                None => index.remove(&removed.key)
+            };
+        }
+
+        removed.value
+    }
+
+    pub(crate) fn retain_fields(&mut self, keep: impl FnMut(&ObjectField) -> bool)
+    {
+        let length = self.fields.len();
+        self.fields.retain(keep);
+
+        if self.fields.len() != length
+        {
+            self.index = OnceLock::new();
+        }
+    }
+
+    // a returned key replaces the old one, sorted objects get sorted again after
+    pub(crate) fn rename_fields(&mut self, mut rename: impl FnMut(&str) -> Option<String>)
+    {
+        let mut changed = false;
+        self.fields.iter_mut().for_each(|field|
+        {
+            if let Some(key) = rename(&field.key)
+            {
+                changed |= *key != *field.key;
+
+                field.key = key.into();
+            }
+        });
+
+        if !changed
+        {
+            return;
+        }
+
+        self.index = OnceLock::new();
+
+        if self.sorted
+        {
+            self.sorted = false;
+            self.sort_keys();
+        }
+    }
+
+    fn position(&self, key: &str) -> Option<usize>
+    {
+        if self.sorted
+        {
+            let id = self.fields.partition_point(|field| &*field.key < key);
+
+            return self.fields.get(id).filter(|field| &*field.key == key).map(|_| id);
+        }
+
+        if self.fields.len() >= KEY_INDEX_THRESHOLD
+        {
+            return self.index.get_or_init(|| self.build_index()).get(key).copied();
+        }
+
+        self.fields.iter().position(|field| &*field.key == key)
+    }
+
+    fn build_index(&self) -> HashMap<Arc<str>, usize>
+    {
+        let mut index = HashMap::with_capacity(self.fields.len());
+
+        self.fields.iter().enumerate().for_each(|(id, field)|
+        {
+            index.entry(field.key.clone()).or_insert(id);
+        });
+
+        index
+    }
+}
+
+impl Index<&str> for Object
+{
+    type Output = ObjectValue;
+
+    fn index(&self, id: &str) -> &Self::Output
+    {
+        self.get(id).unwrap_or_else(|| panic!("field with key \"{}\" not found", id))
+    }
+}
+
+pub enum Entry<'a>
+{
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>)
+}
+
+impl<'a> Entry<'a>
+{
+    pub fn key(&self) -> &str
+    {
+        match self
+        {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key()
+        }
+    }
+
+    pub fn or_insert(self, value: ObjectValue) -> &'a mut ObjectValue
+    {
+        self.or_insert_with(|| value)
+    }
+
+    pub fn or_insert_with(self, f: impl FnOnce() -> ObjectValue) -> &'a mut ObjectValue
+    {
+        match self
+        {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(f())
+        }
+    }
+
+    pub fn and_modify(mut self, f: impl FnOnce(&mut ObjectValue)) -> Self
+    {
+        if let Self::Occupied(entry) = &mut self
+        {
+            f(entry.get_mut());
+        }
+
+        self
+    }
+}
+
+pub struct OccupiedEntry<'a>
+{
+    object: &'a mut Object,
+    id: usize
+}
+
+impl<'a> OccupiedEntry<'a>
+{
+    pub fn key(&self) -> &str
+    {
+        &self.object.fields[self.id].key
+    }
+
+    pub fn get(&self) -> &ObjectValue
+    {
+        &self.object.fields[self.id].value
+    }
+
+    pub fn get_mut(&mut self) -> &mut ObjectValue
+    {
+        &mut self.object.fields[self.id].value
+    }
+
+    pub fn into_mut(self) -> &'a mut ObjectValue
+    {
+        &mut self.object.fields[self.id].value
+    }
+
+    // gives back the old value
+    pub fn insert(&mut self, value: ObjectValue) -> ObjectValue
+    {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> ObjectValue
+    {
+        self.object.remove_at(self.id)
+    }
+}
+
+pub struct VacantEntry<'a>
+{
+    object: &'a mut Object,
+    key: Arc<str>
+}
+
+impl<'a> VacantEntry<'a>
+{
+    pub fn key(&self) -> &str
+    {
+        &self.key
+    }
+
+    pub fn insert(self, value: ObjectValue) -> &'a mut ObjectValue
+    {
+        let id = self.object.insert_new(self.key, value);
+
+        &mut self.object.fields[id].value
+    }
+}
+
+impl IndexMut<&str> for Object
+{
+    fn index_mut(&mut self, id: &str) -> &mut Self::Output
+    {
+        self.get_mut(id).unwrap_or_else(|| panic!("field with key \"{}\" not found", id))
+    }
+}
+
+// the same fields in any order, duplicate keys have to be in the same order between themselves
+impl PartialEq for Object
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        if self.fields.len() != other.fields.len()
+        {
+            return false;
+        }
+
+        // equal objects usually have their keys in the same order anyway
+        if self.fields.iter().zip(other.fields.iter()).all(|(a, b)| a.key == b.key)
+        {
+            return self.fields.iter().zip(other.fields.iter()).all(|(a, b)| a.value == b.value);
+        }
+
+        self.fields_by_key().into_iter().zip(other.fields_by_key()).all(|(a, b)| a == b)
+    }
+}
+
+impl Eq for Object {}
+
+// the key order doesnt matter for == so every field is hashed on its own and they get added up
+impl Hash for Object
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        let fields = self.fields.iter().fold(0_u64, |sum, field|
+        {
+            let mut field_state = DefaultHasher::new();
+            field.hash(&mut field_state);
+
+            sum.wrapping_add(field_state.finish())
+        });
+
+        self.fields.len().hash(state);
+        fields.hash(state);
+    }
+}
+
+// field by field in key order, an object that runs out of fields first is the smaller one
+impl Ord for Object
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering
+    {
+        self.fields_by_key().cmp(&other.fields_by_key())
+    }
+}
+
+impl PartialOrd for Object
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering>
+    {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpanTree
+{
+    pub span: Range<usize>,
+    pub key: Option<Range<usize>>,
+    pub children: Vec<SpanTree>
+}
+
+impl SpanTree
+{
+    pub fn shift(&mut self, amount: isize)
+    {
+        let shift_range = |range: &mut Range<usize>|
+        {
+            range.start = range.start.wrapping_add_signed(amount);
+            range.end = range.end.wrapping_add_signed(amount);
+        };
+
+        shift_range(&mut self.span);
+        self.key.as_mut().map(shift_range);
+
+        self.children.iter_mut().for_each(|child| child.shift(amount));
+    }
+}
+
+// a value along with where it was in the text it got parsed from, for pointing at
+// problems that are only found after parsing, children are found the same way as
+// with get and pointer so duplicate keys point at the field those would give
+#[derive(Debug, Clone, Copy)]
+pub struct Spanned<'a>
+{
+    value: &'a ObjectValue,
+    spans: &'a SpanTree,
+    text: &'a str
+}
+
+impl<'a> Spanned<'a>
+{
+    pub fn value(&self) -> &'a ObjectValue
+    {
+        self.value
+    }
+
+    // bytes of the value itself, for a field thats without the key
+    pub fn span(&self) -> Range<usize>
+    {
+        self.spans.span.clone()
+    }
+
+    // only for values that are fields of an object
+    pub fn key_span(&self) -> Option<Range<usize>>
+    {
+        self.spans.key.clone()
+    }
+
+    // where the value starts
+    pub fn position(&self) -> Position
+    {
+        Position::from_offset(self.text, self.spans.span.start)
+    }
+
+    // right after the last character of the value
+    pub fn end_position(&self) -> Position
+    {
+        Position::from_offset(self.text, self.spans.span.end)
+    }
+
+    pub fn at(&self, id: usize) -> Option<Self>
+    {
+        match self.value
+        {
+            ObjectValue::List(list) => Some(self.child(list.get(id)?, id)),
+            _ => None
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<Self>
+    {
+        match self.value
+        {
+            ObjectValue::Object(object) =>
+            {
+                let id = object.position(key)?;
+
+                Some(self.child(&object.fields[id].value, id))
+            },
+            _ => None
+        }
+    }
+
+    pub fn pointer(&self, pointer: &str) -> Option<Self>
+    {
+        if pointer.is_empty()
+        {
+            return Some(*self);
+        }
+
+        pointer_tokens(pointer)?.try_fold(*self, |spanned, token|
+        {
+            match spanned.value
+            {
+                ObjectValue::List(_) => token.parse::<usize>().ok().and_then(|id| spanned.at(id)),
+                ObjectValue::Object(_) => spanned.get(&token),
+                _ => None
+            }
+        })
+    }
+
+    fn child(&self, value: &'a ObjectValue, id: usize) -> Self
+    {
+        Self{value, spans: &self.spans.children[id], text: self.text}
+    }
+}
+
+const INTERRUPT_CHECK_INTERVAL: u32 = 256;
+
+// a single string or run of whitespace can be the whole document so the characters get counted too
+const CHAR_CHECK_INTERVAL: u32 = 1 << 14;
+
+// deep enough for any sane document and shallow enough for the recursion to fit on a small stack
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 128;
+
+pub(crate) const BYTE_ORDER_MARK: char = '\u{feff}';
+
+// 2^63 and 2^64, the first floats that dont fit
+const I64_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+const U64_LIMIT: f64 = 18_446_744_073_709_551_616.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    pub fn cancel(&self)
+    {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool
+    {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind
+{
+    Syntax,
+    // the cancel token fired or the deadline passed
+    Cancelled,
+    // the reader failed or gave something that isnt utf8
+    Io,
+    // one of the limits in the options was hit
+    Limit,
+    // something the parser assumed about its own state didnt hold, always a bug
+    // but it ends the parse instead of taking the whole program down
+    Internal
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError
+{
+    pub kind: ParseErrorKind,
+    pub message: String,
+    // the character the parser choked on, none if the text ended
+    pub found: Option<char>,
+    pub expected: Option<String>,
+    pub offset: usize,
+    pub position: Position
+}
+
+impl ParseError
+{
+    pub(crate) fn io(err: &io::Error, offset: usize, position: Position) -> Self
+    {
+        Self{
+            kind: ParseErrorKind::Io,
+            message: format!("error reading: {err}"),
+            found: None,
+            expected: None,
+            offset,
+            position
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool
+    {
+        self.kind == ParseErrorKind::Cancelled
+    }
+}
+
+impl fmt::Display for ParseError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.message)?;
+
+        if let Some(expected) = &self.expected
+        {
+            write!(f, ", expected {expected}")?;
+        }
+
+        write!(f, " at {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// what a try_get_* wanted and what the value actually was
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeError
+{
+    pub expected: &'static str,
+    pub found: &'static str
+}
+
+impl fmt::Display for TypeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "expected {} got {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+// whatever parse_recovering got out of a document thats broken in any number of places
+#[derive(Debug, Clone)]
+pub struct Recovered
+{
+    // containers keep everything around the values that didnt parse, none if nothing did
+    pub value: Option<ObjectValue>,
+    // in the order theyre in the text, empty for a valid document
+    pub errors: Vec<ParseError>
+}
+
+// counters for a single parse, lex time is spent reading strings, numbers and
+// literals and build time is everything else
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport
+{
+    pub bytes: usize,
+    pub tokens: usize,
+    pub nulls: usize,
+    pub texts: usize,
+    pub numbers: usize,
+    pub bools: usize,
+    pub lists: usize,
+    pub objects: usize,
+    pub peak_depth: usize,
+    pub lex_time: Duration,
+    pub build_time: Duration
+}
+
+#[derive(Debug, Default)]
+struct ReportRecorder
+{
+    report: ParseReport,
+    depth: usize
+}
+
+impl ReportRecorder
+{
+    fn enter(&mut self)
+    {
+        self.depth += 1;
+        self.report.peak_depth = self.report.peak_depth.max(self.depth);
+    }
+
+    fn count(&mut self, value: &ObjectValue)
+    {
+        let report = &mut self.report;
+
+        match value
+        {
+            ObjectValue::Null => report.nulls += 1,
+            ObjectValue::Text(_) => report.texts += 1,
+            ObjectValue::Number(_) | ObjectValue::Float(_) => report.numbers += 1,
+            ObjectValue::Bool(_) => report.bools += 1,
+            ObjectValue::List(_) => report.lists += 1,
+            ObjectValue::Object(_) => report.objects += 1
+        }
+
+        if !matches!(value, ObjectValue::List(_) | ObjectValue::Object(_))
+        {
+            report.tokens += 1;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SpanRecorder
+{
+    stack: Vec<SpanTree>,
+    key: Option<Range<usize>>,
+    root: Option<SpanTree>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect
+{
+    Json,
+    // turns on comments, trailing commas, unquoted keys, single quotes, hex numbers, nan and infinity
+    // no matter what their switches say and also allows a leading + and a dot at either end of a number
+    Json5
+}
+
+// what ObjectValue::merge does when both sides have a list in the same place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy
+{
+    // the list on top replaces the one below like any other value would
+    #[default]
+    Replace,
+    Append,
+    // elements at the same index get merged and any extra ones on top get added at the end
+    ByIndex
+}
+
+// parse_events, parse_tape and tokenize hand values out as theyre parsed so they can only skip
+// a duplicate or stop at it, with LastWins a duplicate is an error there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys
+{
+    // every field stays and lookups find the first one
+    Keep,
+    Error,
+    FirstWins,
+    // the value replaces the earlier one but keeps its place in the object
+    LastWins
+}
+
+// every place where the parser can be more lenient than rfc 8259 gets its own switch,
+// strict turns all of them off and default is what the parser always accepted,
+// limits are the same in both
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserOptions
+{
+    // TRUE, False and such
+    pub case_insensitive_literals: bool,
+    // numbers like 007
+    pub leading_zeros: bool,
+    // raw characters below U+0020 inside strings
+    pub control_characters: bool,
+    // \u escapes of surrogate code points, decoded as U+FFFD
+    pub lone_surrogates: bool,
+    // any unicode whitespace between tokens instead of just space, tab, LF and CR
+    pub unicode_whitespace: bool,
+    // ignore whatever comes after the first complete value
+    pub trailing_content: bool,
+    // a U+FEFF at the very start like windows tools write, skipped instead of being an error
+    pub byte_order_mark: bool,
+    // json5 is a superset of json, its json in both
+    pub dialect: Dialect,
+    // line and block comments wherever whitespace can go, off in both
+    pub comments: bool,
+    // a comma right before the ] or }, also off in both
+    pub trailing_commas: bool,
+    // identifiers like {foo: 1} as object keys, off in both
+    pub unquoted_keys: bool,
+    // 'text' strings and keys, where \' is an escape in either kind of string, off in both
+    pub single_quotes: bool,
+    // integers like 0x401000, off in both
+    pub hex_numbers: bool,
+    // NaN, Infinity and -Infinity as floats like python writes them, off in both
+    pub nan_and_infinity: bool,
+    // invalid utf8 read from bytes or a reader becomes U+FFFD instead of an error, off in both
+    pub lossy_utf8: bool,
+    // what Object::parse does with a key the object already has, duplicates are valid json
+    // so this is the same in both
+    pub duplicate_keys: DuplicateKeys,
+    // how many lists and objects can be inside each other before parsing fails
+    pub max_depth: usize,
+    // bytes the parser reads before giving up, only whats actually read counts
+    pub max_document_size: usize,
+    // bytes in a single string or key after escapes are decoded
+    pub max_string_length: usize,
+    // values in a single list, the tokenizer leaves counting to whoever drives it
+    pub max_elements: usize,
+    // fields in a single object, same as max_elements
+    pub max_fields: usize
+}
+
+impl Default for ParserOptions
+{
+    fn default() -> Self
+    {
+        Self{
+            case_insensitive_literals: true,
+            leading_zeros: true,
+            control_characters: true,
+            lone_surrogates: true,
+            unicode_whitespace: true,
+            trailing_content: true,
+            byte_order_mark: true,
+            dialect: Dialect::Json,
+            comments: false,
+            trailing_commas: false,
+            unquoted_keys: false,
+            single_quotes: false,
+            hex_numbers: false,
+            nan_and_infinity: false,
+            lossy_utf8: false,
+            duplicate_keys: DuplicateKeys::Keep,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_document_size: usize::MAX,
+            max_string_length: usize::MAX,
+            max_elements: usize::MAX,
+            max_fields: usize::MAX
+        }
+    }
+}
+
+impl ParserOptions
+{
+    pub fn strict() -> Self
+    {
+        Self{
+            case_insensitive_literals: false,
+            leading_zeros: false,
+            control_characters: false,
+            lone_surrogates: false,
+            unicode_whitespace: false,
+            trailing_content: false,
+            byte_order_mark: false,
+            dialect: Dialect::Json,
+            comments: false,
+            trailing_commas: false,
+            unquoted_keys: false,
+            single_quotes: false,
+            hex_numbers: false,
+            nan_and_infinity: false,
+            lossy_utf8: false,
+            duplicate_keys: DuplicateKeys::Keep,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_document_size: usize::MAX,
+            max_string_length: usize::MAX,
+            max_elements: usize::MAX,
+            max_fields: usize::MAX
+        }
+    }
+
+    // every switch also has a setter so options can be built up like
+    // ParserOptions::strict().trailing_content(true)
+    pub fn case_insensitive_literals(mut self, case_insensitive_literals: bool) -> Self
+    {
+        self.case_insensitive_literals = case_insensitive_literals;
+
+        self
+    }
+
+    pub fn leading_zeros(mut self, leading_zeros: bool) -> Self
+    {
+        self.leading_zeros = leading_zeros;
+
+        self
+    }
+
+    pub fn control_characters(mut self, control_characters: bool) -> Self
+    {
+        self.control_characters = control_characters;
+
+        self
+    }
+
+    pub fn lone_surrogates(mut self, lone_surrogates: bool) -> Self
+    {
+        self.lone_surrogates = lone_surrogates;
+
+        self
+    }
+
+    pub fn unicode_whitespace(mut self, unicode_whitespace: bool) -> Self
+    {
+        self.unicode_whitespace = unicode_whitespace;
+
+        self
+    }
+
+    pub fn trailing_content(mut self, trailing_content: bool) -> Self
+    {
+        self.trailing_content = trailing_content;
+
+        self
+    }
+
+    pub fn byte_order_mark(mut self, byte_order_mark: bool) -> Self
+    {
+        self.byte_order_mark = byte_order_mark;
+
+        self
+    }
+
+    pub fn dialect(mut self, dialect: Dialect) -> Self
+    {
+        self.dialect = dialect;
+
+        self
+    }
+
+    pub fn comments(mut self, comments: bool) -> Self
+    {
+        self.comments = comments;
+
+        self
+    }
+
+    pub fn trailing_commas(mut self, trailing_commas: bool) -> Self
+    {
+        self.trailing_commas = trailing_commas;
+
+        self
+    }
+
+    pub fn unquoted_keys(mut self, unquoted_keys: bool) -> Self
+    {
+        self.unquoted_keys = unquoted_keys;
+
+        self
+    }
+
+    pub fn single_quotes(mut self, single_quotes: bool) -> Self
+    {
+        self.single_quotes = single_quotes;
+
+        self
+    }
+
+    pub fn hex_numbers(mut self, hex_numbers: bool) -> Self
+    {
+        self.hex_numbers = hex_numbers;
+
+        self
+    }
+
+    pub fn nan_and_infinity(mut self, nan_and_infinity: bool) -> Self
+    {
+        self.nan_and_infinity = nan_and_infinity;
+
+        self
+    }
+
+    pub fn lossy_utf8(mut self, lossy_utf8: bool) -> Self
+    {
+        self.lossy_utf8 = lossy_utf8;
+
+        self
+    }
+
+    pub fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self
+    {
+        self.duplicate_keys = duplicate_keys;
+
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self
+    {
+        self.max_depth = max_depth;
+
+        self
+    }
+
+    pub fn max_document_size(mut self, max_document_size: usize) -> Self
+    {
+        self.max_document_size = max_document_size;
+
+        self
+    }
+
+    pub fn max_string_length(mut self, max_string_length: usize) -> Self
+    {
+        self.max_string_length = max_string_length;
+
+        self
+    }
+
+    pub fn max_elements(mut self, max_elements: usize) -> Self
+    {
+        self.max_elements = max_elements;
+
+        self
+    }
+
+    pub fn max_fields(mut self, max_fields: usize) -> Self
+    {
+        self.max_fields = max_fields;
+
+        self
+    }
+
+    pub(crate) fn allows_comments(&self) -> bool
+    {
+        self.comments || self.dialect == Dialect::Json5
+    }
+
+    pub(crate) fn allows_trailing_commas(&self) -> bool
+    {
+        self.trailing_commas || self.dialect == Dialect::Json5
+    }
+
+    pub(crate) fn allows_unquoted_keys(&self) -> bool
+    {
+        self.unquoted_keys || self.dialect == Dialect::Json5
+    }
+
+    pub(crate) fn allows_single_quotes(&self) -> bool
+    {
+        self.single_quotes || self.dialect == Dialect::Json5
+    }
+
+    pub(crate) fn allows_hex_numbers(&self) -> bool
+    {
+        self.hex_numbers || self.dialect == Dialect::Json5
+    }
+
+    pub(crate) fn allows_nan_and_infinity(&self) -> bool
+    {
+        self.nan_and_infinity || self.dialect == Dialect::Json5
+    }
+
+    // the characters a string can start with
+    pub(crate) fn is_quote(&self, c: char) -> bool
+    {
+        c == '"' || (c == '\'' && self.allows_single_quotes())
+    }
+}
+
+type TextIterInner<'a> = Chars<'a>;
+
+enum Source<'a>
+{
+    Text{text: &'a str, chars: Chars<'a>},
+    // decoded as the parser goes, a reader never has to be in memory all at once
+    Reader(ReaderChars<'a>)
+}
+
+impl Iterator for Source<'_>
+{
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)>
+    {
+        match self
+        {
+            Self::Text{text, chars} =>
+            {
+                let offset = text.len() - chars.as_str().len();
+
+                chars.next().map(|c| (offset, c))
+            },
+            Self::Reader(reader) => reader.next()
+        }
+    }
+}
+
+pub struct TextIter<'a>
+{
+    chars: Source<'a>,
+    peeked: Option<Option<(usize, char)>>,
+    base: usize,
+    options: ParserOptions,
+    spans: Option<SpanRecorder>,
+    report: Option<ReportRecorder>,
+    interner: Option<&'a SharedInterner>,
+    // every key seen so far, for sharing them within one parse without a shared interner
+    keys: Option<HashSet<Arc<str>>>,
+    cancel: Option<CancelToken>,
+    deadline: Option<Instant>,
+    checks: u32,
+    // characters pulled since the last check
+    pulled: u32,
+    // the cancel token or deadline fired in the middle of something, treated like the text
+    // ending there, this is where the first character that didnt get looked at starts
+    interrupted: Option<usize>,
+    depth: usize,
+    // borrowed lists and objects deeper than this get checked and kept as text instead of built
+    raw_below: Option<usize>,
+    // shared by every raw value so they can be parsed later, made on the first one
+    raw_options: Option<Arc<ParserOptions>>,
+    // the source had more than max_document_size bytes, treated like the text ending there,
+    // this is where the first character that didnt fit starts
+    oversized: Option<usize>,
+    // a block comment that never ended, which also ends the text
+    unterminated: Option<ParseError>
+}
+
+impl<'a> TextIter<'a>
+{
+    fn new(text: &'a str, base: usize, options: ParserOptions) -> Self
+    {
+        Self::with_source(Source::Text{text, chars: text.chars()}, base, options)
+    }
+
+    fn with_source(chars: Source<'a>, base: usize, options: ParserOptions) -> Self
+    {
+        Self{
+            chars,
+            peeked: None,
+            base,
+            options,
+            spans: None,
+            report: None,
+            interner: None,
+            keys: None,
+            cancel: None,
+            deadline: None,
+            checks: 0,
+            pulled: 0,
+            interrupted: None,
+            depth: 0,
+            raw_below: None,
+            raw_options: None,
+            oversized: None,
+            unterminated: None
+        }
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) -> &mut Self
+    {
+        let unicode = self.options.unicode_whitespace;
+
+        loop
+        {
+            #[cfg(feature = "simd")]
+            self.skip_bytes(usize::MAX, simd::whitespace_len);
+
+            while self.next_if(|c|
+            {
+                matches!(c, ' ' | '\t' | '\n' | '\r') || (unicode && c.is_whitespace())
+            }).is_some() {}
+
+            let start = self.offset();
+
+            if !self.options.allows_comments() || self.next_if(|c| *c == '/').is_none()
+            {
+                break;
+            }
+
+            // the comment took the rest of the text so whatever needs it next sees the end and
+            // gets this error instead, same as with a reader failing
+            if let Err(err) = self.skip_comment(start)
+            {
+                self.unterminated = Some(err);
+            }
+        }
+
+        self
+    }
+
+    // jumps over the bytes the scan says can be skipped and gives them back, readers only
+    // ever have part of the text so they go the slow way, it stops short of the size limit
+    // and of the limit given so whatever is over it still gets found one character at a time
+    #[cfg(feature = "simd")]
+    fn skip_bytes(&mut self, limit: usize, scan: impl FnOnce(&[u8]) -> usize) -> &'a str
+    {
+        let start = match self.peek_indexed()
+        {
+            Some((offset, _)) => *offset,
+            None => return ""
+        };
+
+        let (text, chars) = match &mut self.chars
+        {
+            Source::Text{text, chars} => (*text, chars),
+            Source::Reader(_) => return ""
+        };
+
+        let limit = limit.min(self.options.max_document_size.saturating_sub(start));
+        let bytes = &text.as_bytes()[start..];
+
+        let mut amount = scan(&bytes[..bytes.len().min(limit)]);
+
+        // cutting it off at the limit can land in the middle of a character
+        while !text.is_char_boundary(start + amount)
+        {
+            amount -= 1;
+        }
+
+        if amount == 0
+        {
+            return "";
+        }
+
+        *chars = text[start + amount..].chars();
+        self.peeked = None;
+
+        &text[start..start + amount]
+    }
+
+    // right after the first slash, a lone one is left for whatever comes next to complain about
+    // start is where the slash before it was
+    fn skip_comment(&mut self, start: usize) -> Result<(), ParseError>
+    {
+        if self.next_if(|c| *c == '/').is_some()
+        {
+            while self.next_if(|c| *c != '\n').is_some() {}
+        } else if self.next_if(|c| *c == '*').is_some()
+        {
+            let mut previous = ' ';
+            for c in self.by_ref()
+            {
+                if previous == '*' && c == '/'
+                {
+                    return Ok(());
+                }
+
+                previous = c;
+            }
+
+            return Err(self.error_at(start, None, "unterminated block comment"));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> Result<(), ParseError>
+    {
+        // the value might have looked complete right where the reader failed
+        if self.options.trailing_content
+        {
+            return self.cut_short().map_or(Ok(()), Err);
+        }
+
+        let offset = self.skip_whitespace().offset();
+
+        // or a comment after it never ended
+        if let Some(err) = self.cut_short()
+        {
+            return Err(err);
+        }
+
+        match self.peek().copied()
+        {
+            Some(c) => Err(self.error_at(offset, Some(c), format!("unexpected trailing characters starting with {c:?}"))),
+            None => Ok(())
+        }
+    }
+
+    // the text ended because reading failed or the document got too big and not because
+    // that was all of it
+    pub(crate) fn cut_short(&mut self) -> Option<ParseError>
+    {
+        if self.read_error().is_none() && self.oversized.is_none() && self.interrupted.is_none()
+        {
+            return self.unterminated.clone();
+        }
+
+        let offset = self.offset();
+
+        Some(self.error_at(offset, None, "unexpected end of text"))
+    }
+
+    // offset is into the full text, the line and column only into what this iterator sees
+    fn error_at(&self, offset: usize, found: Option<char>, message: impl Into<String>) -> ParseError
+    {
+        let position = match &self.chars
+        {
+            Source::Text{text, ..} => Position::from_offset(text, offset - self.base),
+            Source::Reader(reader) => reader.position(offset)
+        };
+
+        // whatever the parser thinks went wrong, the text only ended early because reading failed
+        if let Some(err) = self.read_error()
+        {
+            return ParseError::io(err, offset, position);
+        }
+
+        // or the parse getting cancelled
+        if self.interrupted.is_some()
+        {
+            return self.cancelled_at(offset);
+        }
+
+        // same for running into the size limit
+        if self.oversized.is_some()
+        {
+            return ParseError{
+                kind: ParseErrorKind::Limit,
+                message: format!("the document is bigger than the limit of {} bytes", self.options.max_document_size),
+                found: None,
+                expected: None,
+                offset,
+                position
+            };
+        }
+
+        if let Some(err) = &self.unterminated
+        {
+            return err.clone();
+        }
+
+        ParseError{
+            kind: ParseErrorKind::Syntax,
+            message: message.into(),
+            found,
+            expected: None,
+            offset,
+            position
+        }
+    }
+
+    // none for a reader, nothing it read is kept around, or for a range that isnt in the text
+    fn slice(&self, start: usize, end: usize) -> Option<&'a str>
+    {
+        match &self.chars
+        {
+            Source::Text{text, ..} => text.get(start.checked_sub(self.base)?..end.checked_sub(self.base)?),
+            Source::Reader(_) => None
+        }
+    }
+
+    fn read_error(&self) -> Option<&io::Error>
+    {
+        match &self.chars
+        {
+            Source::Text{..} => None,
+            Source::Reader(reader) => reader.error()
+        }
+    }
+
+    // error about the next character not being what was expected
+    pub(crate) fn unexpected(&mut self, expected: &str) -> ParseError
+    {
+        let offset = self.offset();
+        let found = self.peek().copied();
+
+        let message = match found
+        {
+            Some(BYTE_ORDER_MARK) => "unexpected byte order mark".to_owned(),
+            Some(c) => format!("unexpected {c:?}"),
+            None => "unexpected end of text".to_owned()
+        };
+
+        let error = self.error_at(offset, found, message);
+
+        if error.kind != ParseErrorKind::Syntax
+        {
+            return error;
+        }
+
+        ParseError{expected: Some(expected.to_owned()), ..error}
+    }
+
+    fn record_spans(mut self) -> Self
+    {
+        self.spans = Some(SpanRecorder::default());
+
+        self
+    }
+
+    // only looks at the token and the clock every few hundred values
+    pub(crate) fn check_interrupt(&mut self) -> Result<(), ParseError>
+    {
+        if self.cancel.is_none() && self.deadline.is_none()
+        {
+            return Ok(());
+        }
+
+        let check = self.checks.is_multiple_of(INTERRUPT_CHECK_INTERVAL);
+        self.checks = self.checks.wrapping_add(1);
+
+        if !check
+        {
+            return Ok(());
+        }
+
+        if self.should_stop()
+        {
+            let offset = self.offset();
+
+            Err(self.cancelled_at(offset))
+        } else
+        {
+            Ok(())
+        }
+    }
+
+    fn should_stop(&self) -> bool
+    {
+        let cancelled = self.cancel.as_ref().map(CancelToken::is_cancelled).unwrap_or(false);
+        let expired = self.deadline.map(|deadline| Instant::now() >= deadline).unwrap_or(false);
+
+        cancelled || expired
+    }
+
+    fn cancelled_at(&self, offset: usize) -> ParseError
+    {
+        let position = match &self.chars
+        {
+            Source::Text{text, ..} => Position::from_offset(text, offset - self.base),
+            Source::Reader(reader) => reader.position(offset)
+        };
+
+        ParseError{
+            kind: ParseErrorKind::Cancelled,
+            message: "parsing was cancelled".to_owned(),
+            found: None,
+            expected: None,
+            offset,
+            position
+        }
+    }
+
+    // right after a comma, if trailing ones are allowed and the container ends here
+    pub(crate) fn trailing_comma(&mut self, closer: char) -> bool
+    {
+        self.options.allows_trailing_commas() && self.skip_whitespace().peek() == Some(&closer)
+    }
+
+    // every list and object goes through here before its opening bracket is consumed
+    pub(crate) fn enter(&mut self) -> Result<(), ParseError>
+    {
+        if self.depth >= self.options.max_depth
+        {
+            return Err(self.limit_error(format!("nesting is deeper than the limit of {}", self.options.max_depth)));
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    pub(crate) fn leave(&mut self)
+    {
+        self.depth -= 1;
+    }
+
+    // right before another value gets added to a list
+    fn check_elements(&mut self, count: usize) -> Result<(), ParseError>
+    {
+        self.skip_whitespace();
+
+        if count >= self.options.max_elements
+        {
+            return Err(self.limit_error(format!("list has more values than the limit of {}", self.options.max_elements)));
+        }
+
+        Ok(())
+    }
+
+    // right before another field gets added to an object
+    fn check_fields(&mut self, count: usize) -> Result<(), ParseError>
+    {
+        self.skip_whitespace();
+
+        if count >= self.options.max_fields
+        {
+            return Err(self.limit_error(format!("object has more fields than the limit of {}", self.options.max_fields)));
+        }
+
+        Ok(())
+    }
+
+    fn limit_error(&mut self, message: String) -> ParseError
+    {
+        let offset = self.offset();
+        let found = self.peek().copied();
+
+        let error = self.error_at(offset, found, message);
+
+        ParseError{kind: ParseErrorKind::Limit, ..error}
+    }
+
+    pub(crate) fn internal_error(&mut self, message: &str) -> ParseError
+    {
+        let offset = self.offset();
+
+        let error = self.error_at(offset, None, format!("internal parser error: {message}"));
+
+        ParseError{kind: ParseErrorKind::Internal, ..error}
+    }
+
+    // for the fast paths that only run on text sources
+    fn text_slice(&mut self, start: usize, end: usize) -> Result<&'a str, ParseError>
+    {
+        match self.slice(start, end)
+        {
+            Some(slice) => Ok(slice),
+            None => Err(self.internal_error(&format!("cant slice the source at {start}..{end}")))
+        }
+    }
+
+    fn intern_key(&mut self, key: String) -> Arc<str>
+    {
+        if let Some(interner) = self.interner
+        {
+            return interner.intern(&key);
+        }
+
+        let keys = match self.keys.as_mut()
+        {
+            Some(x) => x,
+            None => return key.into()
+        };
+
+        if let Some(found) = keys.get(key.as_str())
+        {
+            return found.clone();
+        }
+
+        let key: Arc<str> = key.into();
+        keys.insert(key.clone());
+
+        key
+    }
+
+    fn intern_text(&self, text: String) -> Arc<str>
+    {
+        match self.interner
+        {
+            Some(interner) if text.len() <= interner.max_value_len() => interner.intern(&text),
+            _ => text.into()
+        }
+    }
+
+    fn record_report(mut self) -> Self
+    {
+        self.report = Some(ReportRecorder::default());
+
+        self
+    }
+
+    fn record(&mut self, f: impl FnOnce(&mut ReportRecorder))
+    {
+        if let Some(report) = self.report.as_mut()
+        {
+            f(report);
+        }
+    }
+
+    // only reads the clock when a report is being recorded
+    fn lex_start(&self) -> Option<Instant>
+    {
+        self.report.as_ref().map(|_| Instant::now())
+    }
+
+    fn lex_end(&mut self, started: Option<Instant>)
+    {
+        if let (Some(started), Some(report)) = (started, self.report.as_mut())
+        {
+            report.report.lex_time += started.elapsed();
+        }
+    }
+
+    fn peek_indexed(&mut self) -> Option<&(usize, char)>
+    {
+        if self.peeked.is_none()
+        {
+            self.peeked = Some(self.pull());
+        }
+
+        self.peeked.as_ref().and_then(Option::as_ref)
+    }
+
+    // the next character from the source, nothing past the size limit
+    fn pull(&mut self) -> Option<(usize, char)>
+    {
+        if self.interrupted.is_some()
+        {
+            return None;
+        }
+
+        let (offset, c) = self.chars.next()?;
+
+        if self.cancel.is_some() || self.deadline.is_some()
+        {
+            self.pulled += 1;
+
+            if self.pulled >= CHAR_CHECK_INTERVAL
+            {
+                self.pulled = 0;
+
+                if self.should_stop()
+                {
+                    self.interrupted = Some(offset);
+
+                    return None;
+                }
+            }
+        }
+
+        if offset == 0 && c == BYTE_ORDER_MARK && self.options.byte_order_mark
+        {
+            return self.pull();
+        }
+
+        if offset >= self.options.max_document_size
+        {
+            self.oversized = Some(offset);
+
+            return None;
+        }
+
+        Some((offset, c))
+    }
+
+    pub(crate) fn peek(&mut self) -> Option<&char>
+    {
+        self.peek_indexed().map(|(_, c)| c)
+    }
+
+    fn next_if(&mut self, f: impl FnOnce(&char) -> bool) -> Option<char>
+    {
+        match self.peek_indexed()
+        {
+            Some((_, c)) if f(c) => self.next(),
+            _ => None
+        }
+    }
+
+    pub(crate) fn options(&self) -> &ParserOptions
+    {
+        &self.options
+    }
+
+    // byte offset of the next character
+    pub(crate) fn offset(&mut self) -> usize
+    {
+        let offset = match self.peek_indexed().copied()
+        {
+            Some((id, _)) => id,
+            None => match (&self.chars, self.oversized.or(self.interrupted))
+            {
+                (_, Some(offset)) => offset,
+                (Source::Text{text, ..}, None) => text.len(),
+                (Source::Reader(reader), None) => reader.offset()
+            }
+        };
+
+        self.base + offset
+    }
+
+    fn begin_span(&mut self)
+    {
+        if self.spans.is_none()
+        {
+            return;
+        }
+
+        let start = self.offset();
+
+        if let Some(spans) = self.spans.as_mut()
+        {
+            let key = spans.key.take();
+            spans.stack.push(SpanTree{span: start..start, key, children: Vec::new()});
+        }
+    }
+
+    fn end_span(&mut self)
+    {
+        if self.spans.is_none()
+        {
+            return;
+        }
+
+        let end = self.offset();
+
+        let spans = match self.spans.as_mut()
+        {
+            Some(x) => x,
+            None => return
+        };
+
+        let mut node = match spans.stack.pop()
+        {
+            Some(x) => x,
+            None => return
+        };
+
+        node.span.end = end;
+
+        match spans.stack.last_mut()
+        {
+            Some(parent) => parent.children.push(node),
+            None => spans.root = Some(node)
+        }
+    }
+
+    // the field that was just parsed got thrown out, its spans either go too or replace the ones at id
+    fn drop_span(&mut self, id: Option<usize>)
+    {
+        if let Some(parent) = self.spans.as_mut().and_then(|spans| spans.stack.last_mut())
+        {
+            let node = parent.children.pop();
+
+            if let Some((slot, node)) = id.and_then(|id| parent.children.get_mut(id)).zip(node)
+            {
+                *slot = node;
+            }
+        }
+    }
+
+    fn key_span(&mut self, span: Range<usize>)
+    {
+        if let Some(spans) = self.spans.as_mut()
+        {
+            spans.key = Some(span);
+        }
+    }
+
+    fn take_spans(&mut self) -> Result<SpanTree, ParseError>
+    {
+        match self.spans.as_mut().and_then(|spans| spans.root.take())
+        {
+            Some(spans) => Ok(spans),
+            None => Err(self.internal_error("spans werent recorded"))
+        }
+    }
+}
+
+impl Iterator for TextIter<'_>
+{
+    type Item = char;
+
+    fn next(&mut self) -> Option<char>
+    {
+        match self.peeked.take()
+        {
+            Some(peeked) => peeked,
+            None => self.pull()
+        }.map(|(_, c)| c)
+    }
+}
+
+impl<'a> BorrowedValue<'a>
+{
+    // no spans, reports or interning, the point is to allocate as little as possible
+    pub fn parse(text: &mut TextIter<'a>) -> Result<Self, ParseError>
+    {
+        text.check_interrupt()?;
+
+        text.skip_whitespace();
+
+        let container = matches!(text.peek(), Some('[' | '{'));
+
+        if container && text.raw_below.is_some_and(|depth| text.depth >= depth)
+        {
+            if let Some(raw) = parse_raw(text)?
+            {
+                return Ok(Self::Raw(raw));
+            }
+        }
+
+        match text.peek().copied()
+        {
+            Some('[') => Self::parse_list(text),
+            Some('{') => Self::parse_object(text),
+            Some(c) if text.options.is_quote(c) => Ok(Self::Text(parse_text_borrowed(text)?)),
+            Some(_) =>
+            {
+                let value = match ObjectValue::parse(text)?
+                {
+                    ObjectValue::Null => Self::Null,
+                    ObjectValue::Number(x) => Self::Number(x),
+                    ObjectValue::Float(x) => Self::Float(x),
+                    ObjectValue::Bool(x) => Self::Bool(x),
+                    _ => return Err(text.internal_error("a scalar parsed into a container or a string"))
+                };
+
+                Ok(value)
+            },
+            None => Err(text.unexpected("a value"))
+        }
+    }
+
+    fn parse_list(text: &mut TextIter<'a>) -> Result<Self, ParseError>
+    {
+        text.enter()?;
+        verify_char(text, '[')?;
+
+        let mut values = Vec::new();
+
+        while let Some(c) = text.skip_whitespace().peek()
+        {
+            if *c == ']'
+            {
+                break;
+            }
+
+            if !values.is_empty()
+            {
+                verify_char(text, ',')?;
+
+                if text.trailing_comma(']')
+                {
+                    break;
+                }
+            }
+
+            text.check_elements(values.len())?;
+            values.push(Self::parse(text)?);
+        }
+
+        verify_char(text, ']')?;
+        text.leave();
+
+        Ok(Self::List(values))
+    }
+
+    fn parse_object(text: &mut TextIter<'a>) -> Result<Self, ParseError>
+    {
+        text.enter()?;
+        verify_char(text, '{')?;
+
+        let mut fields: Vec<(Cow<'a, str>, Self)> = Vec::new();
+
+        // only filled when duplicates get looked for
+        let mut seen: HashMap<Cow<'a, str>, usize> = HashMap::new();
+
+        while let Some(c) = text.skip_whitespace().peek()
+        {
+            if *c == '}'
+            {
+                break;
+            }
+
+            if !fields.is_empty()
+            {
+                verify_char(text, ',')?;
+
+                if text.trailing_comma('}')
+                {
+                    break;
+                }
+            }
+
+            text.check_fields(fields.len())?;
+
+            let start = text.skip_whitespace().offset();
+            let key = parse_key(text)?;
+
+            verify_char(text, ':')?;
+
+            let value = Self::parse(text)?;
+
+            if text.options.duplicate_keys == DuplicateKeys::Keep
+            {
+                fields.push((key, value));
+
+                continue;
+            }
+
+            match seen.get(&key).copied()
+            {
+                None =>
+                {
+                    seen.insert(key.clone(), fields.len());
+
+                    fields.push((key, value));
+                },
+                Some(_) if text.options.duplicate_keys == DuplicateKeys::Error =>
+                {
+                    return Err(text.error_at(start, Some('"'), format!("duplicate key {key:?}")));
+                },
+                Some(_) if text.options.duplicate_keys == DuplicateKeys::FirstWins => (),
+                // stays where the key was first seen
+                Some(id) => fields[id].1 = value
+            }
+        }
+
+        verify_char(text, '}')?;
+        text.leave();
+
+        Ok(Self::Object(fields))
+    }
+}
+
+// cancelling, the reader failing or a bug stops recovery, theres nothing after it to look at
+fn recoverable(err: ParseError) -> Result<ParseError, ParseError>
+{
+    match err.kind
+    {
+        ParseErrorKind::Cancelled | ParseErrorKind::Io | ParseErrorKind::Internal => Err(err),
+        _ => Ok(err)
+    }
+}
+
+// like ObjectValue::parse except an error inside a list or object only costs the value it was in,
+// whatever couldnt be parsed is skipped up to the next comma or closing bracket of the container
+fn parse_recovering(text: &mut TextIter, errors: &mut Vec<ParseError>) -> Result<Option<ObjectValue>, ParseError>
+{
+    text.skip_whitespace();
+
+    let beginning = match text.peek().copied()
+    {
+        Some(c @ ('[' | '{')) => c,
+        beginning =>
+        {
+            return match ObjectValue::parse(text)
+            {
+                Ok(value) => Ok(Some(value)),
+                Err(err) =>
+                {
+                    errors.push(recoverable(err)?);
+
+                    // the rest of a broken string could look like anything
+                    if let Some(quote) = beginning.filter(|c| text.options.is_quote(*c))
+                    {
+                        skip_text_rest(text, quote);
+                    }
+
+                    Ok(None)
+                }
+            };
+        }
+    };
+
+    if let Err(err) = text.enter()
+    {
+        errors.push(recoverable(err)?);
+        skip_container(text);
+
+        return Ok(None);
+    }
+
+    let value = if beginning == '['
+    {
+        recover_list(text, errors)?
+    } else
+    {
+        recover_object(text, errors)?
+    };
+
+    text.leave();
+
+    Ok(Some(value))
+}
+
+fn recover_list(text: &mut TextIter, errors: &mut Vec<ParseError>) -> Result<ObjectValue, ParseError>
+{
+    let start = text.offset();
+    text.next();
+
+    let mut values = Vec::new();
+    let mut first = true;
+    let mut full = false;
+
+    loop
+    {
+        let c = match text.skip_whitespace().peek().copied()
+        {
+            Some(c) => c,
+            None =>
+            {
+                errors.push(recoverable(text.error_at(start, Some('['), "unclosed list"))?);
+
+                break;
+            }
+        };
+
+        if c == ']'
+        {
+            text.next();
+
+            break;
+        }
+
+        if !first
+        {
+            match c
+            {
+                ',' =>
+                {
+                    text.next();
+
+                    if text.trailing_comma(']')
+                    {
+                        continue;
+                    }
+                },
+                '}' =>
+                {
+                    errors.push(recoverable(text.unexpected("','"))?);
+
+                    // most likely closes whatever this is inside of
+                    if text.depth == 1
+                    {
+                        text.next();
+                    }
+
+                    break;
+                },
+                // as if the comma was there
+                _ => errors.push(recoverable(text.unexpected("','"))?)
+            }
+        }
+
+        first = false;
+
+        // only the first value past the limit gets an error
+        if !full
+        {
+            if let Err(err) = text.check_elements(values.len())
+            {
+                errors.push(recoverable(err)?);
+                full = true;
+            }
+        }
+
+        match parse_recovering(text, errors)?
+        {
+            Some(value) if !full => values.push(value),
+            Some(_) => (),
+            None => skip_to_structural(text)
+        }
+    }
+
+    Ok(ObjectValue::List(Arc::new(values)))
+}
+
+fn recover_object(text: &mut TextIter, errors: &mut Vec<ParseError>) -> Result<ObjectValue, ParseError>
+{
+    let start = text.offset();
+    text.next();
+
+    let mut fields: Vec<ObjectField> = Vec::new();
+    let mut seen: HashMap<Arc<str>, usize> = HashMap::new();
+    let mut first = true;
+    let mut full = false;
+
+    loop
+    {
+        let c = match text.skip_whitespace().peek().copied()
+        {
+            Some(c) => c,
+            None =>
+            {
+                errors.push(recoverable(text.error_at(start, Some('{'), "unclosed object"))?);
+
+                break;
+            }
+        };
+
+        if c == '}'
+        {
+            text.next();
+
+            break;
+        }
+
+        if !first
+        {
+            match c
+            {
+                ',' =>
+                {
+                    text.next();
+
+                    if text.trailing_comma('}')
+                    {
+                        continue;
+                    }
+                },
+                ']' =>
+                {
+                    errors.push(recoverable(text.unexpected("','"))?);
+
+                    // most likely closes whatever this is inside of
+                    if text.depth == 1
+                    {
+                        text.next();
+                    }
+
+                    break;
+                },
+                _ => errors.push(recoverable(text.unexpected("','"))?)
+            }
+        }
+
+        first = false;
+
+        if !full
+        {
+            if let Err(err) = text.check_fields(fields.len())
+            {
+                errors.push(recoverable(err)?);
+                full = true;
+            }
+        }
+
+        let key_start = text.skip_whitespace().offset();
+        let quote = text.peek().copied().filter(|c| text.options.is_quote(*c));
+
+        let key = match parse_key(text)
+        {
+            Ok(key) => key,
+            Err(err) =>
+            {
+                errors.push(recoverable(err)?);
+
+                if let Some(quote) = quote
+                {
+                    skip_text_rest(text, quote);
+                }
+
+                skip_to_structural(text);
+
+                continue;
+            }
+        };
+
+        if let Err(err) = verify_char(text, ':')
+        {
+            errors.push(recoverable(err)?);
+            skip_to_structural(text);
+
+            continue;
+        }
+
+        let key = text.intern_key(key.into_owned());
+
+        let value = match parse_recovering(text, errors)?
+        {
+            Some(value) => value,
+            None =>
+            {
+                skip_to_structural(text);
+
+                continue;
+            }
+        };
+
+        if full
+        {
+            continue;
+        }
+
+        match seen.get(&key).copied()
+        {
+            Some(id) => match text.options.duplicate_keys
+            {
+                DuplicateKeys::Keep => fields.push(ObjectField{key, value}),
+                DuplicateKeys::FirstWins => (),
+                DuplicateKeys::LastWins => fields[id].value = value,
+                DuplicateKeys::Error =>
+                {
+                    errors.push(text.error_at(key_start, quote, format!("duplicate key {key:?}")));
+                }
+            },
+            None =>
+            {
+                seen.insert(key.clone(), fields.len());
+                fields.push(ObjectField{key, value});
+            }
+        }
+    }
+
+    Ok(ObjectValue::Object(Arc::new(Object::from_fields(fields))))
+}
+
+// goes up to the next comma or closing bracket thats not inside a string or a nested container
+fn skip_to_structural(text: &mut TextIter)
+{
+    let mut depth = 0_usize;
+
+    while let Some(c) = text.peek().copied()
+    {
+        match c
+        {
+            ',' | ']' | '}' if depth == 0 => return,
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            c if text.options.is_quote(c) =>
+            {
+                text.next();
+                skip_text_rest(text, c);
+
+                continue;
+            },
+            _ => ()
+        }
+
+        text.next();
+    }
+}
+
+// right after the opening quote or somewhere in the middle of a string, a line break
+// counts as the end too since the string was most likely just never closed
+fn skip_text_rest(text: &mut TextIter, quote: char)
+{
+    let mut escaped = false;
+
+    while let Some(c) = text.next_if(|c| *c != '\n')
+    {
+        match c
+        {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return,
+            _ => ()
+        }
+    }
+}
+
+// the whole list or object starting right here, for ones too deep to look inside
+fn skip_container(text: &mut TextIter)
+{
+    text.next();
+
+    loop
+    {
+        skip_to_structural(text);
+
+        if text.next() != Some(',')
+        {
+            return;
+        }
+    }
+}
+
+// goes through a value only to check it
+struct Skip;
+
+impl Handler for Skip {}
+
+// for parses that hand values out as they go, FirstWins skips the duplicate instead
+pub(crate) fn streamed_duplicate(text: &TextIter, start: usize, key: &str) -> ParseError
+{
+    let message = match text.options.duplicate_keys
+    {
+        DuplicateKeys::LastWins => format!("duplicate key {key:?}, the last one cant win when values are handed out as theyre parsed"),
+        _ => format!("duplicate key {key:?}")
+    };
+
+    text.error_at(start, Some('"'), message)
+}
+
+// checks the value and hands back its text without building anything, none for a reader
+// since whatever it read is gone
+fn parse_raw<'a>(text: &mut TextIter<'a>) -> Result<Option<RawValue<'a>>, ParseError>
+{
+    let start = text.skip_whitespace().offset();
+
+    if text.slice(start, start).is_none()
+    {
+        return Ok(None);
+    }
+
+    // its only checked here, the policy gets applied when its parsed
+    let duplicates = match text.options.duplicate_keys
+    {
+        DuplicateKeys::Error => DuplicateKeys::Error,
+        _ => DuplicateKeys::Keep
+    };
+
+    parse_events(text, &mut Skip, duplicates)?;
+
+    let end = text.offset();
+    let raw = text.text_slice(start, end)?;
+
+    let options = text.raw_options.get_or_insert_with(|| Arc::new(text.options.clone())).clone();
+
+    Ok(Some(RawValue::new(raw, options)))
+}
+
+// same grammar as ObjectValue::parse but every value goes to the handler instead of into a tree
+fn parse_events(text: &mut TextIter, handler: &mut impl Handler, duplicates: DuplicateKeys) -> Result<(), ParseError>
+{
+    text.check_interrupt()?;
+
+    text.skip_whitespace();
 
-fn verify_char(mut text: impl Iterator<Item=char>, expected: char)
+    match text.peek().copied()
+    {
+        Some('[') =>
+        {
+            text.enter()?;
+            verify_char(text, '[')?;
+            handler.on_list_start();
+
+            let mut count = 0;
+            while let Some(c) = text.skip_whitespace().peek()
+            {
+                if *c == ']'
+                {
+                    break;
+                }
+
+                if count > 0
+                {
+                    verify_char(text, ',')?;
+
+                    if text.trailing_comma(']')
+                    {
+                        break;
+                    }
+                }
+
+                text.check_elements(count)?;
+                count += 1;
+
+                parse_events(text, handler, duplicates)?;
+            }
+
+            verify_char(text, ']')?;
+            handler.on_list_end();
+            text.leave();
+        },
+        Some('{') =>
+        {
+            text.enter()?;
+            verify_char(text, '{')?;
+            handler.on_object_start();
+
+            // only filled when duplicates get looked for
+            let mut seen = HashSet::new();
+
+            let mut count = 0;
+            while let Some(c) = text.skip_whitespace().peek()
+            {
+                if *c == '}'
+                {
+                    break;
+                }
+
+                if count > 0
+                {
+                    verify_char(text, ',')?;
+
+                    if text.trailing_comma('}')
+                    {
+                        break;
+                    }
+                }
+
+                text.check_fields(count)?;
+                count += 1;
+
+                let start = text.skip_whitespace().offset();
+                let key = parse_key(text)?;
+
+                verify_char(text, ':')?;
+
+                let duplicate = duplicates != DuplicateKeys::Keep && !seen.insert(key.clone());
+
+                if duplicate && duplicates == DuplicateKeys::FirstWins
+                {
+                    parse_events(text, &mut Skip, duplicates)?;
+
+                    continue;
+                }
+
+                handler.on_key(&key);
+                parse_events(text, handler, duplicates)?;
+
+                // after the value so a broken one gets complained about first like in parse
+                if duplicate
+                {
+                    return Err(streamed_duplicate(text, start, &key));
+                }
+            }
+
+            verify_char(text, '}')?;
+            handler.on_object_end();
+            text.leave();
+        },
+        Some(c) if text.options.is_quote(c) => handler.on_string(&parse_text_borrowed(text)?),
+        Some(_) =>
+        {
+            match ObjectValue::parse(text)?
+            {
+                ObjectValue::Null => handler.on_null(),
+                ObjectValue::Number(x) => handler.on_number(x),
+                ObjectValue::Float(x) => handler.on_float(x),
+                ObjectValue::Bool(x) => handler.on_bool(x),
+                _ => return Err(text.internal_error("a scalar parsed into a container or a string"))
+            }
+        },
+        None => return Err(text.unexpected("a value"))
+    }
+
+    Ok(())
+}
+
+// parses a single value that starts at byte offset base of the full text, returns how far it got
+pub(crate) fn parse_spanned_at(
+    text: &str,
+    base: usize,
+    options: &ParserOptions
+) -> Result<(ObjectValue, SpanTree, usize), ParseError>
+{
+    let mut text = TextIter::new(text, base, options.clone()).record_spans();
+
+    let value = ObjectValue::parse(&mut text)?;
+
+    Ok((value, text.take_spans()?, text.offset()))
+}
+
+// the elements of a list between two of the commas parallel::split picked, the last
+// chunk also has the closing bracket and whatever comes after it
+#[cfg(feature = "parallel")]
+fn parse_chunk(text: &mut TextIter, last: bool) -> Result<Vec<ObjectValue>, ParseError>
 {
-    let mut this = text.next();
-    if this.map(|c| c.is_whitespace()).unwrap_or(false)
+    // the list the chunk is in counts towards the depth
+    text.enter()?;
+
+    let mut values = vec![ObjectValue::parse(text)?];
+
+    loop
     {
-        this = text.next();
+        match text.skip_whitespace().peek()
+        {
+            None if !last => break,
+            Some(']') if last => break,
+            _ => ()
+        }
+
+        verify_char(text, ',')?;
+
+        if last && text.trailing_comma(']')
+        {
+            break;
+        }
+
+        values.push(ObjectValue::parse(text)?);
     }
 
-    if this != Some(expected)
+    if last
     {
-        panic!(
-            "tried to parse the wrong object (expected '{expected}' got {})",
-            this.map(|c| format!("'{c}'")).unwrap_or_else(|| "none".to_owned())
-        );
+        verify_char(text, ']')?;
+        text.finish()?;
     }
-}
 
-fn parse_text(mut text: &mut TextIter) -> String
-{
-    verify_char(&mut text, '"');
+    text.leave();
 
-    text.take_while(move |c| *c != '"').collect()
+    Ok(values)
 }
 
-#[derive(Debug)]
-pub enum ObjectValue
+pub struct Parser<'a>
 {
-    Text(String),
-    Number(u32),
-    Bool(bool),
-    List(Box<[ObjectValue]>),
-    Object(Box<Object>)
+    text: TextIter<'a>,
+    sorted_keys: bool
 }
 
-impl ObjectValue
+impl<'a> Parser<'a>
 {
-    #[allow(dead_code)]
-    pub fn get_text(&self) -> Option<&str>
+    pub fn new(text: TextIterInner<'a>) -> Self
     {
-        match self
+        Self{text: TextIter::new(text.as_str(), 0, ParserOptions::default()), sorted_keys: false}
+    }
+
+    // reads and decodes as it parses instead of needing the whole text in a string first
+    pub fn from_reader(reader: impl Read + 'a) -> Self
+    {
+        let chars = Source::Reader(ReaderChars::new(reader));
+
+        Self{text: TextIter::with_source(chars, 0, ParserOptions::default()), sorted_keys: false}
+    }
+
+    // for input that only exists as bytes, invalid utf8 is an error at the offset where it starts
+    pub fn from_bytes(bytes: &'a [u8]) -> Self
+    {
+        let chars = Source::Reader(ReaderChars::from_bytes(bytes));
+
+        Self{text: TextIter::with_source(chars, 0, ParserOptions::default()), sorted_keys: false}
+    }
+
+    pub fn options(mut self, options: ParserOptions) -> Self
+    {
+        if let Source::Reader(reader) = &mut self.text.chars
         {
-            ObjectValue::Text(x) => Some(x),
-            _ => None
+            reader.set_lossy(options.lossy_utf8);
         }
+
+        self.text.options = options;
+
+        self
     }
 
-    #[allow(dead_code)]
-    pub fn get_number(&self) -> Option<u32>
+    // keys and short strings come out of the interner so they share memory with every other parse using it
+    pub fn interner(mut self, interner: &'a SharedInterner) -> Self
     {
-        match self
+        self.text.interner = Some(interner);
+
+        self
+    }
+
+    // repeated keys like in a list of similar objects share one allocation, the shared
+    // interner does the same across parses and takes priority over this
+    pub fn intern_keys(mut self, intern_keys: bool) -> Self
+    {
+        self.text.keys = intern_keys.then(HashSet::new);
+
+        self
+    }
+
+    // for parse_borrowed, lists and objects nested deeper than depth are checked but kept as their
+    // text so fields that arent needed never get built and can be written back out untouched, with
+    // 0 thats the whole document, a reader has no text to keep so everything gets parsed
+    pub fn raw_below(mut self, depth: usize) -> Self
+    {
+        self.text.raw_below = Some(depth);
+
+        self
+    }
+
+    pub fn sorted_keys(mut self, sorted_keys: bool) -> Self
+    {
+        self.sorted_keys = sorted_keys;
+
+        self
+    }
+
+    // checked every few hundred values, the parse fails with a cancelled error once it fires
+    pub fn cancel_token(mut self, token: CancelToken) -> Self
+    {
+        self.text.cancel = Some(token);
+
+        self
+    }
+
+    pub fn deadline(mut self, deadline: Instant) -> Self
+    {
+        self.text.deadline = Some(deadline);
+
+        self
+    }
+
+    pub fn parse(mut self) -> Result<ObjectValue, ParseError>
+    {
+        let mut value = ObjectValue::parse(&mut self.text)?;
+        self.text.finish()?;
+
+        if self.sorted_keys
         {
-            ObjectValue::Number(x) => Some(*x),
-            _ => None
+            value.sort_keys();
         }
+
+        Ok(value)
     }
 
-    #[allow(dead_code)]
-    pub fn get_bool(&self) -> Option<bool>
+    pub fn parse_spanned(self) -> Result<(ObjectValue, SpanTree), ParseError>
     {
-        match self
+        let sorted_keys = self.sorted_keys;
+        let mut text = self.text.record_spans();
+
+        let mut value = ObjectValue::parse(&mut text)?;
+        text.finish()?;
+
+        let mut spans = text.take_spans()?;
+
+        if sorted_keys
         {
-            ObjectValue::Bool(x) => Some(*x),
-            _ => None
+            value.sort_keys_spanned(&mut spans);
         }
+
+        Ok((value, spans))
     }
 
-    #[allow(dead_code)]
-    pub fn get_list(&self) -> Option<&[ObjectValue]>
+    pub fn parse_reported(self) -> Result<(ObjectValue, ParseReport), ParseError>
     {
-        match self
+        let started = Instant::now();
+
+        let sorted_keys = self.sorted_keys;
+        let mut text = self.text.record_report();
+
+        let mut value = ObjectValue::parse(&mut text)?;
+        text.finish()?;
+
+        if sorted_keys
         {
-            ObjectValue::List(x) => Some(x),
-            _ => None
+            value.sort_keys();
         }
+
+        let bytes = text.offset() - text.base;
+        let mut report = match text.report.take()
+        {
+            Some(report) => report.report,
+            None => return Err(text.internal_error("the report wasnt recorded"))
+        };
+
+        report.bytes = bytes;
+        report.build_time = started.elapsed().saturating_sub(report.lex_time);
+
+        Ok((value, report))
     }
 
-    #[allow(dead_code)]
-    pub fn get_object(&self) -> Option<&Object>
+    // strings and keys without escapes borrow from the text instead of getting copied
+    pub fn parse_borrowed(mut self) -> Result<BorrowedValue<'a>, ParseError>
     {
-        match self
+        let mut value = BorrowedValue::parse(&mut self.text)?;
+        self.text.finish()?;
+
+        if self.sorted_keys
         {
-            ObjectValue::Object(x) => Some(x),
-            _ => None
+            value.sort_keys();
         }
+
+        Ok(value)
     }
 
-    pub fn parse(text: &mut TextIter) -> Self
+    // keeps going after syntax errors to find all of them at once, only cancelling
+    // and the reader failing are still an error
+    pub fn parse_recovering(mut self) -> Result<Recovered, ParseError>
     {
-        let beginning = text.peek().expect("text must not be empty");
+        let mut errors = Vec::new();
 
-        match beginning.to_ascii_lowercase()
+        let value = parse_recovering(&mut self.text, &mut errors)?;
+
+        if let Err(err) = self.text.finish()
         {
-            '"' => Self::parse_text(text),
-            '[' => Self::parse_list(text),
-            '{' => Self::parse_object(text),
-            'f' | 't' => Self::parse_bool(text),
-            n if n.is_digit(10) => Self::parse_number(text),
-            _ => panic!("unexpected token: '{}'", beginning)
+            errors.push(recoverable(err)?);
         }
+
+        Ok(Recovered{value, errors})
     }
 
-    fn parse_text(text: &mut TextIter) -> Self
+    // nothing gets built, the handler sees every value in document order,
+    // on an error it has already seen everything before it
+    pub fn parse_events(mut self, handler: &mut impl Handler) -> Result<(), ParseError>
     {
-        Self::Text(parse_text(text))
+        let duplicates = self.text.options.duplicate_keys;
+
+        parse_events(&mut self.text, handler, duplicates)?;
+
+        self.text.finish()
     }
 
-    fn parse_number(text: &mut TextIter) -> Self
+    // flat instead of a tree, faster to build and walk for reading through big documents
+    pub fn parse_tape(mut self) -> Result<Tape, ParseError>
     {
-        let mut number = String::new();
+        let mut builder = TapeBuilder::new();
 
-        while let Some(c) = text.peek()
-        {
-            if !c.is_digit(10)
-            {
-                break;
-            }
+        let duplicates = self.text.options.duplicate_keys;
 
-            number.push(text.next().expect("checked that its not none with peek"));
-        }
+        parse_events(&mut self.text, &mut builder, duplicates)?;
+        self.text.finish()?;
 
-        Self::Number(number.parse().expect("must be a number"))
+        match builder.finish()
+        {
+            Some(tape) => Ok(tape),
+            None => Err(self.text.internal_error("the tape doesnt have a whole value"))
+        }
     }
 
-    fn parse_bool(text: &mut TextIter) -> Self
+    // a big list at the root gets split at commas between its elements and the chunks get parsed on
+    // every core, anything else goes through parse and so does an error in any chunk so the errors
+    // are exactly the ones parse gives, keys only get interned within a chunk
+    #[cfg(feature = "parallel")]
+    pub fn parse_parallel(self) -> Result<ObjectValue, ParseError>
     {
-        let beginning = text.next().expect("bool parse request must be valid")
-            .to_ascii_lowercase();
-
-        let keyword_length = match beginning
+        let text = match &self.text.chars
         {
-            't' => 3,
-            'f' => 4,
-            x => panic!("unexpected token: '{}'", x)
+            Source::Text{text, ..} => *text,
+            Source::Reader(_) => return self.parse()
         };
 
-        let mut value = beginning.to_string();
-        value.extend(text.take(keyword_length));
+        let options = &self.text.options;
 
-        let value = match value.to_lowercase().as_ref()
+        // the limit only counts whats read, easier to not split at all than to figure that out
+        if text.len() > options.max_document_size
         {
-            "true" => true,
-            "false" => false,
-            x => panic!("invalid boolean value: {}", x)
-        };
+            return self.parse();
+        }
 
-        Self::Bool(value)
-    }
+        let ranges = match parallel::split(text.as_bytes(), options, rayon::current_num_threads() * 4)
+        {
+            Some(x) => x,
+            None => return self.parse()
+        };
 
-    fn parse_list(mut text: &mut TextIter) -> Self
-    {
-        verify_char(&mut text, '[');
+        let last = ranges.len() - 1;
 
-        let mut values = Vec::new();
+        let interner = self.text.interner;
+        let cancel = &self.text.cancel;
+        let deadline = self.text.deadline;
+        let intern_keys = self.text.keys.is_some();
 
-        while let Some(c) = text.peek()
-        {
-            if *c == ']'
+        let chunks: Result<Vec<Vec<ObjectValue>>, ParseError> = ranges.into_par_iter()
+            .enumerate()
+            .map(|(id, range)|
             {
-                break;
-            }
+                // the last chunk sees the rest of the text to check whats after the list
+                let end = if id == last { text.len() } else { range.end };
 
-            if !values.is_empty()
-            {
-                verify_char(&mut text, ',');
-            }
+                let chars = text[range.start..end].chars();
+                let mut chunk = TextIter::with_source(Source::Text{text: &text[..end], chars}, 0, options.clone());
 
-            let value = ObjectValue::parse(&mut text);
+                chunk.interner = interner;
+                chunk.cancel = cancel.clone();
+                chunk.deadline = deadline;
+                chunk.keys = intern_keys.then(HashSet::new);
 
-            values.push(value);
+                parse_chunk(&mut chunk, id == last)
+            })
+            .collect();
+
+        let values: Vec<ObjectValue> = match chunks
+        {
+            Ok(chunks) => chunks.into_iter().flatten().collect(),
+            Err(_) => return self.parse()
+        };
+
+        if values.len() > options.max_elements
+        {
+            return self.parse();
         }
 
-        verify_char(text, ']');
+        let mut value = ObjectValue::List(Arc::new(values));
+
+        if self.sorted_keys
+        {
+            value.sort_keys();
+        }
 
-        Self::List(values.into_boxed_slice())
+        Ok(value)
     }
 
-    fn parse_object(text: &mut TextIter) -> Self
+    // back to back documents like {..}{..} or one per line, each with the byte offset it starts at
+    pub fn parse_stream(self) -> Documents<'a>
     {
-        Self::Object(Box::new(Object::parse(text)))
+        Documents::new(self.text, self.sorted_keys)
     }
-}
 
-impl Index<usize> for ObjectValue
-{
-    type Output = ObjectValue;
+    // nothing gets parsed until the tokenizer is asked for the next token
+    pub fn tokenize(self) -> Tokenizer<'a>
+    {
+        Tokenizer::new(self.text)
+    }
 
-    fn index(&self, id: usize) -> &Self::Output
+    // reading never blocks the runtime, the parse itself still happens in one go at the end
+    #[cfg(feature = "async")]
+    pub fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(reader: R) -> AsyncParser<R>
     {
-        match self
-        {
-            ObjectValue::List(list) => &list[id],
-            x => panic!("cant index into a value of type: {:?}", x)
-        }
+        AsyncParser::new(reader)
     }
-}
 
-#[derive(Debug)]
-pub struct ObjectField
-{
-    key: String,
-    value: ObjectValue
+    pub fn parse_deduplicated(self) -> Result<(ObjectValue, DedupReport), ParseError>
+    {
+        let mut value = self.parse()?;
+
+        let mut deduplicator = Deduplicator::new();
+        deduplicator.deduplicate(&mut value);
+
+        Ok((value, deduplicator.report()))
+    }
 }
 
-impl ObjectField
+#[cfg(test)]
+mod tests
 {
-    pub fn parse(mut text: &mut TextIter) -> Self
-    {
-        let key = parse_text(text);
+    use crate::events::ValueBuilder;
 
-        verify_char(&mut text, ':');
+    use super::*;
 
-        let value = ObjectValue::parse(text);
 
-        Self{key, value}
+    fn comments() -> ParserOptions
+    {
+        ParserOptions::default().comments(true).trailing_content(false)
     }
 
-    #[allow(dead_code)]
-    pub fn key(&self) -> &str
+    fn parse(text: &str, options: ParserOptions) -> Result<ObjectValue, ParseError>
     {
-        &self.key
+        Parser::new(text.chars()).options(options).parse()
     }
 
-    #[allow(dead_code)]
-    pub fn value(&self) -> &ObjectValue
+    const DUPLICATES: &str = r#"{"a": 1, "b": {"a": 2}, "a": [3]}"#;
+
+    fn duplicates(duplicate_keys: DuplicateKeys) -> Parser<'static>
     {
-        &self.value
+        Parser::new(DUPLICATES.chars()).options(ParserOptions::default().duplicate_keys(duplicate_keys))
     }
-}
-
-#[derive(Debug)]
-pub struct Object
-{
-    fields: Box<[ObjectField]>
-}
 
-impl Object
-{
-    pub fn parse(mut text: &mut TextIter) -> Self
+    // everything that builds a tree has to end up with the same one parse does
+    fn trees(duplicate_keys: DuplicateKeys) -> [Result<ObjectValue, usize>; 4]
     {
-        verify_char(&mut text, '{');
+        let events = || -> Result<ObjectValue, ParseError>
+        {
+            let mut builder = ValueBuilder::new();
+            duplicates(duplicate_keys).parse_events(&mut builder)?;
 
-        let mut fields = Vec::new();
+            Ok(builder.finish().unwrap())
+        };
 
-        while let Some(c) = text.peek()
-        {
-            if *c == '}'
-            {
-                break;
-            }
+        [
+            duplicates(duplicate_keys).parse(),
+            duplicates(duplicate_keys).parse_borrowed().map(BorrowedValue::into_owned),
+            events(),
+            duplicates(duplicate_keys).parse_tape().map(|tape| tape.root().to_value())
+        ].map(|result| result.map_err(|err| err.offset))
+    }
 
-            if !fields.is_empty()
-            {
-                verify_char(&mut text, ',');
-            }
+    #[test]
+    fn duplicate_keys_everywhere()
+    {
+        let nested = r#"{"a": 2}"#;
+
+        let first = parse(&format!(r#"{{"a": 1, "b": {nested}}}"#), ParserOptions::default()).unwrap();
 
-            let field = ObjectField::parse(&mut text);
+        for duplicate_keys in [DuplicateKeys::Keep, DuplicateKeys::FirstWins]
+        {
+            let expected = duplicates(duplicate_keys).parse().unwrap();
 
-            fields.push(field);
+            assert_eq!(trees(duplicate_keys), [0; 4].map(|_| Ok(expected.clone())), "{duplicate_keys:?}");
         }
 
-        verify_char(text, '}');
+        assert_eq!(trees(DuplicateKeys::FirstWins)[0], Ok(first));
+        assert_eq!(trees(DuplicateKeys::Error), [Err(24), Err(24), Err(24), Err(24)]);
+
+        // theres no taking back a value thats already been handed out
+        let last = parse(&format!(r#"{{"a": [3], "b": {nested}}}"#), ParserOptions::default()).unwrap();
 
-        Self{fields: fields.into_boxed_slice()}
+        assert_eq!(trees(DuplicateKeys::LastWins), [Ok(last.clone()), Ok(last), Err(24), Err(24)]);
     }
 
-    #[allow(dead_code)]
-    pub fn fields(&self) -> &[ObjectField]
+    // the prefix and then the filler forever, the token gets cancelled once theres been plenty of it
+    struct Endless
     {
-        &self.fields
+        prefix: &'static [u8],
+        filler: u8,
+        given: usize,
+        token: CancelToken
     }
-}
-
-impl Index<&str> for Object
-{
-    type Output = ObjectValue;
 
-    fn index(&self, id: &str) -> &Self::Output
+    impl Read for Endless
     {
-        self.fields.iter().find_map(|field|
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize>
         {
-            if AsRef::<str>::as_ref(&field.key) == id
+            if !self.prefix.is_empty()
             {
-                Some(&field.value)
-            } else
+                return self.prefix.read(buffer);
+            }
+
+            buffer.fill(self.filler);
+            self.given += buffer.len();
+
+            if self.given > 1 << 20
             {
-                None
+                self.token.cancel();
             }
-        }).unwrap_or_else(|| panic!("field with key \"{}\" not found", id))
+
+            Ok(buffer.len())
+        }
     }
-}
 
-type TextIterInner<'a> = Chars<'a>;
-type TextIter<'a> = Peekable<TextIterInner<'a>>;
+    #[test]
+    fn cancelled_inside_one_value()
+    {
+        for (prefix, filler) in [(&b"[\""[..], b'a'), (b"[", b' '), (b"[/*", b'*')]
+        {
+            let token = CancelToken::new();
+            let reader = Endless{prefix, filler, given: 0, token: token.clone()};
 
-pub struct Parser<'a>
-{
-    text: TextIter<'a>
-}
+            let err = Parser::from_reader(reader).options(comments()).cancel_token(token).parse().unwrap_err();
 
-impl<'a> Parser<'a>
-{
-    pub fn new(text: TextIterInner<'a>) -> Self
+            assert_eq!(err.kind, ParseErrorKind::Cancelled, "{}", String::from_utf8_lossy(prefix));
+        }
+    }
+
+    #[test]
+    fn comments_are_skipped()
     {
-        Self{text: text.peekable()}
+        let value = parse("/* a */ [1, // b\n 2 /* c */] // d", comments()).unwrap();
+
+        assert_eq!(value, ObjectValue::from(vec![ObjectValue::from(1), ObjectValue::from(2)]));
     }
 
-    pub fn parse(mut self) -> ObjectValue
+    #[test]
+    fn unterminated_block_comment()
     {
-        ObjectValue::parse(&mut self.text)
+        for (text, offset) in [("/* a", 0), ("[1, /* a", 4), ("{\"a\" /* a */ /* b", 13), ("1 /* a", 2), ("[1] /*/", 4)]
+        {
+            let err = parse(text, comments()).unwrap_err();
+
+            assert_eq!((err.message.as_str(), err.offset), ("unterminated block comment", offset), "{text}");
+
+            let err = Parser::from_reader(text.as_bytes()).options(comments()).parse().unwrap_err();
+
+            assert_eq!((err.message.as_str(), err.offset), ("unterminated block comment", offset), "{text}");
+        }
+
+        // nothing after the value gets looked at with trailing content allowed
+        assert!(parse("1 /* a", comments().trailing_content(true)).is_ok());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,343 @@
+use std::{
+    iter::Peekable,
+    str::CharIndices,
+    sync::Arc
+};
+
+use crate::{
+    writer,
+    parser::{self, Parser, ObjectValue}
+};
+
+
+#[derive(Debug, Clone)]
+pub enum Stage
+{
+    // pointer where * matches every child
+    Path(String),
+    Select{pointer: String, value: String, negate: bool},
+    // runs the stages over every child and collects the results into a list
+    Map(Vec<Stage>)
+}
+
+pub fn run(stages: &[Stage], input: ObjectValue) -> Vec<ObjectValue>
+{
+    stages.iter().fold(vec![input], |values, stage|
+    {
+        match stage
+        {
+            Stage::Path(path) =>
+            {
+                values.iter().flat_map(|value| value.query(path).into_iter().cloned().collect::<Vec<_>>()).collect()
+            },
+            Stage::Select{pointer, value, negate} =>
+            {
+                values.into_iter().filter(|x|
+                {
+                    let equal = x.pointer(pointer).map(|x| writer::to_compact(x) == *value).unwrap_or(false);
+
+                    equal != *negate
+                }).collect()
+            },
+            Stage::Map(inner) =>
+            {
+                values.into_iter().map(|value|
+                {
+                    let mapped: Vec<ObjectValue> = value.query("/*").into_iter()
+                        .flat_map(|child| run(inner, child.clone()))
+                        .collect();
+
+                    ObjectValue::List(Arc::from(mapped))
+                }).collect()
+            }
+        }
+    })
+}
+
+// POINTER=JSON or POINTER!=JSON
+pub fn parse_where(condition: &str) -> Option<Stage>
+{
+    let (pointer, value, negate) = match condition.split_once("!=")
+    {
+        Some((pointer, value)) => (pointer, value, true),
+        None =>
+        {
+            let (pointer, value) = condition.split_once('=')?;
+
+            (pointer, value, false)
+        }
+    };
+
+    let value = writer::to_compact(&Parser::new(value.chars()).parse().ok()?);
+
+    Some(Stage::Select{pointer: pointer.to_owned(), value, negate})
+}
+
+struct JqParser<'a>
+{
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>
+}
+
+impl<'a> JqParser<'a>
+{
+    fn skip_whitespace(&mut self)
+    {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char>
+    {
+        self.skip_whitespace();
+
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn offset(&mut self) -> usize
+    {
+        self.chars.peek().map(|(offset, _)| *offset).unwrap_or(self.text.len())
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String>
+    {
+        match self.peek()
+        {
+            Some(c) if c == expected =>
+            {
+                self.chars.next();
+
+                Ok(())
+            },
+            Some(c) => Err(format!("expected {expected:?} but found {c:?}")),
+            None => Err(format!("expected {expected:?} but the filter ended"))
+        }
+    }
+
+    fn identifier(&mut self) -> String
+    {
+        let mut name = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+        {
+            name.push(c);
+        }
+
+        name
+    }
+
+    // .foo.bar[0][]["key"] turned into a pointer
+    fn path(&mut self) -> Result<String, String>
+    {
+        self.expect('.')?;
+
+        let mut pointer = String::new();
+
+        let name = self.identifier();
+        if !name.is_empty()
+        {
+            pointer.push('/');
+            pointer.push_str(&parser::escape_pointer_token(&name));
+        }
+
+        loop
+        {
+            match self.chars.peek().map(|(_, c)| *c)
+            {
+                Some('.') =>
+                {
+                    self.chars.next();
+
+                    let name = self.identifier();
+                    if name.is_empty()
+                    {
+                        return Err("expected a key after .".to_owned());
+                    }
+
+                    pointer.push('/');
+                    pointer.push_str(&parser::escape_pointer_token(&name));
+                },
+                Some('[') =>
+                {
+                    self.chars.next();
+
+                    let token = match self.peek()
+                    {
+                        Some(']') => "*".to_owned(),
+                        Some('"') =>
+                        {
+                            let start = self.offset();
+                            let end = literal_end(self.text, start);
+
+                            while self.offset() < end
+                            {
+                                self.chars.next();
+                            }
+
+                            let key = Parser::new(self.text[start..end].chars()).parse()
+                                .map_err(|err| format!("invalid key: {err}"))?;
+
+                            key.get_text().map(parser::escape_pointer_token)
+                                .ok_or_else(|| "expected a string key inside []".to_owned())?
+                        },
+                        _ =>
+                        {
+                            let index = self.identifier();
+                            if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit())
+                            {
+                                return Err(format!("cant index with {index:?}, only numbers and strings"));
+                            }
+
+                            index
+                        }
+                    };
+
+                    self.expect(']')?;
+
+                    pointer.push('/');
+                    pointer.push_str(&token);
+                },
+                _ => return Ok(pointer)
+            }
+        }
+    }
+
+    fn select(&mut self) -> Result<Stage, String>
+    {
+        self.expect('(')?;
+
+        let pointer = self.path()?;
+        if pointer.split('/').any(|token| token == "*")
+        {
+            return Err("select only supports plain paths".to_owned());
+        }
+
+        let negate = match self.peek()
+        {
+            Some('=') => false,
+            Some('!') => true,
+            _ => return Err("select needs a == or != comparison".to_owned())
+        };
+
+        self.chars.next();
+        self.expect('=')?;
+        self.skip_whitespace();
+
+        let start = self.offset();
+        let end = literal_end(self.text, start);
+
+        while self.offset() < end
+        {
+            self.chars.next();
+        }
+
+        let literal = self.text[start..end].trim();
+        if literal.is_empty()
+        {
+            return Err("select needs a value to compare with".to_owned());
+        }
+
+        self.expect(')')?;
+
+        let value = Parser::new(literal.chars()).parse().map_err(|err| format!("invalid value {literal}: {err}"))?;
+        let value = writer::to_compact(&value);
+
+        Ok(Stage::Select{pointer, value, negate})
+    }
+
+    fn term(&mut self) -> Result<Vec<Stage>, String>
+    {
+        match self.peek()
+        {
+            Some('.') => Ok(vec![Stage::Path(self.path()?)]),
+            Some(c) if c.is_alphabetic() =>
+            {
+                let name = self.identifier();
+
+                match name.as_str()
+                {
+                    "select" => Ok(vec![self.select()?]),
+                    "map" =>
+                    {
+                        self.expect('(')?;
+                        let inner = self.pipeline()?;
+                        self.expect(')')?;
+
+                        Ok(vec![Stage::Map(inner)])
+                    },
+                    x => Err(format!("{x} isnt supported in jq compat mode"))
+                }
+            },
+            Some(c) => Err(format!("unexpected {c:?}")),
+            None => Err("expected a filter".to_owned())
+        }
+    }
+
+    fn pipeline(&mut self) -> Result<Vec<Stage>, String>
+    {
+        let mut stages = self.term()?;
+
+        while self.peek() == Some('|')
+        {
+            self.chars.next();
+
+            stages.extend(self.term()?);
+        }
+
+        Ok(stages)
+    }
+}
+
+// end of a json literal starting at start, stops at an unbalanced ) or ]
+fn literal_end(text: &str, start: usize) -> usize
+{
+    let mut depth = 0_usize;
+    let mut in_text = false;
+    let mut escaped = false;
+
+    for (offset, c) in text[start..].char_indices()
+    {
+        if in_text
+        {
+            match c
+            {
+                '\\' => escaped = !escaped,
+                '"' if !escaped =>
+                {
+                    in_text = false;
+
+                    if depth == 0
+                    {
+                        return start + offset + 1;
+                    }
+                },
+                _ => escaped = false
+            }
+
+            continue;
+        }
+
+        match c
+        {
+            '"' => in_text = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' | ')' if depth == 0 => return start + offset,
+            ']' | '}' => depth -= 1,
+            _ => ()
+        }
+    }
+
+    text.len()
+}
+
+// translates the common subset of jq into stages
+pub fn parse_jq(filter: &str) -> Result<Vec<Stage>, String>
+{
+    let mut parser = JqParser{text: filter, chars: filter.char_indices().peekable()};
+
+    let stages = parser.pipeline()?;
+
+    match parser.peek()
+    {
+        Some(c) => Err(format!("unexpected {c:?} after the filter")),
+        None => Ok(stages)
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+
+// splitmix64, plenty for sampling and test data but not for anything secret
+#[derive(Debug, Clone)]
+pub struct Rng
+{
+    state: u64
+}
+
+impl Rng
+{
+    pub fn new(seed: u64) -> Self
+    {
+        Self{state: seed}
+    }
+
+    pub fn from_time() -> Self
+    {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0);
+
+        Self::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut x = self.state;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        x ^ (x >> 31)
+    }
+
+    // uniform in 0..bound
+    pub fn below(&mut self, bound: u64) -> u64
+    {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+}
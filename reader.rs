@@ -0,0 +1,271 @@
+use std::{
+    str,
+    borrow::Cow,
+    collections::VecDeque,
+    io::{self, Read, ErrorKind}
+};
+
+use crate::lexer::Position;
+
+
+const READ_SIZE: usize = 64 * 1024;
+
+// past this many multibyte characters on one line the older ones get counted as ascii for columns
+const WIDE_HISTORY: usize = 64;
+
+fn invalid_utf8() -> io::Error
+{
+    io::Error::new(ErrorKind::InvalidData, "the text isnt valid utf8")
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Line
+{
+    start: usize,
+    number: usize,
+    // bytes past the first one of every multibyte character on the line
+    extra: usize
+}
+
+// decodes utf8 from a reader or a byte slice one character at a time, only the current
+// line is remembered (and barely) so errors can still get a line and column
+pub struct ReaderChars<'a>
+{
+    // none when all the bytes were there from the start
+    reader: Option<Box<dyn Read + 'a>>,
+    buffer: Cow<'a, [u8]>,
+    start: usize,
+    offset: usize,
+    error: Option<io::Error>,
+    // invalid bytes decode to U+FFFD instead of stopping
+    lossy: bool,
+    line: Line,
+    previous: Line,
+    wide: VecDeque<(usize, usize)>
+}
+
+impl<'a> ReaderChars<'a>
+{
+    pub fn new(reader: impl Read + 'a) -> Self
+    {
+        Self::with_buffer(Some(Box::new(reader)), Cow::Owned(Vec::with_capacity(READ_SIZE)))
+    }
+
+    // the slice gets validated as it gets decoded instead of all up front
+    pub fn from_bytes(bytes: &'a [u8]) -> Self
+    {
+        Self::with_buffer(None, Cow::Borrowed(bytes))
+    }
+
+    fn with_buffer(reader: Option<Box<dyn Read + 'a>>, buffer: Cow<'a, [u8]>) -> Self
+    {
+        let line = Line{start: 0, number: 1, extra: 0};
+
+        Self{
+            reader,
+            buffer,
+            start: 0,
+            offset: 0,
+            error: None,
+            lossy: false,
+            line,
+            previous: line,
+            wide: VecDeque::new()
+        }
+    }
+
+    pub fn set_lossy(&mut self, lossy: bool)
+    {
+        self.lossy = lossy;
+    }
+
+    // bytes decoded so far
+    pub fn offset(&self) -> usize
+    {
+        self.offset
+    }
+
+    // reading stops at the first error, to the parser it looks like the text ended
+    pub fn error(&self) -> Option<&io::Error>
+    {
+        self.error.as_ref()
+    }
+
+    // exact for anything on the current line and the end of the previous one
+    pub fn position(&self, offset: usize) -> Position
+    {
+        let (line, extra) = if offset >= self.line.start
+        {
+            let after: usize = self.wide.iter().filter(|(id, _)| *id >= offset).map(|(_, extra)| extra).sum();
+
+            (self.line, self.line.extra.saturating_sub(after))
+        } else
+        {
+            (self.previous, self.previous.extra)
+        };
+
+        Position{
+            line: line.number,
+            column: offset.saturating_sub(line.start).saturating_sub(extra) + 1
+        }
+    }
+
+    // keeps whatever wasnt decoded yet, false once the reader has nothing more
+    fn refill(&mut self) -> io::Result<bool>
+    {
+        let reader = match self.reader.as_mut()
+        {
+            Some(x) => x,
+            None => return Ok(false)
+        };
+
+        let buffer = self.buffer.to_mut();
+
+        buffer.drain(..self.start);
+        self.start = 0;
+
+        let filled = buffer.len();
+        buffer.resize(READ_SIZE, 0);
+
+        loop
+        {
+            match reader.read(&mut buffer[filled..])
+            {
+                Ok(amount) =>
+                {
+                    buffer.truncate(filled + amount);
+
+                    return Ok(amount > 0);
+                },
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) =>
+                {
+                    buffer.truncate(filled);
+
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn decode(&mut self) -> io::Result<Option<(char, usize)>>
+    {
+        if self.start == self.buffer.len() && !self.refill()?
+        {
+            return Ok(None);
+        }
+
+        let first = self.buffer[self.start];
+
+        let width = match first
+        {
+            0x00..=0x7f =>
+            {
+                self.start += 1;
+
+                return Ok(Some((first as char, 1)));
+            },
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => return self.invalid(1)
+        };
+
+        // the character can be split between two reads
+        while self.buffer.len() - self.start < width
+        {
+            if !self.refill()?
+            {
+                break;
+            }
+        }
+
+        let available = width.min(self.buffer.len() - self.start);
+        let bytes = &self.buffer[self.start..self.start + available];
+
+        match str::from_utf8(bytes)
+        {
+            Ok(text) =>
+            {
+                let c = text.chars().next().expect("width is at least 1");
+
+                self.start += width;
+
+                Ok(Some((c, width)))
+            },
+            // same as String::from_utf8_lossy, a cut off character at the end is one replacement
+            Err(err) =>
+            {
+                let invalid = err.error_len().unwrap_or(available);
+
+                self.invalid(invalid)
+            }
+        }
+    }
+
+    fn invalid(&mut self, width: usize) -> io::Result<Option<(char, usize)>>
+    {
+        if !self.lossy
+        {
+            return Err(invalid_utf8());
+        }
+
+        self.start += width;
+
+        Ok(Some((char::REPLACEMENT_CHARACTER, width)))
+    }
+
+    fn track(&mut self, offset: usize, c: char, width: usize)
+    {
+        if c == '\n'
+        {
+            self.previous = self.line;
+            self.line = Line{start: offset + 1, number: self.line.number + 1, extra: 0};
+
+            self.wide.clear();
+        } else if width > 1
+        {
+            self.line.extra += width - 1;
+
+            if self.wide.len() == WIDE_HISTORY
+            {
+                self.wide.pop_front();
+            }
+
+            self.wide.push_back((offset, width - 1));
+        }
+    }
+}
+
+impl Iterator for ReaderChars<'_>
+{
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)>
+    {
+        if self.error.is_some()
+        {
+            return None;
+        }
+
+        match self.decode()
+        {
+            Ok(Some((c, width))) =>
+            {
+                let offset = self.offset;
+                self.offset += width;
+
+                self.track(offset, c, width);
+
+                Some((offset, c))
+            },
+            Ok(None) => None,
+            Err(err) =>
+            {
+                self.error = Some(err);
+
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use crate::{
+    writer,
+    parser::{Parser, ParseError, ObjectValue}
+};
+
+
+fn split_tokens(path: &str) -> Vec<String>
+{
+    if path.is_empty()
+    {
+        return Vec::new();
+    }
+
+    path.trim_start_matches('/').split('/').map(|token|
+    {
+        token.replace("~1", "/").replace("~0", "~")
+    }).collect()
+}
+
+// * matches any single token, ..name matches name at any depth below
+fn tokens_match(pattern: &[String], path: &[String]) -> bool
+{
+    let (token, rest) = match pattern.split_first()
+    {
+        Some(x) => x,
+        None => return path.is_empty()
+    };
+
+    if let Some(name) = token.strip_prefix("..")
+    {
+        return (0..path.len()).any(|id|
+        {
+            (name.is_empty() || name == "*" || path[id] == name) && tokens_match(rest, &path[id + 1..])
+        });
+    }
+
+    match path.split_first()
+    {
+        Some((first, path)) => (token == "*" || token == first) && tokens_match(rest, path),
+        None => false
+    }
+}
+
+// fnv1a, only meant to tell redacted values apart not to hide them from a determined reader
+fn fingerprint(text: &str) -> String
+{
+    let hash = text.bytes().fold(0xcbf29ce484222325_u64, |hash, byte|
+    {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("fnv:{hash:016x}")
+}
+
+// returns the text with every matched value swapped out, everything else stays byte for byte
+pub fn redact(text: &str, patterns: &[String], hash: bool) -> Result<String, ParseError>
+{
+    let (value, spans) = Parser::new(text.chars()).parse_spanned()?;
+
+    let patterns: Vec<Vec<String>> = patterns.iter().map(|pattern| split_tokens(pattern)).collect();
+
+    let mut redacted: Vec<Range<usize>> = Vec::new();
+    value.walk_spanned(&spans, |pointer, _: &ObjectValue, spans|
+    {
+        // children of an already redacted value are gone anyway
+        if redacted.last().map(|last| spans.span.start < last.end).unwrap_or(false)
+        {
+            return;
+        }
+
+        let pointer = split_tokens(pointer);
+        if patterns.iter().any(|pattern| tokens_match(pattern, &pointer))
+        {
+            redacted.push(spans.span.clone());
+        }
+    });
+
+    let mut out = String::with_capacity(text.len());
+
+    let mut last = 0;
+    for span in redacted
+    {
+        out.push_str(&text[last..span.start]);
+
+        let replacement = if hash { fingerprint(&text[span.clone()]) } else { "***".to_owned() };
+        writer::write_text(&mut out, &replacement);
+
+        last = span.end;
+    }
+
+    out.push_str(&text[last..]);
+
+    Ok(out)
+}
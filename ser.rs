@@ -0,0 +1,524 @@
+use std::sync::Arc;
+
+use serde::ser::{
+    self,
+    Serialize,
+    SerializeSeq,
+    SerializeTuple,
+    SerializeTupleStruct,
+    SerializeTupleVariant,
+    SerializeMap,
+    SerializeStruct,
+    SerializeStructVariant
+};
+
+use crate::{
+    de::Error,
+    writer::{self, WriteOptions},
+    parser::{ObjectValue, Object}
+};
+
+
+// the other way around from de, anything with #[derive(Serialize)] gets turned into a tree
+// which can be edited or queried like a parsed one or written out as text
+//
+//     let text = cringejsonparser::ser::to_string(&symbol)?;
+//
+// enums come out in the shape de reads back in, which is what serde_json writes too, nan and infinity
+// stay floats in the tree and only turn into null when theyre written
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> Result<ObjectValue, Error>
+{
+    value.serialize(Serializer)
+}
+
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String, Error>
+{
+    to_value(value).map(|value| writer::to_compact(&value))
+}
+
+pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T, options: &WriteOptions) -> Result<String, Error>
+{
+    to_value(value).map(|value| writer::to_pretty(&value, options))
+}
+
+impl ser::Error for Error
+{
+    fn custom<T: std::fmt::Display>(message: T) -> Self
+    {
+        Self::Message(message.to_string())
+    }
+}
+
+// everything other than a unit variant is an object with the variant name as its only key
+fn with_variant(variant: Option<&'static str>, value: ObjectValue) -> ObjectValue
+{
+    match variant
+    {
+        Some(variant) =>
+        {
+            let mut object = Object::default();
+            object.insert(variant, value);
+
+            object.into()
+        },
+        None => value
+    }
+}
+
+// builds an ObjectValue out of whatever gets serialized into it
+#[derive(Debug, Clone, Copy)]
+pub struct Serializer;
+
+impl ser::Serializer for Serializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = ObjectSerializer;
+    type SerializeStruct = ObjectSerializer;
+    type SerializeStructVariant = ObjectSerializer;
+
+    fn serialize_bool(self, x: bool) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_i8(self, x: i8) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_i16(self, x: i16) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_i32(self, x: i32) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_i64(self, x: i64) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_u8(self, x: u8) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_u16(self, x: u16) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_u32(self, x: u32) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    // anything past i64::MAX becomes a float like it would when parsed
+    fn serialize_u64(self, x: u64) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_f32(self, x: f32) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_f64(self, x: f64) -> Result<ObjectValue, Error>
+    {
+        Ok(x.into())
+    }
+
+    fn serialize_char(self, c: char) -> Result<ObjectValue, Error>
+    {
+        Ok(c.to_string().into())
+    }
+
+    fn serialize_str(self, text: &str) -> Result<ObjectValue, Error>
+    {
+        Ok(text.into())
+    }
+
+    // json has no bytes so theyre a list of numbers
+    fn serialize_bytes(self, bytes: &[u8]) -> Result<ObjectValue, Error>
+    {
+        Ok(bytes.iter().map(|&x| ObjectValue::from(x)).collect::<Vec<_>>().into())
+    }
+
+    fn serialize_none(self) -> Result<ObjectValue, Error>
+    {
+        Ok(ObjectValue::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<ObjectValue, Error>
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<ObjectValue, Error>
+    {
+        Ok(ObjectValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ObjectValue, Error>
+    {
+        Ok(ObjectValue::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _id: u32, variant: &'static str) -> Result<ObjectValue, Error>
+    {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<ObjectValue, Error>
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _id: u32,
+        variant: &'static str,
+        value: &T
+    ) -> Result<ObjectValue, Error>
+    {
+        Ok(with_variant(Some(variant), value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer, Error>
+    {
+        Ok(ListSerializer{values: Vec::with_capacity(len.unwrap_or(0)), variant: None})
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer, Error>
+    {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer, Error>
+    {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _id: u32,
+        variant: &'static str,
+        len: usize
+    ) -> Result<ListSerializer, Error>
+    {
+        Ok(ListSerializer{values: Vec::with_capacity(len), variant: Some(variant)})
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ObjectSerializer, Error>
+    {
+        Ok(ObjectSerializer{object: Object::default(), key: None, variant: None})
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ObjectSerializer, Error>
+    {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _id: u32,
+        variant: &'static str,
+        _len: usize
+    ) -> Result<ObjectSerializer, Error>
+    {
+        Ok(ObjectSerializer{object: Object::default(), key: None, variant: Some(variant)})
+    }
+}
+
+pub struct ListSerializer
+{
+    values: Vec<ObjectValue>,
+    variant: Option<&'static str>
+}
+
+impl ListSerializer
+{
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        self.values.push(to_value(value)?);
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ObjectValue, Error>
+    {
+        Ok(with_variant(self.variant, self.values.into()))
+    }
+}
+
+impl SerializeSeq for ListSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for ListSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for ListSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for ListSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+// a key thats given twice keeps the last value like insert does
+pub struct ObjectSerializer
+{
+    object: Object,
+    // waiting for its value
+    key: Option<Arc<str>>,
+    variant: Option<&'static str>
+}
+
+impl ObjectSerializer
+{
+    fn finish(self) -> Result<ObjectValue, Error>
+    {
+        Ok(with_variant(self.variant, self.object.into()))
+    }
+}
+
+impl SerializeMap for ObjectSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    // keys have to end up as strings, numbers and bools get written out like they would be as values
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error>
+    {
+        let key = match to_value(key)?
+        {
+            ObjectValue::Text(text) => text,
+            ObjectValue::Number(x) => x.to_string().into(),
+            ObjectValue::Float(x) => x.to_string().into(),
+            ObjectValue::Bool(x) => x.to_string().into(),
+            value => return Err(ser::Error::custom(format!("object keys have to be strings, not {}", value.type_name())))
+        };
+
+        self.key = Some(key);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error>
+    {
+        match self.key.take()
+        {
+            Some(key) =>
+            {
+                self.object.insert(key, to_value(value)?);
+
+                Ok(())
+            },
+            None => Err(ser::Error::custom("value serialized before its key"))
+        }
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for ObjectSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    {
+        self.object.insert(key, to_value(value)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for ObjectSerializer
+{
+    type Ok = ObjectValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    {
+        self.object.insert(key, to_value(value)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<ObjectValue, Error>
+    {
+        self.finish()
+    }
+}
+
+// so trees can be written by any serde format, duplicate keys get passed along as they are
+impl Serialize for ObjectValue
+{
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        match self
+        {
+            Self::Null => serializer.serialize_unit(),
+            Self::Text(text) => serializer.serialize_str(text),
+            Self::Number(x) => serializer.serialize_i64(*x),
+            Self::Float(x) => serializer.serialize_f64(*x),
+            Self::Bool(x) => serializer.serialize_bool(*x),
+            Self::List(values) =>
+            {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+
+                values.iter().try_for_each(|value| seq.serialize_element(value))?;
+
+                seq.end()
+            },
+            Self::Object(object) =>
+            {
+                let mut map = serializer.serialize_map(Some(object.fields().len()))?;
+
+                object.fields().iter().try_for_each(|field| map.serialize_entry(field.key(), field.value()))?;
+
+                map.end()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::BTreeMap;
+
+    use serde::{Serialize, Deserialize};
+
+    use crate::de;
+
+    use super::*;
+
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind
+    {
+        Function,
+        Data(u32),
+        Range{start: u64, end: u64}
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Symbol
+    {
+        name: String,
+        size: Option<u32>,
+        kinds: Vec<Kind>
+    }
+
+    #[test]
+    fn derived_struct()
+    {
+        let symbol = Symbol{
+            name: "main".to_owned(),
+            size: None,
+            kinds: vec![Kind::Function, Kind::Data(4), Kind::Range{start: 1, end: 2}]
+        };
+
+        assert_eq!(
+            to_string(&symbol).unwrap(),
+            r#"{"name":"main","size":null,"kinds":["Function",{"Data":4},{"Range":{"start":1,"end":2}}]}"#
+        );
+
+        assert_eq!(de::from_value::<Symbol>(&to_value(&symbol).unwrap()).unwrap(), symbol);
+    }
+
+    #[test]
+    fn keys_become_strings()
+    {
+        let numbers: BTreeMap<u32, bool> = [(1, true), (2, false)].into_iter().collect();
+
+        assert_eq!(to_string(&numbers).unwrap(), r#"{"1":true,"2":false}"#);
+
+        let lists: BTreeMap<Vec<u8>, u8> = [(vec![1], 1)].into_iter().collect();
+
+        assert!(matches!(to_value(&lists), Err(Error::Message(_))));
+    }
+
+    #[test]
+    fn trees_round_trip()
+    {
+        let text = r#"{"a":[1,2.5,"x",null,true],"b":{}}"#;
+        let value = crate::Parser::new(text.chars()).parse().unwrap();
+
+        assert_eq!(to_value(&value).unwrap(), value);
+        assert_eq!(to_string(&value).unwrap(), text);
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{
+    __m128i,
+    _mm_loadu_si128,
+    _mm_set1_epi8,
+    _mm_cmpeq_epi8,
+    _mm_min_epu8,
+    _mm_or_si128,
+    _mm_movemask_epi8
+};
+
+
+// every function here counts how many bytes at the start can be skipped before the
+// first one that matters, 16 at a time where sse2 is there which is every x86_64 cpu
+// so the intrinsics are always fine to call
+#[cfg(target_arch = "x86_64")]
+const LANES: usize = 16;
+
+// space, tab, newline and carriage return, anything else stops it
+pub fn whitespace_len(bytes: &[u8]) -> usize
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        leading(bytes, |chunk| unsafe
+        {
+            let whitespace = any_of(chunk, b" \t\n\r");
+
+            !_mm_movemask_epi8(whitespace) as u32 & 0xffff
+        }, |byte| !is_whitespace(byte))
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        bytes.iter().position(|byte| !is_whitespace(*byte)).unwrap_or(bytes.len())
+    }
+}
+
+// everything inside a string up to the closing quote, an escape or a control character,
+// it always stops on an ascii byte so the skipped part is whole characters
+pub fn plain_text_len(bytes: &[u8], quote: u8) -> usize
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        leading(bytes, |chunk| unsafe
+        {
+            let special = any_of(chunk, &[quote, b'\\']);
+            let control = _mm_cmpeq_epi8(_mm_min_epu8(chunk, _mm_set1_epi8(0x1f)), chunk);
+
+            _mm_movemask_epi8(_mm_or_si128(special, control)) as u32
+        }, |byte| !is_plain_text(byte, quote))
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        bytes.iter().position(|byte| !is_plain_text(*byte, quote)).unwrap_or(bytes.len())
+    }
+}
+
+// anything that can change how deep the scan is, opens a string or starts a comment
+pub fn unstructural_len(bytes: &[u8]) -> usize
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        leading(bytes, |chunk| unsafe{ _mm_movemask_epi8(any_of(chunk, STRUCTURAL)) as u32 }, |byte|
+        {
+            STRUCTURAL.contains(&byte)
+        })
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        bytes.iter().position(|byte| STRUCTURAL.contains(byte)).unwrap_or(bytes.len())
+    }
+}
+
+const STRUCTURAL: &[u8] = b"[]{}\"'/";
+
+fn is_whitespace(byte: u8) -> bool
+{
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+fn is_plain_text(byte: u8, quote: u8) -> bool
+{
+    byte != quote && byte != b'\\' && byte >= 0x20
+}
+
+// stops gives a bit for every byte in the chunk that ends the run, the tail
+// thats shorter than a chunk goes one byte at a time
+#[cfg(target_arch = "x86_64")]
+fn leading(bytes: &[u8], stops: impl Fn(__m128i) -> u32, stop: impl Fn(u8) -> bool) -> usize
+{
+    // most runs between tokens are empty, no point loading a whole chunk for those
+    if bytes.first().is_none_or(|byte| stop(*byte))
+    {
+        return 0;
+    }
+
+    let mut chunks = bytes.chunks_exact(LANES);
+
+    for (id, chunk) in chunks.by_ref().enumerate()
+    {
+        // chunks_exact makes sure all 16 bytes are there, loadu doesnt care about alignment
+        let chunk = unsafe{ _mm_loadu_si128(chunk.as_ptr().cast()) };
+
+        let mask = stops(chunk);
+
+        if mask != 0
+        {
+            return id * LANES + mask.trailing_zeros() as usize;
+        }
+    }
+
+    let done = bytes.len() - chunks.remainder().len();
+
+    chunks.remainder().iter().position(|byte| stop(*byte)).map_or(bytes.len(), |id| done + id)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn any_of(chunk: __m128i, bytes: &[u8]) -> __m128i
+{
+    bytes.iter().fold(unsafe{ _mm_set1_epi8(0) }, |found, byte| unsafe
+    {
+        _mm_or_si128(found, _mm_cmpeq_epi8(chunk, _mm_set1_epi8(*byte as i8)))
+    })
+}
@@ -0,0 +1,663 @@
+use std::{
+    fmt,
+    io::{self, Read, BufRead, Seek, SeekFrom, ErrorKind}
+};
+
+use crate::{
+    lexer::Position,
+    parser::{Parser, ParserOptions, ParseError, ObjectValue, TextIter}
+};
+
+
+fn invalid_data(message: &str) -> io::Error
+{
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+// strips whitespace between tokens so a raw element fits on a single line
+pub fn minify(raw: &str) -> String
+{
+    let mut output = String::with_capacity(raw.len());
+
+    let mut in_text = false;
+    let mut escaped = false;
+
+    for c in raw.chars()
+    {
+        if in_text
+        {
+            match c
+            {
+                '\\' => escaped = !escaped,
+                '"' if !escaped => in_text = false,
+                _ => escaped = false
+            }
+        } else if c == '"'
+        {
+            in_text = true;
+        } else if matches!(c, ' ' | '\t' | '\n' | '\r')
+        {
+            continue;
+        }
+
+        output.push(c);
+    }
+
+    output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State
+{
+    Start,
+    Elements,
+    Finished
+}
+
+impl State
+{
+    fn name(&self) -> &'static str
+    {
+        match self
+        {
+            Self::Start => "start",
+            Self::Elements => "elements",
+            Self::Finished => "finished"
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self>
+    {
+        match name
+        {
+            "start" => Some(Self::Start),
+            "elements" => Some(Self::Elements),
+            "finished" => Some(Self::Finished),
+            _ => None
+        }
+    }
+}
+
+// where a stream stopped, elements only ever get split at depth 0 so past the offset
+// the only stack left to remember is whether the top level array is still open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint
+{
+    state: State,
+    offset: u64,
+    elements: u64
+}
+
+impl Checkpoint
+{
+    // bytes consumed from the start of the input
+    pub fn offset(&self) -> u64
+    {
+        self.offset
+    }
+
+    // elements yielded before this point
+    pub fn elements(&self) -> u64
+    {
+        self.elements
+    }
+
+    pub fn is_finished(&self) -> bool
+    {
+        self.state == State::Finished
+    }
+
+    // the inverse of to_string, like "elements 1534 20"
+    pub fn parse(text: &str) -> Option<Self>
+    {
+        let mut parts = text.split_whitespace();
+
+        let state = State::from_name(parts.next()?)?;
+        let offset = parts.next()?.parse().ok()?;
+        let elements = parts.next()?.parse().ok()?;
+
+        parts.next().is_none().then_some(Self{state, offset, elements})
+    }
+}
+
+impl fmt::Display for Checkpoint
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} {} {}", self.state.name(), self.offset, self.elements)
+    }
+}
+
+// yields the raw source of each element of a top level array without ever holding
+// more than one element in memory, elements arent parsed just split at the right commas
+pub struct ArrayStream<R>
+{
+    reader: R,
+    state: State,
+    offset: u64,
+    // where the last element started
+    element_offset: u64,
+    elements: u64
+}
+
+impl<R: BufRead> ArrayStream<R>
+{
+    pub fn new(reader: R) -> Self
+    {
+        Self{reader, state: State::Start, offset: 0, element_offset: 0, elements: 0}
+    }
+
+    // bytes consumed from the reader so far
+    pub fn offset(&self) -> u64
+    {
+        self.offset
+    }
+
+    // so errors found in an element can point into the whole file
+    pub fn element_offset(&self) -> u64
+    {
+        self.element_offset
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint
+    {
+        Checkpoint{state: self.state, offset: self.offset, elements: self.elements}
+    }
+
+    fn peek_byte(&mut self) -> io::Result<Option<u8>>
+    {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn consume(&mut self, amount: usize)
+    {
+        self.reader.consume(amount);
+        self.offset += amount as u64;
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<Option<u8>>
+    {
+        loop
+        {
+            match self.peek_byte()?
+            {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.consume(1),
+                x => return Ok(x)
+            }
+        }
+    }
+
+    fn start(&mut self) -> io::Result<()>
+    {
+        if self.skip_whitespace()? != Some(b'[')
+        {
+            return Err(invalid_data("top level value isnt an array"));
+        }
+
+        self.consume(1);
+
+        if self.skip_whitespace()? == Some(b']')
+        {
+            self.consume(1);
+            self.finish()?;
+        } else
+        {
+            self.state = State::Elements;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()>
+    {
+        self.state = State::Finished;
+
+        if self.skip_whitespace()?.is_some()
+        {
+            return Err(invalid_data("unexpected content after the top level array"));
+        }
+
+        Ok(())
+    }
+
+    fn element(&mut self) -> io::Result<String>
+    {
+        self.skip_whitespace()?;
+        self.element_offset = self.offset;
+
+        let mut element = Vec::new();
+
+        let mut depth: usize = 0;
+        let mut in_text = false;
+        let mut escaped = false;
+
+        loop
+        {
+            let buffer = self.reader.fill_buf()?;
+
+            if buffer.is_empty()
+            {
+                return Err(invalid_data("top level array isnt closed"));
+            }
+
+            let mut end = None;
+            for (id, byte) in buffer.iter().copied().enumerate()
+            {
+                if in_text
+                {
+                    match byte
+                    {
+                        b'\\' => escaped = !escaped,
+                        b'"' if !escaped => in_text = false,
+                        _ => escaped = false
+                    }
+
+                    continue;
+                }
+
+                match byte
+                {
+                    b'"' => in_text = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' if depth > 0 => depth -= 1,
+                    b',' | b']' if depth == 0 =>
+                    {
+                        end = Some((id, byte));
+
+                        break;
+                    },
+                    _ => ()
+                }
+            }
+
+            match end
+            {
+                Some((id, byte)) =>
+                {
+                    element.extend_from_slice(&buffer[..id]);
+                    self.consume(id + 1);
+
+                    if byte == b']'
+                    {
+                        self.finish()?;
+                    }
+
+                    break;
+                },
+                None =>
+                {
+                    let length = buffer.len();
+
+                    element.extend_from_slice(buffer);
+                    self.consume(length);
+                }
+            }
+        }
+
+        let element = String::from_utf8(element).map_err(|_| invalid_data("element isnt valid utf8"))?;
+        let element = element.trim_matches([' ', '\t', '\n', '\r']);
+
+        if element.is_empty()
+        {
+            return Err(invalid_data("empty array element"));
+        }
+
+        Ok(element.to_owned())
+    }
+}
+
+impl<R: BufRead> Iterator for ArrayStream<R>
+{
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.state == State::Start
+        {
+            if let Err(err) = self.start()
+            {
+                self.state = State::Finished;
+
+                return Some(Err(err));
+            }
+        }
+
+        if self.state == State::Finished
+        {
+            return None;
+        }
+
+        let element = self.element();
+
+        match element
+        {
+            Ok(_) => self.elements += 1,
+            Err(_) => self.state = State::Finished
+        }
+
+        Some(element)
+    }
+}
+
+impl<R: BufRead + Seek> ArrayStream<R>
+{
+    // picks up right after the last element the checkpoint saw, the reader has to be the same input
+    pub fn resume(mut reader: R, checkpoint: &Checkpoint) -> io::Result<Self>
+    {
+        reader.seek(SeekFrom::Start(checkpoint.offset))?;
+
+        Ok(Self{
+            reader,
+            state: checkpoint.state,
+            offset: checkpoint.offset,
+            element_offset: checkpoint.offset,
+            elements: checkpoint.elements
+        })
+    }
+}
+
+// one document per line, blank lines are skipped
+pub struct LineStream<R>
+{
+    reader: R,
+    state: State,
+    offset: u64,
+    elements: u64
+}
+
+impl<R: BufRead> LineStream<R>
+{
+    pub fn new(reader: R) -> Self
+    {
+        Self{reader, state: State::Elements, offset: 0, elements: 0}
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint
+    {
+        Checkpoint{state: self.state, offset: self.offset, elements: self.elements}
+    }
+}
+
+impl<R: BufRead + Seek> LineStream<R>
+{
+    pub fn resume(mut reader: R, checkpoint: &Checkpoint) -> io::Result<Self>
+    {
+        reader.seek(SeekFrom::Start(checkpoint.offset))?;
+
+        let state = if checkpoint.is_finished() { State::Finished } else { State::Elements };
+
+        Ok(Self{reader, state, offset: checkpoint.offset, elements: checkpoint.elements})
+    }
+}
+
+impl<R: BufRead> Iterator for LineStream<R>
+{
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while self.state != State::Finished
+        {
+            let mut line = String::new();
+
+            match self.reader.read_line(&mut line)
+            {
+                Ok(0) => self.state = State::Finished,
+                Ok(length) =>
+                {
+                    self.offset += length as u64;
+
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    if !line.trim().is_empty()
+                    {
+                        self.elements += 1;
+
+                        return Some(Ok(line.to_owned()));
+                    }
+                },
+                Err(err) =>
+                {
+                    self.state = State::Finished;
+
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// one parsed document per line, blank lines are skipped and a broken line is only an error
+// for that line, error positions and offsets are into the whole input not the line
+pub struct JsonLines<R>
+{
+    reader: R,
+    options: ParserOptions,
+    offset: usize,
+    line: usize,
+    finished: bool
+}
+
+impl<R: BufRead> JsonLines<R>
+{
+    pub fn new(reader: R) -> Self
+    {
+        Self{reader, options: ParserOptions::default(), offset: 0, line: 0, finished: false}
+    }
+
+    pub fn options(mut self, options: ParserOptions) -> Self
+    {
+        self.options = options;
+
+        self
+    }
+
+    // line number of the last line that was read
+    pub fn line(&self) -> usize
+    {
+        self.line
+    }
+
+    // a line past the size limit only gets read up to where the parser would stop anyway,
+    // the rest of it is skipped so the next line starts in the right place
+    fn read_line(&mut self, line: &mut Vec<u8>) -> io::Result<usize>
+    {
+        let limit = (self.options.max_document_size as u64).saturating_add(1);
+
+        let length = (&mut self.reader).take(limit).read_until(b'\n', line)?;
+
+        if line.len() > self.options.max_document_size && line.last() != Some(&b'\n')
+        {
+            return Ok(length + self.reader.skip_until(b'\n')?);
+        }
+
+        Ok(length)
+    }
+}
+
+impl<R: BufRead> Iterator for JsonLines<R>
+{
+    type Item = Result<ObjectValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while !self.finished
+        {
+            // bytes so invalid utf8 only breaks its own line
+            let mut line = Vec::new();
+
+            let length = match self.read_line(&mut line)
+            {
+                Ok(x) => x,
+                Err(err) =>
+                {
+                    self.finished = true;
+
+                    let position = Position{line: self.line + 1, column: 1};
+
+                    return Some(Err(ParseError::io(&err, self.offset, position)));
+                }
+            };
+
+            if length == 0
+            {
+                self.finished = true;
+
+                break;
+            }
+
+            let start = self.offset;
+
+            self.offset += length;
+            self.line += 1;
+
+            let line = line.strip_suffix(b"\n").unwrap_or(&line);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if line.iter().all(u8::is_ascii_whitespace)
+            {
+                continue;
+            }
+
+            let value = Parser::from_bytes(line).options(self.options.clone()).parse();
+
+            return Some(value.map_err(|err|
+            {
+                let position = Position{line: self.line, column: err.position.column};
+
+                ParseError{offset: start + err.offset, position, ..err}
+            }));
+        }
+
+        None
+    }
+}
+
+// documents one after another in the same text, only whitespace is allowed between them
+// and the first broken one ends the stream since theres no telling where the next starts
+pub struct Documents<'a>
+{
+    text: TextIter<'a>,
+    sorted_keys: bool,
+    finished: bool
+}
+
+impl<'a> Documents<'a>
+{
+    pub(crate) fn new(text: TextIter<'a>, sorted_keys: bool) -> Self
+    {
+        Self{text, sorted_keys, finished: false}
+    }
+}
+
+impl Iterator for Documents<'_>
+{
+    type Item = Result<(usize, ObjectValue), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.finished
+        {
+            return None;
+        }
+
+        if self.text.skip_whitespace().peek().is_none()
+        {
+            self.finished = true;
+
+            // running out of text because the reader failed isnt the end of the documents
+            return self.text.cut_short().map(Err);
+        }
+
+        let offset = self.text.offset();
+
+        match ObjectValue::parse(&mut self.text)
+        {
+            Ok(mut value) =>
+            {
+                if self.sorted_keys
+                {
+                    value.sort_keys();
+                }
+
+                Some(Ok((offset, value)))
+            },
+            Err(err) =>
+            {
+                self.finished = true;
+
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::ParseErrorKind;
+
+    use super::*;
+
+
+    // gives out its bytes and then fails instead of ending
+    struct Failing(&'static [u8]);
+
+    impl Read for Failing
+    {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize>
+        {
+            if self.0.is_empty()
+            {
+                return Err(io::Error::other("disconnected"));
+            }
+
+            self.0.read(buffer)
+        }
+    }
+
+    fn documents(parser: Parser) -> Vec<Result<(usize, ObjectValue), ParseErrorKind>>
+    {
+        parser.parse_stream().map(|document| document.map_err(|err| err.kind)).collect()
+    }
+
+    #[test]
+    fn back_to_back()
+    {
+        let found = documents(Parser::new("{\"a\": 1}[2]\n3".chars()));
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[1], Ok((8, ObjectValue::from(vec![ObjectValue::from(2)]))));
+        assert_eq!(found[2], Ok((12, ObjectValue::from(3))));
+    }
+
+    #[test]
+    fn reader_error_after_a_document()
+    {
+        let found = documents(Parser::from_reader(Failing(b"[1] [2] ")));
+
+        assert_eq!(found.len(), 3);
+        assert!(found[..2].iter().all(Result::is_ok));
+        assert_eq!(found[2], Err(ParseErrorKind::Io));
+    }
+
+    #[test]
+    fn reader_error_inside_a_document()
+    {
+        let found = documents(Parser::from_reader(Failing(b"[1] [2")));
+
+        assert_eq!(found[1..], [Err(ParseErrorKind::Io)]);
+    }
+
+    #[test]
+    fn over_the_size_limit()
+    {
+        let options = ParserOptions::default().max_document_size(8);
+
+        let found = documents(Parser::from_reader(&b"[1] [2] [3] [4]"[..]).options(options));
+
+        assert_eq!(found.last(), Some(&Err(ParseErrorKind::Limit)));
+        assert!(found[..found.len() - 1].iter().all(Result::is_ok));
+    }
+}
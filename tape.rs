@@ -0,0 +1,545 @@
+use std::sync::Arc;
+
+use crate::{
+    events::Handler,
+    parser::{self, ObjectValue, Object, ObjectField}
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Node
+{
+    Null,
+    Bool(bool),
+    Number(i64),
+    Float(f64),
+    // start and length in the string buffer
+    Text(usize, usize),
+    Key(usize, usize),
+    // end is the id of the matching End so skipping over a container is a single jump
+    ListStart{end: usize, len: usize},
+    ObjectStart{end: usize, len: usize},
+    End
+}
+
+// the whole document as one flat list of nodes in document order with every string in one
+// buffer, walking it touches memory front to back instead of chasing a pointer per value
+#[derive(Debug, Clone)]
+pub struct Tape
+{
+    nodes: Vec<Node>,
+    strings: String
+}
+
+impl Tape
+{
+    pub fn root(&self) -> Cursor<'_>
+    {
+        Cursor{tape: self, id: 0}
+    }
+
+    // every value, key and container end counts as one
+    pub fn node_count(&self) -> usize
+    {
+        self.nodes.len()
+    }
+
+    fn text(&self, start: usize, len: usize) -> &str
+    {
+        &self.strings[start..start + len]
+    }
+
+    // the id right after the value at id
+    fn skip(&self, id: usize) -> usize
+    {
+        match self.nodes[id]
+        {
+            Node::ListStart{end, ..} | Node::ObjectStart{end, ..} => end + 1,
+            _ => id + 1
+        }
+    }
+}
+
+// a container thats still open and how many values it has so far
+struct Open
+{
+    start: usize,
+    len: usize,
+    // an object got a key and is waiting for its value
+    key: bool
+}
+
+// builds a tape out of events, which is also what Parser::parse_tape does
+#[derive(Default)]
+pub struct TapeBuilder
+{
+    nodes: Vec<Node>,
+    strings: String,
+    stack: Vec<Open>,
+    // the events didnt make a single value, like a key outside an object or an end
+    // for a container that isnt open, the cursors rely on the tape being well formed
+    broken: bool
+}
+
+impl TapeBuilder
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    // none until a whole value was seen, or if the events couldnt have come from one
+    pub fn finish(self) -> Option<Tape>
+    {
+        if self.broken || self.nodes.is_empty() || !self.stack.is_empty()
+        {
+            return None;
+        }
+
+        Some(Tape{nodes: self.nodes, strings: self.strings})
+    }
+
+    fn push(&mut self, node: Node)
+    {
+        match self.stack.last_mut()
+        {
+            Some(open) => match self.nodes[open.start]
+            {
+                Node::ListStart{..} => open.len += 1,
+                // objects count their keys instead
+                _ if open.key => open.key = false,
+                _ => self.broken = true
+            },
+            // theres already a whole value so this would be a second one
+            None if !self.nodes.is_empty() => self.broken = true,
+            None => ()
+        }
+
+        self.nodes.push(node);
+    }
+
+    fn push_text(&mut self, text: &str) -> (usize, usize)
+    {
+        let start = self.strings.len();
+        self.strings.push_str(text);
+
+        (start, text.len())
+    }
+
+    fn start(&mut self, node: Node)
+    {
+        self.push(node);
+
+        self.stack.push(Open{start: self.nodes.len() - 1, len: 0, key: false});
+    }
+
+    fn end(&mut self, list: bool)
+    {
+        let open = match self.stack.pop()
+        {
+            Some(x) => x,
+            None =>
+            {
+                self.broken = true;
+
+                return;
+            }
+        };
+
+        let end = self.nodes.len();
+        self.nodes.push(Node::End);
+
+        let len = open.len;
+
+        self.nodes[open.start] = match self.nodes[open.start]
+        {
+            Node::ListStart{..} if list => Node::ListStart{end, len},
+            Node::ObjectStart{..} if !list && !open.key => Node::ObjectStart{end, len},
+            node =>
+            {
+                self.broken = true;
+
+                node
+            }
+        };
+    }
+}
+
+impl Handler for TapeBuilder
+{
+    fn on_object_start(&mut self)
+    {
+        self.start(Node::ObjectStart{end: 0, len: 0});
+    }
+
+    fn on_object_end(&mut self)
+    {
+        self.end(false);
+    }
+
+    fn on_list_start(&mut self)
+    {
+        self.start(Node::ListStart{end: 0, len: 0});
+    }
+
+    fn on_list_end(&mut self)
+    {
+        self.end(true);
+    }
+
+    fn on_key(&mut self, key: &str)
+    {
+        let (start, len) = self.push_text(key);
+        self.nodes.push(Node::Key(start, len));
+
+        match self.stack.last_mut()
+        {
+            Some(open) if matches!(self.nodes[open.start], Node::ObjectStart{..}) && !open.key =>
+            {
+                open.len += 1;
+                open.key = true;
+            },
+            _ => self.broken = true
+        }
+    }
+
+    fn on_string(&mut self, value: &str)
+    {
+        let (start, len) = self.push_text(value);
+        self.push(Node::Text(start, len));
+    }
+
+    fn on_number(&mut self, value: i64)
+    {
+        self.push(Node::Number(value));
+    }
+
+    fn on_float(&mut self, value: f64)
+    {
+        self.push(Node::Float(value));
+    }
+
+    fn on_bool(&mut self, value: bool)
+    {
+        self.push(Node::Bool(value));
+    }
+
+    fn on_null(&mut self)
+    {
+        self.push(Node::Null);
+    }
+}
+
+// points at one value on the tape, copying it around is free
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a>
+{
+    tape: &'a Tape,
+    id: usize
+}
+
+impl<'a> Cursor<'a>
+{
+    fn node(&self) -> Node
+    {
+        self.tape.nodes[self.id]
+    }
+
+    pub fn get_text(&self) -> Option<&'a str>
+    {
+        match self.node()
+        {
+            Node::Text(start, len) => Some(self.tape.text(start, len)),
+            _ => None
+        }
+    }
+
+    pub fn get_number(&self) -> Option<i64>
+    {
+        match self.node()
+        {
+            Node::Number(x) => Some(x),
+            _ => None
+        }
+    }
+
+    // integers convert too, same as ObjectValue::get_float
+    pub fn get_float(&self) -> Option<f64>
+    {
+        match self.node()
+        {
+            Node::Float(x) => Some(x),
+            Node::Number(x) => Some(x as f64),
+            _ => None
+        }
+    }
+
+    pub fn get_bool(&self) -> Option<bool>
+    {
+        match self.node()
+        {
+            Node::Bool(x) => Some(x),
+            _ => None
+        }
+    }
+
+    pub fn is_null(&self) -> bool
+    {
+        self.node() == Node::Null
+    }
+
+    pub fn type_name(&self) -> &'static str
+    {
+        match self.node()
+        {
+            Node::Null => "null",
+            Node::Text(..) => "string",
+            Node::Number(_) | Node::Float(_) => "number",
+            Node::Bool(_) => "bool",
+            Node::ListStart{..} => "array",
+            Node::ObjectStart{..} => "object",
+            Node::Key(..) | Node::End => unreachable!("cursors only point at values")
+        }
+    }
+
+    // elements of a list or fields of an object, zero for anything else
+    pub fn len(&self) -> usize
+    {
+        match self.node()
+        {
+            Node::ListStart{len, ..} | Node::ObjectStart{len, ..} => len,
+            _ => 0
+        }
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    // empty for anything thats not a list
+    pub fn elements(&self) -> Elements<'a>
+    {
+        let (id, end) = match self.node()
+        {
+            Node::ListStart{end, ..} => (self.id + 1, end),
+            _ => (0, 0)
+        };
+
+        Elements{tape: self.tape, id, end}
+    }
+
+    // empty for anything thats not an object
+    pub fn fields(&self) -> Fields<'a>
+    {
+        let (id, end) = match self.node()
+        {
+            Node::ObjectStart{end, ..} => (self.id + 1, end),
+            _ => (0, 0)
+        };
+
+        Fields{tape: self.tape, id, end}
+    }
+
+    // skips over the elements before it without looking inside them
+    pub fn at(&self, index: usize) -> Option<Self>
+    {
+        self.elements().nth(index)
+    }
+
+    // objects arent indexed so this goes through the keys in order, the first match wins
+    pub fn get(&self, key: &str) -> Option<Self>
+    {
+        self.fields().find(|(field, _)| *field == key).map(|(_, value)| value)
+    }
+
+    pub fn pointer(&self, pointer: &str) -> Option<Self>
+    {
+        if pointer.is_empty()
+        {
+            return Some(*self);
+        }
+
+        parser::pointer_tokens(pointer)?.try_fold(*self, |cursor, token|
+        {
+            match cursor.node()
+            {
+                Node::ListStart{..} => token.parse::<usize>().ok().and_then(|id| cursor.at(id)),
+                Node::ObjectStart{..} => cursor.get(&token),
+                _ => None
+            }
+        })
+    }
+
+    // copies everything under the cursor into a regular tree
+    pub fn to_value(&self) -> ObjectValue
+    {
+        match self.node()
+        {
+            Node::Null => ObjectValue::Null,
+            Node::Bool(x) => ObjectValue::Bool(x),
+            Node::Number(x) => ObjectValue::Number(x),
+            Node::Float(x) => ObjectValue::Float(x),
+            Node::Text(start, len) => ObjectValue::Text(self.tape.text(start, len).into()),
+            Node::ListStart{..} =>
+            {
+                ObjectValue::List(Arc::new(self.elements().map(|element| element.to_value()).collect()))
+            },
+            Node::ObjectStart{..} =>
+            {
+                let fields = self.fields().map(|(key, value)| ObjectField::new(key, value.to_value())).collect();
+
+                ObjectValue::Object(Arc::new(Object::from_fields(fields)))
+            },
+            Node::Key(..) | Node::End => unreachable!("cursors only point at values")
+        }
+    }
+}
+
+pub struct Elements<'a>
+{
+    tape: &'a Tape,
+    id: usize,
+    end: usize
+}
+
+impl<'a> Iterator for Elements<'a>
+{
+    type Item = Cursor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.id >= self.end
+        {
+            return None;
+        }
+
+        let cursor = Cursor{tape: self.tape, id: self.id};
+        self.id = self.tape.skip(self.id);
+
+        Some(cursor)
+    }
+}
+
+pub struct Fields<'a>
+{
+    tape: &'a Tape,
+    id: usize,
+    end: usize
+}
+
+impl<'a> Iterator for Fields<'a>
+{
+    type Item = (&'a str, Cursor<'a>);
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.id >= self.end
+        {
+            return None;
+        }
+
+        let key = match self.tape.nodes[self.id]
+        {
+            Node::Key(start, len) => self.tape.text(start, len),
+            _ => unreachable!("object fields always start with a key")
+        };
+
+        let value = Cursor{tape: self.tape, id: self.id + 1};
+        self.id = self.tape.skip(value.id);
+
+        Some((key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use crate::Parser;
+
+
+    fn build(events: impl FnOnce(&mut TapeBuilder)) -> Option<ObjectValue>
+    {
+        let mut builder = TapeBuilder::new();
+        events(&mut builder);
+
+        builder.finish().map(|tape| tape.root().to_value())
+    }
+
+    #[test]
+    fn same_as_parse()
+    {
+        let text = r#"{"a": [1, 2.5, "x", null, true, {}], "b": {"c": []}}"#;
+
+        let tape = Parser::new(text.chars()).parse_tape().unwrap();
+
+        assert_eq!(tape.root().to_value(), Parser::new(text.chars()).parse().unwrap());
+        assert_eq!(tape.root().pointer("/a/2").and_then(|cursor| cursor.get_text()), Some("x"));
+        assert_eq!(tape.root().get("b").map(|cursor| cursor.len()), Some(1));
+    }
+
+    #[test]
+    fn events_from_a_handler()
+    {
+        let value = build(|t|
+        {
+            t.on_object_start();
+            t.on_key("a");
+            t.on_list_start();
+            t.on_number(1);
+            t.on_list_end();
+            t.on_object_end();
+        });
+
+        assert_eq!(value, Some(Parser::new(r#"{"a": [1]}"#.chars()).parse().unwrap()));
+    }
+
+    #[test]
+    fn misuse_isnt_a_tape()
+    {
+        assert_eq!(build(|_| ()), None);
+        assert_eq!(build(|t| t.on_key("a")), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_object_start();
+            t.on_string("x");
+            t.on_object_end();
+        }), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_object_start();
+            t.on_key("a");
+            t.on_object_end();
+        }), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_list_start();
+            t.on_key("a");
+            t.on_null();
+            t.on_list_end();
+        }), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_list_start();
+            t.on_object_end();
+        }), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_null();
+            t.on_null();
+        }), None);
+
+        assert_eq!(build(|t|
+        {
+            t.on_null();
+            t.on_list_end();
+        }), None);
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    fs,
+    path::Path
+};
+
+use cringejsonparser::{Parser, ParserOptions};
+
+
+// a checked in part of jsontestsuite's test_parsing, named the same way so the conformance
+// subcommand runs on it too, the whole suite is too big to keep around
+fn cases() -> Vec<(String, Vec<u8>)>
+{
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/jsontestsuite");
+
+    let mut cases: Vec<(String, Vec<u8>)> = fs::read_dir(&directory).unwrap().map(|entry|
+    {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+        (name, fs::read(&path).unwrap())
+    }).collect();
+
+    cases.sort();
+
+    assert!(!cases.is_empty(), "no cases in {}", directory.display());
+
+    cases
+}
+
+fn accepts(bytes: &[u8], options: &ParserOptions) -> bool
+{
+    Parser::from_bytes(bytes).options(options.clone()).parse().is_ok()
+}
+
+#[test]
+fn strict_matches_jsontestsuite()
+{
+    let options = ParserOptions::strict();
+
+    let failed: Vec<String> = cases().into_iter().filter_map(|(name, bytes)|
+    {
+        match (name.split('_').next(), accepts(&bytes, &options))
+        {
+            (Some("y"), false) => Some(format!("{name} should parse but didnt")),
+            (Some("n"), true) => Some(format!("{name} should be rejected but parsed")),
+            _ => None
+        }
+    }).collect();
+
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
+
+// being lenient only ever adds to what gets accepted
+#[test]
+fn default_accepts_every_valid_case()
+{
+    let options = ParserOptions::default();
+
+    let failed: Vec<String> = cases().into_iter()
+        .filter(|(name, bytes)| name.starts_with("y_") && !accepts(bytes, &options))
+        .map(|(name, _)| name)
+        .collect();
+
+    assert!(failed.is_empty(), "should parse but didnt: {}", failed.join(", "));
+}
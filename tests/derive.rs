@@ -0,0 +1,60 @@
+use cringejsonparser::{Parser, ObjectValue, ToJson, FromJson, FromJsonError};
+
+
+#[derive(Debug, Clone, PartialEq, ToJson, FromJson)]
+struct Symbol
+{
+    name: String,
+    #[json(rename = "vaddr")]
+    address: u64,
+    #[json(default)]
+    tags: Vec<String>,
+    #[json(skip)]
+    cache: Option<String>
+}
+
+fn parse(text: &str) -> ObjectValue
+{
+    Parser::new(text.chars()).parse().unwrap()
+}
+
+#[test]
+fn rename_is_the_key_both_ways()
+{
+    let symbol = Symbol{name: "main".to_owned(), address: 4198400, tags: vec!["entry".to_owned()], cache: None};
+
+    let value = symbol.to_json();
+
+    assert_eq!(value.pointer("/vaddr"), Some(&ObjectValue::from(4198400_u64)));
+    assert_eq!(value.pointer("/address"), None);
+
+    assert_eq!(Symbol::from_json(&value), Ok(symbol));
+}
+
+#[test]
+fn default_fills_in_a_missing_key()
+{
+    let symbol = Symbol::from_json(&parse(r#"{"name": "main", "vaddr": 1}"#)).unwrap();
+
+    assert!(symbol.tags.is_empty());
+}
+
+#[test]
+fn skip_is_never_written_or_read()
+{
+    let symbol = Symbol{name: "main".to_owned(), address: 1, tags: Vec::new(), cache: Some("x".to_owned())};
+
+    assert_eq!(symbol.to_json().pointer("/cache"), None);
+
+    let read = Symbol::from_json(&parse(r#"{"name": "main", "vaddr": 1, "cache": "y"}"#)).unwrap();
+
+    assert_eq!(read.cache, None);
+}
+
+#[test]
+fn missing_required_key_is_an_error()
+{
+    let err = Symbol::from_json(&parse(r#"{"name": "main"}"#)).unwrap_err();
+
+    assert_eq!(err, FromJsonError::Missing("/vaddr".to_owned()));
+}
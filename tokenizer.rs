@@ -0,0 +1,326 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet
+};
+
+use crate::parser::{self, ObjectValue, DuplicateKeys, ParseError, TextIter};
+
+
+// unlike the lexer these are checked against the grammar, anything out of
+// place is an error instead of a token
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a>
+{
+    BeginObject,
+    EndObject,
+    BeginList,
+    EndList,
+    Key(Cow<'a, str>),
+    Text(Cow<'a, str>),
+    Number(i64),
+    Float(f64),
+    Bool(bool),
+    Null
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container
+{
+    List,
+    Object
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect
+{
+    Value,
+    // right after [ or {, where the container can also just end
+    FirstElement,
+    FirstKey,
+    // whatever comes after a value, depends on what its inside of
+    Next,
+    Done
+}
+
+// pull parser, the caller drives it a token at a time and can skip whatever it doesnt need
+pub struct Tokenizer<'a>
+{
+    text: TextIter<'a>,
+    stack: Vec<Container>,
+    // keys of every open object, only filled when duplicates get looked for
+    keys: Vec<HashSet<Cow<'a, str>>>,
+    expect: Expect
+}
+
+impl<'a> Tokenizer<'a>
+{
+    pub(crate) fn new(text: TextIter<'a>) -> Self
+    {
+        Self{text, stack: Vec::new(), keys: Vec::new(), expect: Expect::Value}
+    }
+
+    // how many lists and objects the next token is inside of
+    pub fn depth(&self) -> usize
+    {
+        self.stack.len()
+    }
+
+    // skips the next value with everything inside it, a key gets skipped together with its value
+    // and at the end of a list or object its closing token is what gets skipped
+    pub fn skip_value(&mut self) -> Result<(), ParseError>
+    {
+        let depth = self.stack.len();
+
+        while let Some(token) = self.next()
+        {
+            let token = token?;
+
+            if matches!(token, Token::Key(_)) && self.stack.len() == depth
+            {
+                continue;
+            }
+
+            if self.stack.len() <= depth
+            {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Token<'a>, ParseError>
+    {
+        self.text.check_interrupt()?;
+
+        self.expect = Expect::Next;
+
+        let token = match self.text.skip_whitespace().peek().copied()
+        {
+            Some('[') =>
+            {
+                self.text.enter()?;
+                self.text.next();
+
+                self.stack.push(Container::List);
+                self.expect = Expect::FirstElement;
+
+                Token::BeginList
+            },
+            Some('{') =>
+            {
+                self.text.enter()?;
+                self.text.next();
+
+                self.stack.push(Container::Object);
+                self.keys.push(HashSet::new());
+                self.expect = Expect::FirstKey;
+
+                Token::BeginObject
+            },
+            Some(c) if self.text.options().is_quote(c) => Token::Text(parser::parse_text_borrowed(&mut self.text)?),
+            Some(_) =>
+            {
+                match ObjectValue::parse(&mut self.text)?
+                {
+                    ObjectValue::Null => Token::Null,
+                    ObjectValue::Number(x) => Token::Number(x),
+                    ObjectValue::Float(x) => Token::Float(x),
+                    ObjectValue::Bool(x) => Token::Bool(x),
+                    _ => return Err(self.text.internal_error("containers and strings are handled above"))
+                }
+            },
+            None => return Err(self.text.unexpected("a value"))
+        };
+
+        Ok(token)
+    }
+
+    fn key(&mut self) -> Result<Token<'a>, ParseError>
+    {
+        let start = self.text.skip_whitespace().offset();
+        let key = parser::parse_key(&mut self.text)?;
+
+        parser::verify_char(&mut self.text, ':')?;
+
+        self.expect = Expect::Value;
+
+        let duplicates = self.text.options().duplicate_keys;
+
+        let duplicate = match self.keys.last_mut()
+        {
+            Some(keys) if duplicates != DuplicateKeys::Keep => !keys.insert(key.clone()),
+            _ => false
+        };
+
+        if !duplicate
+        {
+            return Ok(Token::Key(key));
+        }
+
+        if duplicates != DuplicateKeys::FirstWins
+        {
+            return Err(parser::streamed_duplicate(&self.text, start, &key));
+        }
+
+        // the caller never sees the key or its value, just whatever comes after them
+        self.skip_value()?;
+
+        match self.next_token()?
+        {
+            Some(token) => Ok(token),
+            None => Err(self.text.internal_error("the tokens ended inside an object"))
+        }
+    }
+
+    fn end(&mut self, token: Token<'a>) -> Token<'a>
+    {
+        self.text.next();
+
+        if self.stack.pop() == Some(Container::Object)
+        {
+            self.keys.pop();
+        }
+
+        self.text.leave();
+        self.expect = Expect::Next;
+
+        token
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError>
+    {
+        let token = match self.expect
+        {
+            Expect::Done => return Ok(None),
+            Expect::Value => self.value()?,
+            Expect::FirstElement =>
+            {
+                if self.text.skip_whitespace().peek() == Some(&']')
+                {
+                    self.end(Token::EndList)
+                } else
+                {
+                    self.value()?
+                }
+            },
+            Expect::FirstKey =>
+            {
+                if self.text.skip_whitespace().peek() == Some(&'}')
+                {
+                    self.end(Token::EndObject)
+                } else
+                {
+                    self.key()?
+                }
+            },
+            Expect::Next =>
+            {
+                let closing = match self.stack.last()
+                {
+                    Some(Container::List) => (']', Token::EndList),
+                    Some(Container::Object) => ('}', Token::EndObject),
+                    None =>
+                    {
+                        self.expect = Expect::Done;
+                        self.text.finish()?;
+
+                        return Ok(None);
+                    }
+                };
+
+                if self.text.skip_whitespace().peek() == Some(&closing.0)
+                {
+                    self.end(closing.1)
+                } else
+                {
+                    parser::verify_char(&mut self.text, ',')?;
+
+                    if self.text.trailing_comma(closing.0)
+                    {
+                        return Ok(Some(self.end(closing.1)));
+                    }
+
+                    match self.stack.last()
+                    {
+                        Some(Container::Object) => self.key()?,
+                        _ => self.value()?
+                    }
+                }
+            }
+        };
+
+        Ok(Some(token))
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a>
+{
+    type Item = Result<Token<'a>, ParseError>;
+
+    // nothing comes after an error
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.next_token().inspect_err(|_| self.expect = Expect::Done).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::parser::{Parser, ParserOptions};
+
+    use super::*;
+
+
+    fn tokens(text: &str, duplicate_keys: DuplicateKeys) -> Result<Vec<Token<'_>>, usize>
+    {
+        Parser::new(text.chars())
+            .options(ParserOptions::default().duplicate_keys(duplicate_keys))
+            .tokenize()
+            .collect::<Result<_, _>>()
+            .map_err(|err| err.offset)
+    }
+
+    #[test]
+    fn skip_value()
+    {
+        let mut tokenizer = Parser::new(r#"[{"a": [1, {}]}, 2]"#.chars()).tokenize();
+
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BeginList)));
+
+        tokenizer.skip_value().unwrap();
+
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(2))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::EndList)));
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    fn duplicate_keys()
+    {
+        let text = r#"{"a": 1, "b": {"a": 2}, "a": [3], "c": null}"#;
+
+        assert_eq!(tokens(text, DuplicateKeys::Keep).map(|tokens| tokens.len()), Ok(15));
+
+        assert_eq!(tokens(text, DuplicateKeys::FirstWins), Ok(vec![
+            Token::BeginObject,
+            Token::Key("a".into()),
+            Token::Number(1),
+            Token::Key("b".into()),
+            Token::BeginObject,
+            Token::Key("a".into()),
+            Token::Number(2),
+            Token::EndObject,
+            Token::Key("c".into()),
+            Token::Null,
+            Token::EndObject
+        ]));
+
+        assert_eq!(tokens(text, DuplicateKeys::Error), Err(24));
+        assert_eq!(tokens(text, DuplicateKeys::LastWins), Err(24));
+
+        // the last one can be skipped too
+        assert_eq!(tokens(r#"{"a": 1, "a": 2}"#, DuplicateKeys::FirstWins).map(|tokens| tokens.len()), Ok(4));
+    }
+}
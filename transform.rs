@@ -0,0 +1,309 @@
+use std::{
+    fmt,
+    sync::Arc,
+    collections::HashMap
+};
+
+use crate::parser::ObjectValue;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case
+{
+    Snake,
+    Camel,
+    Pascal,
+    Kebab
+}
+
+impl Case
+{
+    pub fn from_name(name: &str) -> Option<Self>
+    {
+        let case = match name
+        {
+            "snake" | "snake_case" => Self::Snake,
+            "camel" | "camelCase" => Self::Camel,
+            "pascal" | "PascalCase" => Self::Pascal,
+            "kebab" | "kebab-case" => Self::Kebab,
+            _ => return None
+        };
+
+        Some(case)
+    }
+
+    // splits on _ - and spaces and at lower to upper changes, HTTPServer is http and server
+    fn words(key: &str) -> Vec<String>
+    {
+        let chars: Vec<char> = key.chars().collect();
+
+        let mut words = Vec::new();
+        let mut current = String::new();
+
+        for (id, c) in chars.iter().copied().enumerate()
+        {
+            if matches!(c, '_' | '-' | ' ')
+            {
+                if !current.is_empty()
+                {
+                    words.push(std::mem::take(&mut current));
+                }
+
+                continue;
+            }
+
+            let boundary = !current.is_empty() && c.is_uppercase() && {
+                let previous = chars[id - 1];
+                let next_lower = chars.get(id + 1).map(|c| c.is_lowercase()).unwrap_or(false);
+
+                previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_lower)
+            };
+
+            if boundary
+            {
+                words.push(std::mem::take(&mut current));
+            }
+
+            current.push(c);
+        }
+
+        if !current.is_empty()
+        {
+            words.push(current);
+        }
+
+        words
+    }
+
+    fn capitalized(word: &str) -> String
+    {
+        let mut chars = word.chars();
+
+        chars.next().map(|first| first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect())
+            .unwrap_or_default()
+    }
+
+    // leading underscores like in _id are kept as they are
+    pub fn convert(self, key: &str) -> String
+    {
+        let rest = key.trim_start_matches('_');
+        let prefix = &key[..key.len() - rest.len()];
+
+        let words = Self::words(rest);
+        if words.is_empty()
+        {
+            return key.to_owned();
+        }
+
+        let converted = match self
+        {
+            Self::Snake => words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join("_"),
+            Self::Kebab => words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join("-"),
+            Self::Pascal => words.iter().map(|word| Self::capitalized(word)).collect(),
+            Self::Camel =>
+            {
+                words.iter().enumerate().map(|(id, word)|
+                {
+                    if id == 0 { word.to_lowercase() } else { Self::capitalized(word) }
+                }).collect()
+            }
+        };
+
+        format!("{prefix}{converted}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum KeyRename
+{
+    // keys missing from the map stay the same
+    Map(HashMap<String, String>),
+    Case(Case)
+}
+
+impl KeyRename
+{
+    fn rename(&self, key: &str) -> Option<String>
+    {
+        match self
+        {
+            Self::Map(map) => map.get(key).cloned(),
+            Self::Case(case) => Some(case.convert(key))
+        }
+    }
+}
+
+// two different keys in the same object would end up as the same one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameCollision
+{
+    pub first: String,
+    pub second: String,
+    pub renamed: String
+}
+
+impl fmt::Display for RenameCollision
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{:?} and {:?} would both be renamed to {:?}", self.first, self.second, self.renamed)
+    }
+}
+
+impl std::error::Error for RenameCollision {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOptions
+{
+    pub nulls: bool,
+    pub empty_objects: bool,
+    pub empty_lists: bool
+}
+
+impl Default for PruneOptions
+{
+    fn default() -> Self
+    {
+        Self{nulls: true, empty_objects: true, empty_lists: true}
+    }
+}
+
+impl PruneOptions
+{
+    fn removes(&self, value: &ObjectValue) -> bool
+    {
+        match value
+        {
+            ObjectValue::Null => self.nulls,
+            ObjectValue::List(list) => self.empty_lists && list.is_empty(),
+            ObjectValue::Object(object) => self.empty_objects && object.fields().is_empty(),
+            _ => false
+        }
+    }
+}
+
+impl ObjectValue
+{
+    // removes nulls and empty containers from objects and lists, children go first so a
+    // container left empty by pruning goes too, returns how many values got removed
+    pub fn prune(&mut self, options: &PruneOptions) -> usize
+    {
+        let needs_pruning = |value: &ObjectValue|
+        {
+            match value
+            {
+                ObjectValue::List(list) => !list.is_empty(),
+                ObjectValue::Object(object) => !object.fields().is_empty(),
+                _ => false
+            }
+        };
+
+        if !needs_pruning(self)
+        {
+            return 0;
+        }
+
+        let mut removed = 0;
+        match self
+        {
+            ObjectValue::List(list) =>
+            {
+                let values = Arc::make_mut(list);
+                values.iter_mut().for_each(|value| removed += value.prune(options));
+
+                if values.iter().any(|value| options.removes(value))
+                {
+                    let kept: Vec<ObjectValue> = values.iter().filter(|value| !options.removes(value)).cloned().collect();
+
+                    removed += values.len() - kept.len();
+
+                    *list = kept.into();
+                }
+            },
+            ObjectValue::Object(object) =>
+            {
+                let object = Arc::make_mut(object);
+                object.fields_mut().iter_mut().for_each(|field| removed += field.value_mut().prune(options));
+
+                let length = object.fields().len();
+                object.retain_fields(|field| !options.removes(field.value()));
+
+                removed += length - object.fields().len();
+            },
+            _ => ()
+        }
+
+        removed
+    }
+
+    // renames keys of every object in the tree, if that would merge two keys into one
+    // nothing gets renamed, keys that were already duplicates before are fine
+    pub fn rename_keys(&mut self, rename: &KeyRename) -> Result<(), RenameCollision>
+    {
+        match self.rename_collision(rename)
+        {
+            Some(collision) => Err(collision),
+            None =>
+            {
+                self.rename_all_keys(rename);
+
+                Ok(())
+            }
+        }
+    }
+
+    fn rename_collision(&self, rename: &KeyRename) -> Option<RenameCollision>
+    {
+        match self
+        {
+            ObjectValue::List(list) => list.iter().find_map(|value| value.rename_collision(rename)),
+            ObjectValue::Object(object) =>
+            {
+                // renamed key to the original one it came from
+                let mut renamed: HashMap<String, &str> = HashMap::new();
+
+                let collision = object.fields().iter().find_map(|field|
+                {
+                    let key = rename.rename(field.key()).unwrap_or_else(|| field.key().to_owned());
+
+                    match renamed.get(&key)
+                    {
+                        Some(first) if *first != field.key() =>
+                        {
+                            Some(RenameCollision{first: first.to_string(), second: field.key().to_owned(), renamed: key})
+                        },
+                        Some(_) => None,
+                        None =>
+                        {
+                            renamed.insert(key, field.key());
+
+                            None
+                        }
+                    }
+                });
+
+                collision.or_else(|| object.fields().iter().find_map(|field| field.value().rename_collision(rename)))
+            },
+            _ => None
+        }
+    }
+
+    fn rename_all_keys(&mut self, rename: &KeyRename)
+    {
+        match self
+        {
+            ObjectValue::List(list) =>
+            {
+                Arc::make_mut(list).iter_mut().for_each(|value| value.rename_all_keys(rename));
+            },
+            ObjectValue::Object(object) =>
+            {
+                let object = Arc::make_mut(object);
+
+                object.rename_fields(|key| rename.rename(key));
+                object.fields_mut().iter_mut().for_each(|field| field.value_mut().rename_all_keys(rename));
+            },
+            _ => ()
+        }
+    }
+}
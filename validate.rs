@@ -0,0 +1,253 @@
+use std::{
+    fmt,
+    borrow::Cow,
+    ops::Range,
+    collections::HashMap
+};
+
+use crate::{
+    parser::{self, Parser, ParserOptions, DuplicateKeys, ParseError},
+    events::Handler,
+    lexer::{Lexer, Token, TokenKind, Position}
+};
+
+
+// doubles (and so javascript) cant represent every integer above this
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity
+{
+    Error,
+    Warning
+}
+
+impl fmt::Display for Severity
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic
+{
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub span: Range<usize>
+}
+
+impl fmt::Display for Diagnostic
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at {}..{}", self.message, self.span.start, self.span.end)
+    }
+}
+
+fn suggest_token(token: &Token) -> Option<String>
+{
+    match token.kind
+    {
+        TokenKind::Text if token.text.starts_with('\'') =>
+        {
+            Some("replace the single quotes with '\"'".to_owned())
+        },
+        TokenKind::Text if !token.text.ends_with('"') || token.text.len() == 1 =>
+        {
+            Some("add the closing '\"'".to_owned())
+        },
+        TokenKind::Literal if ["true", "false", "null"].iter().any(|literal| token.text.eq_ignore_ascii_case(literal)) =>
+        {
+            Some(format!("write it as {}", token.text.to_ascii_lowercase()))
+        },
+        TokenKind::Number if token.text.len() > 1 && token.text.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            Some("remove the leading zeros".to_owned()).filter(|_| token.text.starts_with('0'))
+        },
+        _ => None
+    }
+}
+
+// the token the parser stopped at so theres something to underline and maybe a fix for it,
+// just the character if theres no token starting right there (like inside a string)
+fn diagnostic(text: &str, err: &ParseError) -> Diagnostic
+{
+    let token = text.get(err.offset..)
+        .and_then(|rest| Lexer::new(rest).next())
+        .filter(|token| token.span.start == 0);
+
+    let span = match &token
+    {
+        Some(token) => err.offset..err.offset + token.span.end,
+        None => err.offset..err.offset + err.found.map(char::len_utf8).unwrap_or(0)
+    };
+
+    let message = match &err.expected
+    {
+        Some(expected) => format!("{}, expected {expected}", err.message),
+        None => err.message.clone()
+    };
+
+    Diagnostic{
+        severity: Severity::Error,
+        message,
+        suggestion: token.as_ref().and_then(suggest_token),
+        span
+    }
+}
+
+// goes through the value only to check it
+struct Skip;
+
+impl Handler for Skip {}
+
+// the parser without building any values, so its exactly what parse would accept, nothing
+// is kept so it doesnt matter which duplicate wins but events would refuse LastWins
+pub fn validate(text: &str, options: &ParserOptions) -> Result<(), Diagnostic>
+{
+    let mut options = options.clone();
+
+    if options.duplicate_keys == DuplicateKeys::LastWins
+    {
+        options.duplicate_keys = DuplicateKeys::FirstWins;
+    }
+
+    Parser::new(text.chars()).options(options).parse_events(&mut Skip).map_err(|err| diagnostic(text, &err))
+}
+
+// things that parse fine but probably arent what was meant, the lexer is enough for these
+// so they come out even for broken documents
+fn warnings(text: &str) -> Vec<Diagnostic>
+{
+    let mut diagnostics = Vec::new();
+
+    // keys seen so far in every open container, none for lists
+    let mut containers: Vec<Option<HashMap<Cow<str>, Range<usize>>>> = Vec::new();
+
+    let mut tokens = Lexer::new(text).filter(|token| token.kind != TokenKind::Comment).peekable();
+
+    while let Some(token) = tokens.next()
+    {
+        match token.kind
+        {
+            TokenKind::BeginObject => containers.push(Some(HashMap::new())),
+            TokenKind::BeginList => containers.push(None),
+            TokenKind::EndObject | TokenKind::EndList =>
+            {
+                containers.pop();
+            },
+            TokenKind::Number =>
+            {
+                if let Some(number) = token.text.parse::<i128>().ok().filter(|x| x.unsigned_abs() > u128::from(MAX_SAFE_INTEGER))
+                {
+                    diagnostics.push(Diagnostic{
+                        severity: Severity::Warning,
+                        message: format!("{number} is too large to be represented exactly by most json implementations"),
+                        suggestion: Some("store it as a string".to_owned()),
+                        span: token.span.clone()
+                    });
+                }
+            },
+            _ => ()
+        }
+
+        let is_key = matches!(token.kind, TokenKind::Text | TokenKind::Literal)
+            && tokens.peek().map(|next| next.kind == TokenKind::Colon).unwrap_or(false);
+
+        let keys = match containers.last_mut()
+        {
+            Some(Some(keys)) if is_key => keys,
+            _ => continue
+        };
+
+        // "a" and "\u0061" are the same key, identifiers dont have escapes and a broken string
+        // is left as it is
+        let key = match token.kind
+        {
+            TokenKind::Text => parser::decode_key(token.text).unwrap_or(Cow::Borrowed(token.text)),
+            _ => Cow::Borrowed(token.text)
+        };
+
+        if let Some(previous) = keys.insert(key, token.span.clone())
+        {
+            let position = Position::from_offset(text, previous.start);
+
+            diagnostics.push(Diagnostic{
+                severity: Severity::Warning,
+                message: format!("duplicate key {} (first defined at {position})", token.text),
+                suggestion: Some("remove or rename one of them".to_owned()),
+                span: token.span
+            });
+        }
+    }
+
+    diagnostics
+}
+
+// every syntax error the parser can get past plus the warnings, in the order theyre in the text
+pub fn lint(text: &str, options: &ParserOptions) -> Vec<Diagnostic>
+{
+    let mut diagnostics: Vec<Diagnostic> = match Parser::new(text.chars()).options(options.clone()).parse_recovering()
+    {
+        Ok(recovered) => recovered.errors.iter().map(|err| diagnostic(text, err)).collect(),
+        Err(err) => vec![diagnostic(text, &err)]
+    };
+
+    diagnostics.extend(warnings(text));
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::Dialect;
+
+    use super::*;
+
+
+    fn duplicates(text: &str) -> Vec<Range<usize>>
+    {
+        lint(text, &ParserOptions::default().dialect(Dialect::Json5)).into_iter()
+            .filter(|diagnostic| diagnostic.message.starts_with("duplicate key"))
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_keys_are_compared_decoded()
+    {
+        assert_eq!(duplicates(r#"{"a": 1, "\u0061": 2}"#), vec![9..17]);
+        assert_eq!(duplicates(r#"{'a': 1, a: 2, "a": 3}"#), [9..10, 15..18]);
+    }
+
+    #[test]
+    fn same_key_in_different_objects()
+    {
+        assert!(duplicates(r#"{"a": 1, "b": {"a": 2}, "c": [{"a": 3}]}"#).is_empty());
+        assert!(duplicates(r#"{"a": 1, "b": 2}"#).is_empty());
+    }
+
+    #[test]
+    fn validate_accepts_what_parse_does()
+    {
+        let text = r#"{"a": 1, "a": 2}"#;
+
+        for duplicate_keys in [DuplicateKeys::Keep, DuplicateKeys::FirstWins, DuplicateKeys::LastWins, DuplicateKeys::Error]
+        {
+            let options = ParserOptions::default().duplicate_keys(duplicate_keys);
+
+            let parsed = Parser::new(text.chars()).options(options.clone()).parse();
+
+            assert_eq!(validate(text, &options).is_ok(), parsed.is_ok(), "{duplicate_keys:?}");
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::parser::{self, Parser, ParserOptions, ObjectValue};
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence
+{
+    OnlyOursAccepted,
+    OnlyReferenceAccepted,
+    Value{pointer: String, ours: String, reference: String}
+}
+
+impl fmt::Display for Divergence
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Self::OnlyOursAccepted => write!(f, "accepted by cringejson but rejected by the reference"),
+            Self::OnlyReferenceAccepted => write!(f, "accepted by the reference but rejected by cringejson"),
+            Self::Value{pointer, ours, reference} =>
+            {
+                write!(f, "value at \"{pointer}\" differs, cringejson: {ours}, reference: {reference}")
+            }
+        }
+    }
+}
+
+pub fn verify(text: &str) -> Result<(), Divergence>
+{
+    let ours = Parser::new(text.chars()).options(ParserOptions::strict()).parse().ok();
+    let reference = serde_json::from_str::<Value>(text).ok();
+
+    match (ours, reference)
+    {
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(Divergence::OnlyOursAccepted),
+        (None, Some(_)) => Err(Divergence::OnlyReferenceAccepted),
+        (Some(ours), Some(reference)) => compare(&ours, &reference, &mut String::new())
+    }
+}
+
+fn compare(ours: &ObjectValue, reference: &Value, pointer: &mut String) -> Result<(), Divergence>
+{
+    let same = match (ours, reference)
+    {
+        (ObjectValue::Null, Value::Null) => true,
+        (ObjectValue::Text(a), Value::String(b)) => **a == **b,
+        (ObjectValue::Number(a), Value::Number(b)) => b.as_i64() == Some(*a),
+        (ObjectValue::Float(a), Value::Number(b)) => b.as_f64() == Some(*a),
+        (ObjectValue::Bool(a), Value::Bool(b)) => a == b,
+        (ObjectValue::List(a), Value::Array(b)) if a.len() == b.len() =>
+        {
+            return a.iter().zip(b.iter()).enumerate().try_for_each(|(id, (a, b))|
+            {
+                compare_child(a, b, pointer, &id.to_string())
+            });
+        },
+        (ObjectValue::Object(a), Value::Object(b)) =>
+        {
+            let mut keys: Vec<&str> = a.fields().iter().map(|field| field.key()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+
+            if keys.len() == b.len()
+            {
+                // lookups here return the first duplicate while the reference keeps the last
+                return b.iter().try_for_each(|(key, b)|
+                {
+                    match a.get(key)
+                    {
+                        Some(value) => compare_child(value, b, pointer, key),
+                        None => Err(mismatch(ours, reference, pointer))
+                    }
+                });
+            }
+
+            false
+        },
+        _ => false
+    };
+
+    if same
+    {
+        Ok(())
+    } else
+    {
+        Err(mismatch(ours, reference, pointer))
+    }
+}
+
+fn compare_child(
+    ours: &ObjectValue,
+    reference: &Value,
+    pointer: &mut String,
+    token: &str
+) -> Result<(), Divergence>
+{
+    let length = pointer.len();
+
+    pointer.push('/');
+    pointer.push_str(&parser::escape_pointer_token(token));
+
+    let result = compare(ours, reference, pointer);
+
+    pointer.truncate(length);
+
+    result
+}
+
+fn mismatch(ours: &ObjectValue, reference: &Value, pointer: &str) -> Divergence
+{
+    Divergence::Value{
+        pointer: pointer.to_owned(),
+        ours: format!("{ours:?}"),
+        reference: reference.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+
+    #[test]
+    fn agrees()
+    {
+        assert_eq!(verify(r#"{"a": [1, 2.5, "xA", null, true], "b": {}}"#), Ok(()));
+        assert_eq!(verify("[1,"), Ok(()));
+        assert_eq!(verify("[01]"), Ok(()));
+    }
+
+    #[test]
+    fn pointer_to_the_difference()
+    {
+        let err = |pointer: &str, ours: &str, reference: &str|
+        {
+            Err(Divergence::Value{pointer: pointer.to_owned(), ours: ours.to_owned(), reference: reference.to_owned()})
+        };
+
+        // the reference keeps the last duplicate
+        assert_eq!(verify(r#"{"b": {"a": 1, "a": 2}}"#), err("/b/a", "Number(1)", "2"));
+
+        // and reads negative zero as a float
+        assert_eq!(verify("[[], [-0]]"), err("/1/0", "Number(0)", "-0.0"));
+    }
+}
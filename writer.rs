@@ -0,0 +1,774 @@
+use std::{
+    io,
+    fmt::{self, Write as _}
+};
+
+use crate::{
+    parser::{self, ObjectValue},
+    borrowed::BorrowedValue
+};
+
+
+pub fn write_text(out: &mut String, text: &str)
+{
+    out.push('"');
+
+    text.chars().for_each(|c|
+    {
+        match c
+        {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 =>
+            {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c)
+        }
+    });
+
+    out.push('"');
+}
+
+pub fn write_compact(out: &mut String, value: &ObjectValue)
+{
+    write_compact_with(out, value, &FloatFormat::default());
+}
+
+pub fn write_compact_with(out: &mut String, value: &ObjectValue, floats: &FloatFormat)
+{
+    match value
+    {
+        ObjectValue::Null => out.push_str("null"),
+        ObjectValue::Text(x) => write_text(out, x),
+        ObjectValue::Number(x) => out.push_str(&x.to_string()),
+        ObjectValue::Float(x) => write_float(out, *x, floats),
+        ObjectValue::Bool(x) => out.push_str(if *x { "true" } else { "false" }),
+        ObjectValue::List(values) =>
+        {
+            out.push('[');
+            values.iter().enumerate().for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_compact_with(out, value, floats);
+            });
+            out.push(']');
+        },
+        ObjectValue::Object(object) =>
+        {
+            out.push('{');
+            object.fields().iter().enumerate().for_each(|(id, field)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_text(out, field.key());
+                out.push(':');
+                write_compact_with(out, field.value(), floats);
+            });
+            out.push('}');
+        }
+    }
+}
+
+pub fn to_compact(value: &ObjectValue) -> String
+{
+    let mut out = String::new();
+    write_compact(&mut out, value);
+
+    out
+}
+
+// same as write_compact, raw values go in exactly like they were in the source
+pub fn write_borrowed(out: &mut String, value: &BorrowedValue)
+{
+    match value
+    {
+        BorrowedValue::Null => out.push_str("null"),
+        BorrowedValue::Text(x) => write_text(out, x),
+        BorrowedValue::Number(x) => out.push_str(&x.to_string()),
+        BorrowedValue::Float(x) => write_float(out, *x, &FloatFormat::default()),
+        BorrowedValue::Bool(x) => out.push_str(if *x { "true" } else { "false" }),
+        BorrowedValue::List(values) =>
+        {
+            out.push('[');
+            values.iter().enumerate().for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_borrowed(out, value);
+            });
+            out.push(']');
+        },
+        BorrowedValue::Object(fields) =>
+        {
+            out.push('{');
+            fields.iter().enumerate().for_each(|(id, (key, value))|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_text(out, key);
+                out.push(':');
+                write_borrowed(out, value);
+            });
+            out.push('}');
+        },
+        BorrowedValue::Raw(raw) => out.push_str(raw.get())
+    }
+}
+
+// rfc 8785 escapes the short forms and lowercase \u00xx for the other control characters, nothing else
+fn write_canonical_text(out: &mut String, text: &str)
+{
+    out.push('"');
+
+    text.chars().for_each(|c|
+    {
+        match c
+        {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 =>
+            {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c)
+        }
+    });
+
+    out.push('"');
+}
+
+// javascripts Number.prototype.toString, every number is a double here so big integers round,
+// x has to be finite
+fn write_canonical_number(out: &mut String, x: f64)
+{
+    if x == 0.0
+    {
+        out.push('0');
+
+        return;
+    }
+
+    if x < 0.0
+    {
+        out.push('-');
+    }
+
+    // shortest digits that read back to the same double, like 1.2345e3
+    let scientific = format!("{:e}", x.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+
+    // the point goes after this many digits
+    let point = exponent + 1;
+    let length = digits.len() as i32;
+
+    if length <= point && point <= 21
+    {
+        out.push_str(&digits);
+        (0..point - length).for_each(|_| out.push('0'));
+    } else if 0 < point && point <= 21
+    {
+        let (whole, fraction) = digits.split_at(point as usize);
+
+        out.push_str(whole);
+        out.push('.');
+        out.push_str(fraction);
+    } else if -6 < point && point <= 0
+    {
+        out.push_str("0.");
+        (0..-point).for_each(|_| out.push('0'));
+        out.push_str(&digits);
+    } else
+    {
+        let (first, rest) = digits.split_at(1);
+
+        out.push_str(first);
+
+        if !rest.is_empty()
+        {
+            out.push('.');
+            out.push_str(rest);
+        }
+
+        let sign = if exponent < 0 { '-' } else { '+' };
+        let _ = write!(out, "e{sign}{}", exponent.abs());
+    }
+}
+
+// keys are ordered by their utf16 code units, not by bytes
+fn utf16_order(a: &str, b: &str) -> std::cmp::Ordering
+{
+    a.encode_utf16().cmp(b.encode_utf16())
+}
+
+// rfc 8785 only has finite numbers, pointer is where the nan or infinity was
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalError
+{
+    pub pointer: String,
+    pub value: f64
+}
+
+impl CanonicalError
+{
+    fn within(mut self, token: &str) -> Self
+    {
+        self.pointer = format!("/{}{}", parser::escape_pointer_token(token), self.pointer);
+
+        self
+    }
+}
+
+impl fmt::Display for CanonicalError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at {:?} has no canonical form, only finite numbers do", self.value, self.pointer)
+    }
+}
+
+impl std::error::Error for CanonicalError {}
+
+// out is left with whatever got written before the error
+pub fn write_canonical(out: &mut String, value: &ObjectValue) -> Result<(), CanonicalError>
+{
+    match value
+    {
+        ObjectValue::Null => out.push_str("null"),
+        ObjectValue::Text(x) => write_canonical_text(out, x),
+        ObjectValue::Number(x) => write_canonical_number(out, *x as f64),
+        ObjectValue::Float(x) if !x.is_finite() => return Err(CanonicalError{pointer: String::new(), value: *x}),
+        ObjectValue::Float(x) => write_canonical_number(out, *x),
+        ObjectValue::Bool(x) => out.push_str(if *x { "true" } else { "false" }),
+        ObjectValue::List(values) =>
+        {
+            out.push('[');
+            values.iter().enumerate().try_for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_canonical(out, value).map_err(|err| err.within(&id.to_string()))
+            })?;
+            out.push(']');
+        },
+        ObjectValue::Object(object) =>
+        {
+            let mut fields: Vec<_> = object.fields().iter().collect();
+            fields.sort_by(|a, b| utf16_order(a.key(), b.key()));
+
+            out.push('{');
+            fields.into_iter().enumerate().try_for_each(|(id, field)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                write_canonical_text(out, field.key());
+                out.push(':');
+                write_canonical(out, field.value()).map_err(|err| err.within(field.key()))
+            })?;
+            out.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+// rfc 8785 json canonicalization, the same value always gives the same bytes for hashing and signing
+pub fn to_canonical(value: &ObjectValue) -> Result<String, CanonicalError>
+{
+    let mut out = String::new();
+    write_canonical(&mut out, value)?;
+
+    Ok(out)
+}
+
+// output goes out in chunks of about this many bytes
+const STREAM_CHUNK_SIZE: usize = 8 * 1024;
+
+struct ChunkWriter<'a, W>
+{
+    writer: &'a mut W,
+    buffer: String
+}
+
+impl<W: io::Write> ChunkWriter<'_, W>
+{
+    fn flush_full(&mut self) -> io::Result<()>
+    {
+        if self.buffer.len() >= STREAM_CHUNK_SIZE
+        {
+            self.writer.write_all(self.buffer.as_bytes())?;
+            self.buffer.clear();
+        }
+
+        Ok(())
+    }
+}
+
+fn stream_compact(chunks: &mut ChunkWriter<impl io::Write>, value: &ObjectValue) -> io::Result<()>
+{
+    match value
+    {
+        ObjectValue::List(values) =>
+        {
+            chunks.buffer.push('[');
+            for (id, value) in values.iter().enumerate()
+            {
+                if id != 0
+                {
+                    chunks.buffer.push(',');
+                }
+
+                stream_compact(chunks, value)?;
+            }
+            chunks.buffer.push(']');
+        },
+        ObjectValue::Object(object) =>
+        {
+            chunks.buffer.push('{');
+            for (id, field) in object.fields().iter().enumerate()
+            {
+                if id != 0
+                {
+                    chunks.buffer.push(',');
+                }
+
+                write_text(&mut chunks.buffer, field.key());
+                chunks.buffer.push(':');
+
+                stream_compact(chunks, field.value())?;
+            }
+            chunks.buffer.push('}');
+        },
+        x => write_compact(&mut chunks.buffer, x)
+    }
+
+    chunks.flush_full()
+}
+
+// compact json, {:#} gives the pretty form with the default options
+impl fmt::Display for ObjectValue
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let text = if f.alternate() { to_pretty(self, &WriteOptions::default()) } else { to_compact(self) };
+
+        f.write_str(&text)
+    }
+}
+
+impl fmt::Display for BorrowedValue<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let mut text = String::new();
+        write_borrowed(&mut text, self);
+
+        f.write_str(&text)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatFormat
+{
+    // digits after the point, none means the shortest text that parses back to the same float
+    pub precision: Option<usize>,
+    // magnitudes at or above 10^exponent_above or below 10^exponent_below get an exponent
+    pub exponent_above: i32,
+    pub exponent_below: i32
+}
+
+impl Default for FloatFormat
+{
+    // same thresholds as javascript
+    fn default() -> Self
+    {
+        Self{precision: None, exponent_above: 21, exponent_below: -6}
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WriteOptions
+{
+    pub indent: String,
+    // containers that fit before this column stay on one line
+    pub width: Option<usize>,
+    pub floats: FloatFormat
+}
+
+impl Default for WriteOptions
+{
+    fn default() -> Self
+    {
+        Self{indent: "  ".to_owned(), width: None, floats: FloatFormat::default()}
+    }
+}
+
+// json has no nan or infinity so those become null, whole floats keep a .0 so they read back as floats
+pub fn write_float(out: &mut String, x: f64, format: &FloatFormat)
+{
+    if !x.is_finite()
+    {
+        out.push_str("null");
+
+        return;
+    }
+
+    let magnitude = x.abs();
+    let exponent = magnitude != 0.0 && (magnitude >= 10.0_f64.powi(format.exponent_above)
+        || magnitude < 10.0_f64.powi(format.exponent_below));
+
+    let text = match (exponent, format.precision)
+    {
+        (true, Some(precision)) => format!("{x:.precision$e}"),
+        (true, None) => format!("{x:e}"),
+        (false, Some(precision)) => format!("{x:.precision$}"),
+        (false, None) => x.to_string()
+    };
+
+    out.push_str(&text);
+
+    if !text.contains(['.', 'e'])
+    {
+        out.push_str(".0");
+    }
+}
+
+fn column(out: &str) -> usize
+{
+    let line_start = out.rfind('\n').map(|id| id + 1).unwrap_or(0);
+
+    out[line_start..].chars().count()
+}
+
+// writes the spaced single line form, gives up as soon as it gets longer than the budget
+fn write_inline(out: &mut String, value: &ObjectValue, budget: usize, floats: &FloatFormat) -> bool
+{
+    let start = out.len();
+    let fits = |out: &String| out[start..].chars().count() <= budget;
+
+    match value
+    {
+        ObjectValue::List(values) =>
+        {
+            out.push('[');
+            for (id, value) in values.iter().enumerate()
+            {
+                if id != 0
+                {
+                    out.push_str(", ");
+                }
+
+                if !write_inline(out, value, budget, floats) || !fits(out)
+                {
+                    return false;
+                }
+            }
+            out.push(']');
+        },
+        ObjectValue::Object(object) =>
+        {
+            out.push('{');
+            for (id, field) in object.fields().iter().enumerate()
+            {
+                if id != 0
+                {
+                    out.push_str(", ");
+                }
+
+                write_text(out, field.key());
+                out.push_str(": ");
+
+                if !write_inline(out, field.value(), budget, floats) || !fits(out)
+                {
+                    return false;
+                }
+            }
+            out.push('}');
+        },
+        x => write_compact_with(out, x, floats)
+    }
+
+    fits(out)
+}
+
+pub fn write_pretty(out: &mut String, value: &ObjectValue, options: &WriteOptions, depth: usize)
+{
+    let newline = |out: &mut String, depth: usize|
+    {
+        out.push('\n');
+        (0..depth).for_each(|_| out.push_str(&options.indent));
+    };
+
+    let is_container = matches!(value, ObjectValue::List(_) | ObjectValue::Object(_));
+
+    if let (true, Some(width)) = (is_container, options.width)
+    {
+        let mut inline = String::new();
+        if write_inline(&mut inline, value, width.saturating_sub(column(out)), &options.floats)
+        {
+            out.push_str(&inline);
+
+            return;
+        }
+    }
+
+    match value
+    {
+        ObjectValue::List(values) if !values.is_empty() =>
+        {
+            out.push('[');
+            values.iter().enumerate().for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                newline(out, depth + 1);
+                write_pretty(out, value, options, depth + 1);
+            });
+            newline(out, depth);
+            out.push(']');
+        },
+        ObjectValue::Object(object) if !object.fields().is_empty() =>
+        {
+            out.push('{');
+            object.fields().iter().enumerate().for_each(|(id, field)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                newline(out, depth + 1);
+                write_text(out, field.key());
+                out.push_str(": ");
+                write_pretty(out, field.value(), options, depth + 1);
+            });
+            newline(out, depth);
+            out.push('}');
+        },
+        x => write_compact_with(out, x, &options.floats)
+    }
+}
+
+pub fn to_pretty(value: &ObjectValue, options: &WriteOptions) -> String
+{
+    let mut out = String::new();
+    write_pretty(&mut out, value, options, 0);
+
+    out
+}
+
+fn human_size(bytes: usize) -> String
+{
+    const KB: f64 = 1024.0;
+
+    let bytes_float = bytes as f64;
+    if bytes_float < KB
+    {
+        format!("{bytes}B")
+    } else if bytes_float < KB * KB
+    {
+        format!("{:.1}KB", bytes_float / KB)
+    } else
+    {
+        format!("{:.1}MB", bytes_float / (KB * KB))
+    }
+}
+
+fn write_preview(out: &mut String, value: &ObjectValue, limits: (usize, usize, usize), depth: usize)
+{
+    let (max_depth, max_items, max_string_len) = limits;
+
+    let plural = |amount: usize, name: &str| if amount == 1 { format!("{amount} {name}") } else { format!("{amount} {name}s") };
+
+    match value
+    {
+        ObjectValue::Text(x) if x.chars().count() > max_string_len =>
+        {
+            let cut: String = x.chars().take(max_string_len).collect();
+
+            write_text(out, &(cut + "…"));
+            out.push_str(&format!("({})", human_size(x.len())));
+        },
+        ObjectValue::List(values) if !values.is_empty() && depth >= max_depth =>
+        {
+            out.push_str(&format!("[…{}]", plural(values.len(), "item")));
+        },
+        ObjectValue::Object(object) if !object.fields().is_empty() && depth >= max_depth =>
+        {
+            out.push_str(&format!("{{…{}}}", plural(object.fields().len(), "key")));
+        },
+        ObjectValue::List(values) =>
+        {
+            out.push('[');
+            values.iter().take(max_items).enumerate().for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push_str(", ");
+                }
+
+                write_preview(out, value, limits, depth + 1);
+            });
+
+            if values.len() > max_items
+            {
+                let separator = if max_items == 0 { "" } else { ", " };
+                out.push_str(&format!("{separator}…{}", plural(values.len() - max_items, "more item")));
+            }
+            out.push(']');
+        },
+        ObjectValue::Object(object) =>
+        {
+            out.push('{');
+            object.fields().iter().take(max_items).enumerate().for_each(|(id, field)|
+            {
+                if id != 0
+                {
+                    out.push_str(", ");
+                }
+
+                write_text(out, field.key());
+                out.push_str(": ");
+                write_preview(out, field.value(), limits, depth + 1);
+            });
+
+            let fields = object.fields().len();
+            if fields > max_items
+            {
+                let separator = if max_items == 0 { "" } else { ", " };
+                out.push_str(&format!("{separator}…{}", plural(fields - max_items, "more key")));
+            }
+            out.push('}');
+        },
+        x => write_compact(out, x)
+    }
+}
+
+const SNAPSHOT_FLOATS: FloatFormat = FloatFormat{precision: None, exponent_above: 21, exponent_below: -6};
+
+// the indent and the number formatting are fixed here instead of coming from WriteOptions
+// so nothing outside this function can change what a snapshot looks like
+fn write_snapshot(out: &mut String, value: &ObjectValue, depth: usize)
+{
+    let newline = |out: &mut String, depth: usize|
+    {
+        out.push('\n');
+        (0..depth).for_each(|_| out.push_str("  "));
+    };
+
+    match value
+    {
+        ObjectValue::List(values) if !values.is_empty() =>
+        {
+            out.push('[');
+            values.iter().enumerate().for_each(|(id, value)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                newline(out, depth + 1);
+                write_snapshot(out, value, depth + 1);
+            });
+            newline(out, depth);
+            out.push(']');
+        },
+        ObjectValue::Object(object) if !object.fields().is_empty() =>
+        {
+            // stable sort so duplicate keys keep their order from the source
+            let mut fields: Vec<_> = object.fields().iter().collect();
+            fields.sort_by(|a, b| a.key().cmp(b.key()));
+
+            out.push('{');
+            fields.into_iter().enumerate().for_each(|(id, field)|
+            {
+                if id != 0
+                {
+                    out.push(',');
+                }
+
+                newline(out, depth + 1);
+                write_text(out, field.key());
+                out.push_str(": ");
+                write_snapshot(out, field.value(), depth + 1);
+            });
+            newline(out, depth);
+            out.push('}');
+        },
+        x => write_compact_with(out, x, &SNAPSHOT_FLOATS)
+    }
+}
+
+impl ObjectValue
+{
+    // same text as to_json_string but only a few kilobytes of it are in memory at once
+    pub fn write_json(&self, writer: &mut impl io::Write) -> io::Result<()>
+    {
+        let mut chunks = ChunkWriter{writer, buffer: String::with_capacity(STREAM_CHUNK_SIZE * 2)};
+
+        stream_compact(&mut chunks, self)?;
+
+        chunks.writer.write_all(chunks.buffer.as_bytes())
+    }
+
+    // parses back to the same value
+    pub fn to_json_string(&self) -> String
+    {
+        to_compact(self)
+    }
+
+    // output for golden files and snapshot tests: keys sorted by bytes, two space indents,
+    // one value per line and a trailing newline, the same value always gives the same text
+    // this format is frozen, changing anything about it needs a major version bump
+    pub fn to_snapshot_string(&self) -> String
+    {
+        let mut out = String::new();
+        write_snapshot(&mut out, self, 0);
+        out.push('\n');
+
+        out
+    }
+
+    // one line with long strings cut and deep or long containers summarized, for logs
+    pub fn preview(&self, max_depth: usize, max_items: usize, max_string_len: usize) -> String
+    {
+        let mut out = String::new();
+        write_preview(&mut out, self, (max_depth, max_items, max_string_len), 0);
+
+        out
+    }
+}